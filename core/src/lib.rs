@@ -0,0 +1,321 @@
+//! Core of the schedule analysis, which only depends on `alloc`: Kahn's topological sort of the
+//! task graph along with the longest paths leading to each task. Labels and durations are left
+//! to the caller, as long as labels can be ordered and durations can be added up without
+//! overflowing silently.
+#![cfg_attr(all(not(feature = "std"), not(test)), no_std)]
+extern crate alloc;
+
+use alloc::collections::{BTreeMap, BinaryHeap};
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+use core::fmt;
+
+/// Outcome of sorting the tasks topologically
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoreAnalysis<L, T> {
+    /// tasks in the order they complete, ties broken in favor of the task followed by fewer tasks,
+    /// then of the smallest label
+    pub topological_order: Vec<L>,
+    /// maximum number of tasks executing at the same time
+    pub max_parallelism: usize,
+    /// longest time spent, including the task's own duration, along the paths leading to a task
+    pub longest_duration_path_to_task: BTreeMap<L, T>,
+    /// task -> preceding tasks on the longest paths leading to the task
+    pub parent_tasks: BTreeMap<L, Vec<L>>,
+    /// tasks that no other task depends on, in topological order
+    pub sink_tasks: Vec<L>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CoreError<L> {
+    /// the task is found in the graph, but not among the durations
+    MissingDuration(L),
+    Cycle,
+    /// the duration of a path leading to the task does not fit in the duration type
+    DurationOverflow(L),
+}
+
+/// Hooks into the traversal of `analyze_with_visitor`, e.g., to accumulate metrics under a custom
+/// cost model without walking the graph again
+pub trait ScheduleVisitor<L, T> {
+    /// Called once per task, in topological order, as the task completes at `earliest_finish`.
+    /// `current_parallelism` is the number of tasks executing right before it completes.
+    fn on_task_scheduled(&mut self, task: L, earliest_finish: T, current_parallelism: usize);
+}
+
+/// Visits nothing
+impl<L, T> ScheduleVisitor<L, T> for () {
+    fn on_task_scheduled(&mut self, _task: L, _earliest_finish: T, _current_parallelism: usize) {}
+}
+
+/// Durations that can be added up, reporting overflows rather than wrapping around
+pub trait CheckedAdd: Sized {
+    fn checked_add(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! impl_checked_add {
+    ($($t:ty),*) => {
+        $(
+            impl CheckedAdd for $t {
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+            }
+        )*
+    };
+}
+
+impl_checked_add!(u8, u16, u32, u64, u128, usize);
+
+impl<L: fmt::Debug> fmt::Display for CoreError<L> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CoreError::MissingDuration(task) => write!(f, "Missing duration for: {:?}", task),
+            CoreError::Cycle => write!(f, "There's a cycle in the schedule"),
+            CoreError::DurationOverflow(task) => {
+                write!(f, "Duration of the paths leading to {:?} overflows", task)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<L: fmt::Debug> std::error::Error for CoreError<L> {}
+
+/// Sorts the tasks of `task_graph` (task -> tasks depending on it) topologically. Tasks without
+/// relations are keys without neighbors. Relations missing from `edge_weights` cost nothing.
+/// Time: O((V + E)logV)
+/// Space: O(V + E)
+pub fn analyze<L, T>(
+    task_graph: &BTreeMap<L, Vec<L>>,
+    task_durations: &BTreeMap<L, T>,
+    edge_weights: &BTreeMap<(L, L), T>,
+) -> Result<CoreAnalysis<L, T>, CoreError<L>>
+where
+    L: Ord + Copy,
+    T: Ord + Copy + Default + CheckedAdd,
+{
+    analyze_with_visitor(task_graph, task_durations, edge_weights, &mut ())
+}
+
+/// Same as `analyze`, except that the visitor is told about each task as it gets scheduled
+pub fn analyze_with_visitor<L, T, V>(
+    task_graph: &BTreeMap<L, Vec<L>>,
+    task_durations: &BTreeMap<L, T>,
+    edge_weights: &BTreeMap<(L, L), T>,
+    visitor: &mut V,
+) -> Result<CoreAnalysis<L, T>, CoreError<L>>
+where
+    L: Ord + Copy,
+    T: Ord + Copy + Default + CheckedAdd,
+    V: ScheduleVisitor<L, T> + ?Sized,
+{
+    let mut preceding_task_count = BTreeMap::new();
+    for (&task, next_tasks) in task_graph {
+        preceding_task_count.entry(task).or_insert(0usize);
+        for &next_task in next_tasks {
+            *preceding_task_count.entry(next_task).or_insert(0) += 1;
+        }
+    }
+    let next_task_count = |task: L| {
+        task_graph
+            .get(&task)
+            .map_or(0, |next_tasks| next_tasks.len())
+    };
+    let duration_of = |task: L| {
+        task_durations
+            .get(&task)
+            .copied()
+            .ok_or(CoreError::MissingDuration(task))
+    };
+
+    // ordered by end time, then by the number of tasks following the task, then by label, so that
+    // the order in which tasks leave the queue is deterministic. Among the tasks ending at the
+    // same time, the ones followed by fewer tasks leave first, so that their task-runners are
+    // freed up before the tasks following the others are counted as executing. Ordering them by
+    // the longest path remaining ahead of them instead would take A off the queue before K when
+    // A precedes B and C and none of them takes any time, counting B, C and K as executing at
+    // once: a peak of 3 rather than 2.
+    let mut task_queue = BinaryHeap::new();
+    let mut longest_duration_path_to_task = BTreeMap::new();
+    for (&task, &count) in &preceding_task_count {
+        if count == 0 {
+            let duration = duration_of(task)?;
+            task_queue.push(Reverse((duration, next_task_count(task), task)));
+            longest_duration_path_to_task.insert(task, duration);
+        }
+    }
+    if task_queue.is_empty() && !preceding_task_count.is_empty() {
+        return Err(CoreError::Cycle);
+    }
+
+    let mut max_parallelism = 0usize;
+    let mut sink_tasks = Vec::new();
+    let mut parent_tasks: BTreeMap<L, Vec<L>> = BTreeMap::new();
+    let mut topological_order = Vec::with_capacity(preceding_task_count.len());
+    while let Some(Reverse((end_time, _, from_task))) = task_queue.pop() {
+        let current_parallelism = task_queue.len() + 1;
+        max_parallelism = max_parallelism.max(current_parallelism);
+        visitor.on_task_scheduled(from_task, end_time, current_parallelism);
+        topological_order.push(from_task);
+        let next_tasks = task_graph
+            .get(&from_task)
+            .map_or(&[][..], |next_tasks| &next_tasks[..]);
+        if next_tasks.is_empty() {
+            sink_tasks.push(from_task);
+        }
+        for &to_task in next_tasks {
+            let edge_weight = edge_weights
+                .get(&(from_task, to_task))
+                .copied()
+                .unwrap_or_default();
+            let to_task_duration = duration_of(to_task)?;
+            let alternative_path_duration = longest_duration_path_to_task[&from_task]
+                .checked_add(edge_weight)
+                .and_then(|duration| duration.checked_add(to_task_duration))
+                .ok_or(CoreError::DurationOverflow(to_task))?;
+            match longest_duration_path_to_task.get(&to_task) {
+                // relaxing path duration
+                Some(&previous_path_duration)
+                    if alternative_path_duration < previous_path_duration => {}
+                Some(&previous_path_duration)
+                    if alternative_path_duration == previous_path_duration =>
+                {
+                    parent_tasks.entry(to_task).or_default().push(from_task);
+                }
+                _ => {
+                    longest_duration_path_to_task.insert(to_task, alternative_path_duration);
+                    parent_tasks.insert(to_task, alloc::vec![from_task]);
+                }
+            }
+            let count = preceding_task_count.get_mut(&to_task).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                task_queue.push(Reverse((
+                    longest_duration_path_to_task[&to_task],
+                    next_task_count(to_task),
+                    to_task,
+                )));
+            }
+        }
+    }
+
+    if topological_order.len() != preceding_task_count.len() {
+        return Err(CoreError::Cycle);
+    }
+    Ok(CoreAnalysis {
+        topological_order,
+        max_parallelism,
+        longest_duration_path_to_task,
+        parent_tasks,
+        sink_tasks,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn graph(relations: &[(char, char)], tasks: &[char]) -> BTreeMap<char, Vec<char>> {
+        let mut task_graph = BTreeMap::new();
+        for &task in tasks {
+            task_graph.entry(task).or_insert_with(Vec::new);
+        }
+        for &(from_task, to_task) in relations {
+            task_graph
+                .entry(from_task)
+                .or_insert_with(Vec::new)
+                .push(to_task);
+        }
+        task_graph
+    }
+
+    #[test]
+    fn diamond() {
+        //    /--> B -\
+        //  A          > D
+        //    \--> C -/
+        let task_graph = graph(&[('A', 'B'), ('A', 'C'), ('B', 'D'), ('C', 'D')], &[]);
+        let task_durations = [('A', 1u32), ('B', 2), ('C', 2), ('D', 1)]
+            .iter()
+            .copied()
+            .collect();
+        let analysis = analyze(&task_graph, &task_durations, &BTreeMap::new()).unwrap();
+        assert_eq!(analysis.topological_order, vec!['A', 'B', 'C', 'D']);
+        assert_eq!(analysis.max_parallelism, 2);
+        assert_eq!(analysis.longest_duration_path_to_task[&'D'], 4);
+        assert_eq!(analysis.parent_tasks[&'D'], vec!['B', 'C']);
+        assert_eq!(analysis.sink_tasks, vec!['D']);
+
+        let edge_weights = [(('C', 'D'), 3u32)].iter().copied().collect();
+        let analysis = analyze(&task_graph, &task_durations, &edge_weights).unwrap();
+        assert_eq!(analysis.longest_duration_path_to_task[&'D'], 7);
+        assert_eq!(analysis.parent_tasks[&'D'], vec!['C']);
+    }
+
+    #[test]
+    fn visiting_tasks() {
+        struct Recorder(Vec<(char, u32, usize)>);
+        impl ScheduleVisitor<char, u32> for Recorder {
+            fn on_task_scheduled(&mut self, task: char, earliest_finish: u32, parallelism: usize) {
+                self.0.push((task, earliest_finish, parallelism));
+            }
+        }
+
+        let task_graph = graph(&[('A', 'B'), ('A', 'C'), ('B', 'D'), ('C', 'D')], &[]);
+        let task_durations = [('A', 1u32), ('B', 2), ('C', 3), ('D', 1)]
+            .iter()
+            .copied()
+            .collect();
+        let mut recorder = Recorder(Vec::new());
+        analyze_with_visitor(
+            &task_graph,
+            &task_durations,
+            &BTreeMap::new(),
+            &mut recorder,
+        )
+        .unwrap();
+        assert_eq!(
+            recorder.0,
+            vec![('A', 1, 1), ('B', 3, 2), ('C', 4, 1), ('D', 5, 1)]
+        );
+    }
+
+    #[test]
+    fn unrelated_tasks() {
+        let task_graph = graph(&[], &['A', 'B', 'C']);
+        let task_durations = [('A', 3u32), ('B', 1), ('C', 1)].iter().copied().collect();
+        let analysis = analyze(&task_graph, &task_durations, &BTreeMap::new()).unwrap();
+        assert_eq!(analysis.topological_order, vec!['B', 'C', 'A']);
+        assert_eq!(analysis.max_parallelism, 3);
+        assert_eq!(analysis.sink_tasks, vec!['B', 'C', 'A']);
+    }
+
+    #[test]
+    fn errors() {
+        let task_graph = graph(&[('A', 'B'), ('B', 'C'), ('C', 'B')], &[]);
+        let task_durations = [('A', 1u32), ('B', 1), ('C', 1)].iter().copied().collect();
+        assert_eq!(
+            analyze(&task_graph, &task_durations, &BTreeMap::new()),
+            Err(CoreError::Cycle)
+        );
+
+        let task_graph = graph(&[('A', 'B')], &[]);
+        let task_durations = [('A', 1u32)].iter().copied().collect();
+        assert_eq!(
+            analyze(&task_graph, &task_durations, &BTreeMap::new()),
+            Err(CoreError::MissingDuration('B'))
+        );
+
+        let task_graph = graph(&[('A', 'B'), ('B', 'C')], &[]);
+        let task_durations = [('A', u8::MAX - 1), ('B', 1), ('C', 1)]
+            .iter()
+            .copied()
+            .collect();
+        assert_eq!(
+            analyze(&task_graph, &task_durations, &BTreeMap::new()),
+            Err(CoreError::DurationOverflow('C'))
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use crate::analyzer::ScheduleAnalysis;
+use crate::task::TotalDuration;
+use std::fmt;
+use std::fmt::Formatter;
+
+/// Outcome of analyzing one of the files of an aggregate report
+#[derive(Debug, PartialEq, Eq)]
+pub struct FileSummary {
+    pub file_path: String,
+    /// (minimum completion time, maximum parallelism), or why the file can't be analyzed
+    pub outcome: Result<(TotalDuration, usize), String>,
+}
+
+impl FileSummary {
+    pub fn new(file_path: &str, analysis: Result<&ScheduleAnalysis, String>) -> Self {
+        FileSummary {
+            file_path: file_path.to_string(),
+            outcome: analysis.map(|analysis| {
+                (
+                    analysis.minimum_completion_time(),
+                    analysis.max_parallelism(),
+                )
+            }),
+        }
+    }
+
+    pub fn passed(&self) -> bool {
+        self.outcome.is_ok()
+    }
+}
+
+/// Files analyzed independently of each other, such as the schedules of a repository. Files that
+/// can't be analyzed, e.g., cyclic schedules, fail their own row rather than the whole report.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct AggregateReport {
+    pub files: Vec<FileSummary>,
+}
+
+impl AggregateReport {
+    pub fn failed_count(&self) -> usize {
+        self.files.iter().filter(|file| !file.passed()).count()
+    }
+}
+
+impl fmt::Display for AggregateReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let file_width = self
+            .files
+            .iter()
+            .map(|file| file.file_path.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max("file".len());
+        writeln!(
+            f,
+            "{:<width$}  {:>8}  {:>11}  status",
+            "file",
+            "makespan",
+            "parallelism",
+            width = file_width
+        )?;
+        for file in &self.files {
+            let (makespan, parallelism, status) = match &file.outcome {
+                Ok((makespan, parallelism)) => (
+                    makespan.to_string(),
+                    parallelism.to_string(),
+                    String::from("pass"),
+                ),
+                Err(reason) => (
+                    String::from("-"),
+                    String::from("-"),
+                    format!("fail: {}", reason),
+                ),
+            };
+            writeln!(
+                f,
+                "{:<width$}  {:>8}  {:>11}  {}",
+                file.file_path,
+                makespan,
+                parallelism,
+                status,
+                width = file_width
+            )?;
+        }
+        let failed_count = self.failed_count();
+        write!(
+            f,
+            "{} files, {} passed, {} failed",
+            self.files.len(),
+            self.files.len() - failed_count,
+            failed_count
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processor::process;
+
+    #[test]
+    fn aggregate_report_as_table() {
+        let analysis = process("A(1)\nB(2) after [A]\nC(1) after [A]").unwrap();
+        let report = AggregateReport {
+            files: vec![
+                FileSummary::new("a.tasks.in", Ok(&analysis)),
+                FileSummary::new(
+                    "cyclic.tasks.in",
+                    Err(String::from("There's a cycle in the schedule")),
+                ),
+            ],
+        };
+        assert_eq!(report.failed_count(), 1);
+        assert_eq!(
+            report.to_string(),
+            "file             makespan  parallelism  status
+a.tasks.in              3            2  pass
+cyclic.tasks.in         -            -  fail: There's a cycle in the schedule
+2 files, 1 passed, 1 failed"
+        );
+    }
+}
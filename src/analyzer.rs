@@ -1,27 +1,84 @@
-use crate::task::{Duration, TaskLabel, TaskOrder, TotalDuration};
+use crate::task::{Duration, ScheduleDuration, TaskLabel, TaskOrder, TaskRelation};
+use analyze_task_schedule_core as schedule_core;
 use log::{debug, trace};
+pub use schedule_core::ScheduleVisitor;
+use std::borrow::Cow;
 use std::cmp::{Ordering, Reverse};
-use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet};
+use std::convert::TryFrom;
 use std::error::Error as StdError;
 use std::fmt;
 use std::fmt::Formatter;
 use std::fmt::Write;
+use std::str::FromStr;
+use std::time::{Duration as StdDuration, Instant};
+
+// sum of durations along a path
+type Total<D> = <D as ScheduleDuration>::Total;
+
+// counting topological orders takes exponential time and space in the number of tasks
+const MAX_TASKS_TO_COUNT_ORDERS: usize = 20;
+
+/// How critical paths are ordered
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum PathOrder {
+    /// paths with more tasks first, then in lexicographical order of their labels
+    #[default]
+    Length,
+    /// lexicographical order of the labels, regardless of the number of tasks
+    Lexicographic,
+    /// by the label of the first task, keeping the paths starting with the same task in the
+    /// default order
+    FirstTask,
+}
+
+impl FromStr for PathOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "length" => Ok(PathOrder::Length),
+            "lex" => Ok(PathOrder::Lexicographic),
+            "first" => Ok(PathOrder::FirstTask),
+            unknown => Err(format!("Unknown path order: {}", unknown)),
+        }
+    }
+}
 
 /// Uses Kahn's topological sorting algorithm to analyze acyclic schedules. It recognizes the fact
 /// that a finite DAG has at least one source and at least one sink. It is capable of detecting
 /// cycles, which results in AnalysisError::Cycle
 
 #[derive(Debug)]
-pub struct ScheduleAnalysis<'a> {
+pub struct ScheduleAnalysis<'a, D: ScheduleDuration = Duration> {
     max_parallelism: usize,
     task_count: usize,
-    minimum_completion_time: TotalDuration,
+    minimum_completion_time: Total<D>,
     critical_path_count: usize,
     critical_paths: Vec<Vec<TaskLabel<'a>>>,
+    task_graph: HashMap<TaskLabel<'a>, Vec<TaskLabel<'a>>>, // task -> neighbors
+    preceding_task_count: HashMap<TaskLabel<'a>, usize>,    // task -> number of preceding tasks
+    topological_order: Vec<TaskLabel<'a>>,
+    task_durations: HashMap<TaskLabel<'a>, D>,
+    edge_weights: HashMap<(TaskLabel<'a>, TaskLabel<'a>), D>,
+    stage_timings: Vec<(&'static str, StdDuration)>,
+    // task -> preceding tasks on the longest paths leading to the task
+    parent_tasks: HashMap<TaskLabel<'a>, Vec<TaskLabel<'a>>>,
+    longest_duration_path_to_task: HashMap<TaskLabel<'a>, Total<D>>,
+    // descriptions do not take part in the analysis, they are only reported
+    task_descriptions: HashMap<TaskLabel<'a>, &'a str>,
+    // values do not take part in the analysis either, they weigh the progress of the schedule
+    task_values: HashMap<TaskLabel<'a>, f64>,
+    // milestones are analyzed as tasks taking no time, they are only flagged in reports
+    milestones: HashSet<TaskLabel<'a>>,
+    path_order: PathOrder,
+    // how the schedule is analyzed, so that its components are analyzed the same way
+    task_positions: Option<HashMap<TaskLabel<'a>, usize>>,
+    max_critical_paths: Option<usize>,
 }
 
 #[allow(dead_code)]
-impl<'a> ScheduleAnalysis<'a> {
+impl<'a, D: ScheduleDuration> ScheduleAnalysis<'a, D> {
     pub fn max_parallelism(&self) -> usize {
         self.max_parallelism
     }
@@ -30,7 +87,7 @@ impl<'a> ScheduleAnalysis<'a> {
         self.task_count
     }
 
-    pub fn minimum_completion_time(&self) -> TotalDuration {
+    pub fn minimum_completion_time(&self) -> Total<D> {
         self.minimum_completion_time
     }
 
@@ -38,502 +95,3295 @@ impl<'a> ScheduleAnalysis<'a> {
         self.critical_path_count
     }
 
+    /// Empty when there were more critical paths than the analysis was allowed to list, see
+    /// `critical_paths_listed`
     pub fn critical_paths(&self) -> &Vec<Vec<TaskLabel<'a>>> {
         &self.critical_paths
     }
-}
 
-impl<'a> std::fmt::Display for ScheduleAnalysis<'a> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "task_count: {}", self.task_count)?;
-        writeln!(f, "max_parallelism: {}", self.max_parallelism)?;
+    /// Critical paths with their labels joined by `->` on a single line, regardless of their length
+    pub fn critical_path_strings(&self) -> Vec<String> {
+        self.critical_paths
+            .iter()
+            .map(|path| {
+                path.iter()
+                    .map(|task| task.as_ref())
+                    .collect::<Vec<_>>()
+                    .join("->")
+            })
+            .collect()
+    }
+
+    /// Critical path with the most tasks, i.e., the one breaking the schedule down the most. Ties
+    /// go to the path with the lexicographically smallest labels, regardless of how the critical
+    /// paths are ordered. None when the critical paths are not listed.
+    pub fn most_detailed_critical_path(&self) -> Option<&Vec<TaskLabel<'a>>> {
+        self.critical_paths.iter().min_by(|path1, path2| {
+            path2
+                .len()
+                .cmp(&path1.len())
+                .then_with(|| path1.iter().cmp(path2.iter()))
+        })
+    }
+
+    /// Whether all the critical paths are listed, rather than only counted
+    pub fn critical_paths_listed(&self) -> bool {
+        self.critical_paths.len() == self.critical_path_count
+    }
+
+    /// Time spent in each stage of the analysis, in the order the stages run
+    pub fn stage_timings(&self) -> &[(&'static str, StdDuration)] {
+        &self.stage_timings
+    }
+
+    /// Distinct tasks found on any of the critical paths, ordered by their labels
+    pub fn critical_tasks(&self) -> Vec<TaskLabel<'a>> {
+        let mut critical_tasks = self
+            .critical_paths
+            .iter()
+            .flatten()
+            .cloned()
+            .collect::<Vec<_>>();
+        critical_tasks.sort_unstable();
+        critical_tasks.dedup();
+        critical_tasks
+    }
+
+    pub fn task_duration(&self, task: TaskLabel<'a>) -> Option<D> {
+        self.task_durations.get(&task).cloned()
+    }
+
+    pub fn task_durations(&self) -> &HashMap<TaskLabel<'a>, D> {
+        &self.task_durations
+    }
+
+    /// Tasks in the order they complete when each task starts as soon as its preceding tasks
+    /// complete, which is a valid topological order. Tasks completing at the same time are
+    /// ordered by the number of tasks following them, then by their labels. Reversing the order
+    /// puts the sink tasks first.
+    pub fn topological_order(&self) -> &Vec<TaskLabel<'a>> {
+        &self.topological_order
+    }
+
+    pub fn task_description(&self, task: TaskLabel<'a>) -> Option<&'a str> {
+        self.task_descriptions.get(&task).copied()
+    }
+
+    pub fn task_descriptions(&self) -> &HashMap<TaskLabel<'a>, &'a str> {
+        &self.task_descriptions
+    }
+
+    /// Attaches descriptions to the tasks, leaving the analysis as it is
+    pub fn with_task_descriptions(
+        mut self,
+        task_descriptions: HashMap<TaskLabel<'a>, &'a str>,
+    ) -> Self {
+        self.task_descriptions = task_descriptions;
+        self
+    }
+
+    /// Share of the value of the schedule the task delivers once it completes. Tasks without a
+    /// value are worth 1.
+    pub fn task_value(&self, task: TaskLabel<'a>) -> f64 {
+        self.task_values.get(&task).copied().unwrap_or(1.0)
+    }
+
+    /// Attaches values to the tasks, leaving the analysis as it is
+    pub fn with_task_values(mut self, task_values: HashMap<TaskLabel<'a>, f64>) -> Self {
+        self.task_values = task_values;
+        self
+    }
+
+    /// Share of the value of the schedule delivered over time if each task completes at its
+    /// earliest finish time, i.e., the values of the tasks completed by the time, over the value
+    /// of all the tasks. There is a point at time 0 and at each time a task completes, the last of
+    /// which is at the minimum completion time with a share of 1. Empty when no task is worth
+    /// anything.
+    /// Time: O(V * logV)
+    pub fn value_progress_curve(&self) -> Vec<(Total<D>, f64)> {
+        let mut completions = self
+            .longest_duration_path_to_task
+            .iter()
+            .map(|(&task, &earliest_finish)| (earliest_finish, self.task_value(task)))
+            .collect::<Vec<_>>();
+        completions.sort_unstable_by_key(|&(earliest_finish, _)| earliest_finish);
+        // summed in the same order as the delivered value, so that the curve ends with 1
+        let total_value = completions.iter().map(|&(_, value)| value).sum::<f64>();
+        if total_value <= 0.0 {
+            return Vec::new();
+        }
+        let mut curve = vec![(Total::<D>::default(), 0.0)];
+        let mut delivered_value = 0.0;
+        for (earliest_finish, value) in completions {
+            delivered_value += value;
+            let share = delivered_value / total_value;
+            match curve.last_mut() {
+                Some((time, last_share)) if *time == earliest_finish => *last_share = share,
+                _ => curve.push((earliest_finish, share)),
+            }
+        }
+        curve
+    }
+
+    pub fn is_milestone(&self, task: TaskLabel<'a>) -> bool {
+        self.milestones.contains(&task)
+    }
+
+    /// Tasks declared as milestones, in topological order
+    pub fn milestones(&self) -> Vec<TaskLabel<'a>> {
+        self.topological_order
+            .iter()
+            .filter(|task| self.milestones.contains(task))
+            .cloned()
+            .collect()
+    }
+
+    /// Reorders the critical paths, regardless of the order they are in. The default order puts
+    /// the single-task paths in the order the tasks are declared in if asked to.
+    pub fn with_path_order(mut self, order: PathOrder) -> Self {
+        self.path_order = order;
+        let task_positions = self.task_positions.as_ref();
+        match order {
+            PathOrder::Length => self
+                .critical_paths
+                .sort_unstable_by(|path1, path2| compare_paths(path1, path2, task_positions)),
+            PathOrder::Lexicographic => self
+                .critical_paths
+                .sort_unstable_by(|path1, path2| path1.iter().cmp(path2.iter())),
+            PathOrder::FirstTask => self.critical_paths.sort_unstable_by(|path1, path2| {
+                path1
+                    .first()
+                    .cmp(&path2.first())
+                    .then_with(|| compare_paths(path1, path2, task_positions))
+            }),
+        }
+        self
+    }
+
+    /// Flags the tasks as milestones, leaving the analysis as it is
+    pub fn with_milestones(mut self, milestones: HashSet<TaskLabel<'a>>) -> Self {
+        self.milestones = milestones;
+        self
+    }
+
+    /// Writes the analysis out the way it is displayed, except that the critical paths are
+    /// written out by `serialize_path`, e.g., to elide them
+    pub fn write_text(
+        &self,
+        buffer: &mut dyn Write,
+        serialize_path: &dyn Fn(&[TaskLabel<'a>], &mut dyn Write) -> std::fmt::Result,
+    ) -> std::fmt::Result {
+        writeln!(buffer, "task_count: {}", self.task_count)?;
+        writeln!(buffer, "max_parallelism: {}", self.max_parallelism)?;
         writeln!(
-            f,
+            buffer,
             "minimum_completion_time: {}",
             self.minimum_completion_time
         )?;
-        writeln!(f, "critical_path_count: {}", self.critical_path_count)?;
-        writeln!(
-            f,
-            "critical_path{}:",
-            if self.critical_path_count > 1 {
-                "s"
-            } else {
-                ""
-            }
-        )?;
+        writeln!(buffer, "critical_path_count: {}", self.critical_path_count)?;
+        // paths are always numbered, so that the output has the same structure regardless of
+        // the number of critical paths
+        writeln!(buffer, "critical_paths:")?;
         for (path_idx, path) in self.critical_paths.iter().enumerate() {
-            if self.critical_path_count > 1 {
-                writeln!(f, "{})", path_idx + 1)?;
-            }
-            serialize_path(path, f, "->", TaskLabel::MAX_LEN)?;
+            writeln!(buffer, "{})", path_idx + 1)?;
+            serialize_path(path, buffer)?;
             let not_last_path = path_idx != self.critical_path_count - 1;
             if not_last_path {
-                writeln!(f)?;
+                writeln!(buffer)?;
             }
         }
         Ok(())
     }
-}
 
-#[derive(Debug, Eq, PartialEq, Clone)]
-pub enum AnalysisError<'a> {
-    EmptyInput,
-    MissingDurations(Vec<TaskLabel<'a>>),
-    MissingOrders(Vec<TaskLabel<'a>>),
-    Cycle,
-}
+    /// task -> tasks it depends on that finish last, i.e., the tasks preceding it on the longest
+    /// paths leading to it, which the critical paths are made up from. Other tasks it depends on
+    /// are left out, so this is not the inverse of the task graph. Tasks that don't depend on
+    /// another task have no entry.
+    pub fn critical_parents(&self) -> &HashMap<TaskLabel<'a>, Vec<TaskLabel<'a>>> {
+        &self.parent_tasks
+    }
 
-impl<'a> StdError for AnalysisError<'a> {}
+    /// task -> tasks depending on it
+    pub fn task_graph(&self) -> &HashMap<TaskLabel<'a>, Vec<TaskLabel<'a>>> {
+        &self.task_graph
+    }
 
-impl<'a> fmt::Display for AnalysisError<'a> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        format_analysis_error(self, f)
+    pub fn edge_weights(&self) -> &HashMap<(TaskLabel<'a>, TaskLabel<'a>), D> {
+        &self.edge_weights
     }
-}
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-struct TaskExecutionEndTime<'a> {
-    task: TaskLabel<'a>,
-    end_time: TotalDuration,
-}
+    /// Number of tasks the given task directly depends on
+    pub fn in_degree(&self, task: TaskLabel<'a>) -> usize {
+        self.preceding_task_count.get(&task).copied().unwrap_or(0)
+    }
 
-impl<'a> PartialOrd for TaskExecutionEndTime<'a> {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.end_time.partial_cmp(&other.end_time)
+    /// Number of distinct relations between the tasks, i.e., the ordering constraints the
+    /// schedule satisfies
+    pub fn relation_count(&self) -> usize {
+        self.task_graph.values().map(Vec::len).sum()
     }
-}
 
-impl<'a> Ord for TaskExecutionEndTime<'a> {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.end_time.cmp(&other.end_time)
+    /// Number of tasks directly depending on the given task
+    pub fn out_degree(&self, task: TaskLabel<'a>) -> usize {
+        self.task_graph.get(&task).map_or(0, Vec::len)
     }
-}
 
-fn format_analysis_error<'a>(err: &AnalysisError<'a>, f: &mut fmt::Formatter) -> fmt::Result {
-    match err {
-        AnalysisError::EmptyInput => write!(f, "Input is empty"),
-        AnalysisError::MissingDurations(vec) => {
-            write!(
-                f,
-                "Schedule is missing durations for: {:?}",
-                vec.iter().map(|tl| tl.as_ref()).collect::<Vec<_>>()
-            )
+    /// Lists (split, join) pairs where all the tasks following the split task reconverge at the
+    /// join task. Only the nearest reconvergence points are reported for each split task, i.e.,
+    /// a join task is left out if one of its preceding tasks is also a reconvergence point.
+    /// Time: O(V * (V + E)) for finding the tasks reachable from each task
+    /// Space: O(V^2)
+    pub fn diamonds(&self) -> Vec<(TaskLabel<'a>, TaskLabel<'a>)> {
+        let inverse_task_graph = invert(&self.task_graph);
+        let mut diamonds = Vec::new();
+        for (&split, adjacent_tasks) in &self.task_graph {
+            if adjacent_tasks.len() < 2 {
+                continue;
+            }
+            let mut reconverging_tasks = reachable_tasks(&self.task_graph, adjacent_tasks[0]);
+            for &task in &adjacent_tasks[1..] {
+                let reachable = reachable_tasks(&self.task_graph, task);
+                reconverging_tasks.retain(|task| reachable.contains(task));
+            }
+            for &join in &reconverging_tasks {
+                let nearest_join = adjacent(&inverse_task_graph, &join)
+                    .iter()
+                    .all(|task| !reconverging_tasks.contains(task));
+                if nearest_join {
+                    diamonds.push((split, join));
+                }
+            }
         }
-        AnalysisError::MissingOrders(vec) => {
-            write!(
-                f,
-                "Schedule is missing orders for: {:?}",
-                vec.iter().map(|tl| tl.as_ref()).collect::<Vec<_>>()
-            )
+        diamonds.sort_unstable();
+        diamonds
+    }
+
+    /// Lists (task, dependent task, intermediate task) triples for the relations implied by other
+    /// relations, i.e., the dependent task also depends on the task through the intermediate task,
+    /// so that the transitive reduction of the schedule would leave the relation out. When several
+    /// intermediate tasks imply the relation, the one with the smallest label is picked.
+    /// Time: O(E * (V + E)) for finding the tasks reachable from each related task
+    /// Space: O(V)
+    pub fn redundant_relations(&self) -> Vec<(TaskLabel<'a>, TaskLabel<'a>, TaskLabel<'a>)> {
+        let mut redundant_relations = Vec::new();
+        for (&task, adjacent_tasks) in &self.task_graph {
+            let mut intermediate_tasks = adjacent_tasks.clone();
+            intermediate_tasks.sort_unstable();
+            let mut implied_by = HashMap::new();
+            for &intermediate_task in &intermediate_tasks {
+                let reachable = reachable_tasks(&self.task_graph, intermediate_task);
+                for dependent_task in adjacent_tasks {
+                    if reachable.contains(dependent_task) {
+                        implied_by
+                            .entry(*dependent_task)
+                            .or_insert(intermediate_task);
+                    }
+                }
+            }
+            redundant_relations.extend(implied_by.into_iter().map(
+                |(dependent_task, intermediate_task)| (task, dependent_task, intermediate_task),
+            ));
         }
-        AnalysisError::Cycle => write!(f, "There's a cycle in the schedule"),
+        redundant_relations.sort_unstable();
+        redundant_relations
     }
-}
 
-/// Produces an analysis of provided task schedule
-/// Time: O((V+E)logV) for topological sorting that uses a binary heap to figure out
-///       maximum parallel task execution, where V is the number of
-///       tasks and E is the number of relations between tasks. However,
-///       using a fibonacci heap could bring the cost down to O(E + VlogV).
-///       See https://docs.rs/rudac/0.8.3/rudac/heap/struct.FibonacciHeap.html
-///       We need to add to this the additional cost of multiple critical path construction,
-///       which is ~O(N^M * M). See "construct_path" for more explanation on that.
-/// Space: O(V). Might end up adding all tasks to the queue at once
-pub fn analyze_schedule<'a>(
-    task_orders: &HashSet<TaskOrder<'a>>,
-    task_durations: &HashMap<TaskLabel<'a>, Duration>,
-) -> Result<ScheduleAnalysis<'a>, AnalysisError<'a>> {
-    if task_orders.is_empty() && task_durations.is_empty() {
-        return Err(AnalysisError::EmptyInput);
+    /// Tasks that some other task can only be reached through from the sources, ordered by their
+    /// labels. If such a task fails, the tasks behind it cannot proceed regardless of which
+    /// sources the work starts from, e.g., the only source of a schedule, or the tasks within a
+    /// chain. These are the tasks dominating another task, where all the sources hang off a
+    /// common root.
+    /// Time: O(V + E * V) for walking up the dominator tree from each relation
+    /// Space: O(V)
+    pub fn critical_cut_tasks(&self) -> Vec<TaskLabel<'a>> {
+        let mut preceding_tasks = HashMap::new();
+        for (&task, next_tasks) in &self.task_graph {
+            for &next_task in next_tasks {
+                preceding_tasks
+                    .entry(next_task)
+                    .or_insert_with(Vec::new)
+                    .push(task);
+            }
+        }
+        // task -> (immediate dominator, depth in the dominator tree), where None stands for the
+        // root. A task is only dominated by tasks that come before it in topological order.
+        let mut dominators = HashMap::new();
+        for &task in &self.topological_order {
+            let dominator = preceding_tasks.get(&task).and_then(|preceding_tasks| {
+                preceding_tasks
+                    .iter()
+                    .map(|&preceding_task| Some(preceding_task))
+                    .reduce(|task1, task2| nearest_common_dominator(&dominators, task1, task2))
+                    .flatten()
+            });
+            let depth = dominator.map_or(0, |dominator| dominators[&dominator].1) + 1;
+            dominators.insert(task, (dominator, depth));
+        }
+        let mut cut_tasks = dominators
+            .values()
+            .filter_map(|&(dominator, _)| dominator)
+            .collect::<Vec<_>>();
+        cut_tasks.sort_unstable();
+        cut_tasks.dedup();
+        cut_tasks
     }
-    let Graph {
-        task_graph,
-        mut preceding_task_count,
-    } = Graph::new(task_orders);
-    {
-        let mut missing = preceding_task_count
-            .keys()
-            .filter(|&task| !task_durations.contains_key(task))
-            .cloned()
+
+    /// Groups of tasks that could be merged into one, as they take the same time and have the same
+    /// preceding and following tasks, along with the same weights on their relations, e.g., `D`,
+    /// `E` and `F` in `C -> {D, E, F}`. Tasks within a group are ordered by their labels, and
+    /// groups by their first task. Tasks that cannot be merged with another task are left out.
+    /// Time: O(V * logV + E * logE)
+    pub fn mergeable_groups(&self) -> Vec<Vec<TaskLabel<'a>>> {
+        let weight = |task, next_task| self.edge_weights.get(&(task, next_task)).copied();
+        let mut preceding_tasks = HashMap::new();
+        for (&task, next_tasks) in &self.task_graph {
+            for &next_task in next_tasks {
+                preceding_tasks
+                    .entry(next_task)
+                    .or_insert_with(Vec::new)
+                    .push((task, weight(task, next_task)));
+            }
+        }
+        let mut groups = BTreeMap::new();
+        for &task in &self.topological_order {
+            let mut preceding_tasks = preceding_tasks.remove(&task).unwrap_or_default();
+            preceding_tasks.sort_unstable();
+            let mut next_tasks = adjacent(&self.task_graph, &task)
+                .iter()
+                .map(|&next_task| (next_task, weight(task, next_task)))
+                .collect::<Vec<_>>();
+            next_tasks.sort_unstable();
+            groups
+                .entry((preceding_tasks, next_tasks, self.task_durations[&task]))
+                .or_insert_with(Vec::new)
+                .push(task);
+        }
+        let mut mergeable_groups = groups
+            .into_values()
+            .filter(|group| group.len() > 1)
+            .map(|mut group| {
+                group.sort_unstable();
+                group
+            })
             .collect::<Vec<_>>();
-        if !missing.is_empty() {
-            missing.sort_unstable();
-            return Err(AnalysisError::MissingDurations(missing));
+        mergeable_groups.sort_unstable();
+        mergeable_groups
+    }
+
+    /// Analysis of each weakly connected component of the schedule on its own, ordered by their
+    /// smallest label. Components are analyzed the same way as the schedule, and they keep the
+    /// descriptions, values and milestones of their tasks along with how paths are ordered and
+    /// written out. See `analyze_components`.
+    /// Time: O(V * logV + E) in addition to the analysis of each component
+    pub fn components(&self) -> Result<Vec<ScheduleAnalysis<'a, D>>, AnalysisError<'a>> {
+        let mut task_orders = HashSet::new();
+        for (&task, next_tasks) in &self.task_graph {
+            task_orders.insert(task.node());
+            task_orders.extend(next_tasks.iter().map(|&next_task| task.arrow(next_task)));
         }
+        let components = analyze_partitioned_schedule(
+            &task_orders,
+            &self.task_durations,
+            &self.edge_weights,
+            self.task_positions.as_ref(),
+            self.max_critical_paths,
+        )?;
+        Ok(components
+            .into_iter()
+            .map(|component| {
+                let in_component =
+                    |task: &TaskLabel<'a>| component.task_durations.contains_key(task);
+                let task_descriptions = self
+                    .task_descriptions
+                    .iter()
+                    .filter(|(task, _)| in_component(task))
+                    .map(|(&task, &description)| (task, description))
+                    .collect();
+                let task_values = self
+                    .task_values
+                    .iter()
+                    .filter(|(task, _)| in_component(task))
+                    .map(|(&task, &value)| (task, value))
+                    .collect();
+                let milestones = self
+                    .milestones
+                    .iter()
+                    .filter(|task| in_component(task))
+                    .cloned()
+                    .collect();
+                component
+                    .with_task_descriptions(task_descriptions)
+                    .with_task_values(task_values)
+                    .with_milestones(milestones)
+                    .with_path_order(self.path_order)
+            })
+            .collect())
     }
 
-    if task_durations.len() != preceding_task_count.len() {
-        let mut missing = task_durations
-            .keys()
-            .filter(|&task| !preceding_task_count.contains_key(task))
-            .cloned()
-            .collect::<Vec<_>>();
-        missing.sort_unstable();
-        return Err(AnalysisError::MissingOrders(missing));
+    /// Number of tasks that directly or transitively depend on the given task, i.e., the tasks
+    /// affected if the given task slips. Computed on demand.
+    /// Time: O(V + E)
+    pub fn downstream_count(&self, task: TaskLabel<'a>) -> usize {
+        reachable_tasks(&self.task_graph, task).len()
     }
 
-    debug!("created task_graph: {:?}", task_graph);
-    debug!("created preceding_task_count: {:?}", preceding_task_count);
-    // using heap to figure out the maximum number of tasks that can be run simultaneously
-    let mut task_queue = BinaryHeap::new();
-    // longest time spent, including the task's own duration, along the path to reach the task
-    // via one of the paths
-    let mut longest_duration_path_to_task = HashMap::new();
-    for (&task, count) in &preceding_task_count {
-        let source_task = *count == 0;
-        if source_task {
-            task_queue.push(Reverse(TaskExecutionEndTime {
-                task,
-                end_time: task_durations[&task] as TotalDuration,
-            }));
-            longest_duration_path_to_task.insert(task, task_durations[&task] as TotalDuration);
+    /// Whether `task` has to finish before `other_task` can start, i.e., there is a path from
+    /// `task` to `other_task`. A task doesn't precede itself. Computed on demand, stopping as soon
+    /// as `other_task` is reached.
+    /// Time: O(V + E)
+    pub fn precedes(&self, task: TaskLabel<'a>, other_task: TaskLabel<'a>) -> bool {
+        let mut visited = HashSet::new();
+        let mut stack = adjacent(&self.task_graph, &task).to_vec();
+        while let Some(next_task) = stack.pop() {
+            if next_task == other_task {
+                return true;
+            }
+            if visited.insert(next_task) {
+                stack.extend_from_slice(adjacent(&self.task_graph, &next_task));
+            }
         }
+        false
     }
-    {
-        let no_source_tasks_exist = task_queue.is_empty();
-        if no_source_tasks_exist {
-            return Err(AnalysisError::Cycle);
-        }
-    }
-    debug!("source_tasks: {:?}", task_queue);
-    let mut max_parallel_tasks = 0usize;
-    let mut sink_tasks = Vec::new(); // they do not precede any tasks
-    let mut parent_tasks = HashMap::new();
-    while !task_queue.is_empty() {
-        max_parallel_tasks = max_parallel_tasks.max(task_queue.len());
-        let TaskExecutionEndTime {
-            task: from_task, ..
-        } = task_queue.pop().unwrap().0;
-        // Given two paths such as ["A", "C -> K -> L"], "A" is a single-path task. "C" and "K"
-        // precede other tasks; C needs to be executed before K, and K needs to be executed before "L"
-        // L is a "sink" task. A is also a "sink" task due to being the last task to execute on the path.
-        let single_task_path_or_precedes_other_tasks = task_graph.contains_key(&from_task);
-        if single_task_path_or_precedes_other_tasks {
-            let adjacent_tasks = &task_graph[&from_task];
-            let path_with_single_task = adjacent_tasks.is_empty();
-            if path_with_single_task {
-                sink_tasks.push(from_task);
+
+    /// Number of distinct valid topological orders, i.e., the ways the tasks can be executed one
+    /// at a time, which tells how much freedom there is in scheduling them. A chain has a single
+    /// order, while N unrelated tasks have N! orders. Returns None for schedules with more than
+    /// 20 tasks. Computed on demand by counting the orders of every subset of tasks that can be
+    /// executed first.
+    /// Time: O(2^V * V)
+    /// Space: O(2^V)
+    pub fn topological_order_count(&self) -> Option<u128> {
+        let task_count = self.topological_order.len();
+        if task_count > MAX_TASKS_TO_COUNT_ORDERS {
+            return None;
+        }
+        let task_indices = self
+            .topological_order
+            .iter()
+            .enumerate()
+            .map(|(task_idx, &task)| (task, task_idx))
+            .collect::<HashMap<_, _>>();
+        // bit set of the tasks each task depends on
+        let mut preceding_tasks = vec![0usize; task_count];
+        for (task, next_tasks) in &self.task_graph {
+            for next_task in next_tasks {
+                preceding_tasks[task_indices[next_task]] |= 1 << task_indices[task];
             }
-            for &to_task in adjacent_tasks {
-                let alternative_path_duration = longest_duration_path_to_task[&from_task]
-                    + task_durations[&to_task] as TotalDuration;
-                if let Some(&previous_path_duration) = longest_duration_path_to_task.get(&to_task) {
-                    // relaxing path duration
-                    if alternative_path_duration > previous_path_duration {
-                        longest_duration_path_to_task.insert(to_task, alternative_path_duration);
-                        parent_tasks.insert(to_task, vec![from_task]);
-                    } else if alternative_path_duration == previous_path_duration {
-                        parent_tasks
-                            .entry(to_task)
-                            .and_modify(|vec| vec.push(from_task));
-                    }
-                } else {
-                    longest_duration_path_to_task.insert(to_task, alternative_path_duration);
-                    parent_tasks.insert(to_task, vec![from_task]);
-                }
-                preceding_task_count
-                    .entry(to_task)
-                    .and_modify(|count| *count -= 1);
-                let ready_to_schedule = preceding_task_count[&to_task] == 0;
-                if ready_to_schedule {
-                    task_queue.push(Reverse(TaskExecutionEndTime {
-                        task: to_task,
-                        end_time: longest_duration_path_to_task[&to_task],
-                    }));
+        }
+        // number of orders in which the tasks in the subset can be executed first
+        let mut order_counts = vec![0u128; 1 << task_count];
+        order_counts[0] = 1;
+        for executed_tasks in 0..order_counts.len() {
+            let order_count = order_counts[executed_tasks];
+            if order_count == 0 {
+                continue;
+            }
+            for (task_idx, &task_preceding_tasks) in preceding_tasks.iter().enumerate() {
+                let is_executed = executed_tasks & (1 << task_idx) != 0;
+                if !is_executed && task_preceding_tasks & !executed_tasks == 0 {
+                    order_counts[executed_tasks | (1 << task_idx)] += order_count;
                 }
             }
-        } else {
-            sink_tasks.push(from_task);
         }
+        order_counts.last().copied()
     }
 
-    // being extra careful
-    let no_cycle_exists = preceding_task_count.values().all(|&count| count == 0);
-    if no_cycle_exists {
-        trace!("finding critical paths...");
-        let CriticalPaths {
-            paths: critical_paths,
-            duration: critical_path_duration,
-        } = CriticalPaths::find_critical_paths(
-            &parent_tasks,
-            &longest_duration_path_to_task,
-            &sink_tasks,
-        );
-        debug!("critical paths:{:?}", critical_paths);
-        Ok(ScheduleAnalysis {
-            max_parallelism: max_parallel_tasks,
-            task_count: preceding_task_count.len(),
-            critical_path_count: critical_paths.len(),
-            minimum_completion_time: critical_path_duration,
-            critical_paths,
-        })
-    } else {
-        Err(AnalysisError::Cycle)
+    /// Number of tasks the given task directly or transitively depends on. Computed on demand.
+    /// Time: O(V + E), including the cost of inverting the task graph
+    pub fn upstream_count(&self, task: TaskLabel<'a>) -> usize {
+        reachable_tasks(&invert(&self.task_graph), task).len()
     }
-}
-
-#[derive(Debug)]
-struct Graph<'a> {
-    task_graph: HashMap<TaskLabel<'a>, Vec<TaskLabel<'a>>>, // task -> neighbors
-    preceding_task_count: HashMap<TaskLabel<'a>, usize>,    // task -> number of preceding tasks
-}
 
-impl<'a> Graph<'a> {
-    fn new(orders: &HashSet<TaskOrder<'a>>) -> Self {
-        let mut preceding_task_count = HashMap::new(); // aka, preceding_edge_count
-        let mut task_graph = HashMap::new();
-        for task_order in orders {
-            // make sure all nodes/tasks have an "incoming edge"/"preceding task" count,
-            // including the sources at the head of the graph
-            preceding_task_count
-                .entry(task_order.first())
-                .or_insert(0usize);
-            let adj_list = task_graph
-                .entry(task_order.first())
-                .or_insert_with(Vec::new);
-            task_order.second().iter().for_each(|&second| {
-                adj_list.push(second);
-                *preceding_task_count.entry(second).or_insert(0usize) += 1;
-            });
-        }
-        Graph {
-            task_graph,
-            preceding_task_count,
-        }
+    /// Longest path leading to each sink task along with its duration, including the sinks that
+    /// are not on any critical path. When several paths tie for a sink, the one going through
+    /// the preceding tasks with the smallest labels is picked.
+    /// Time: O(S * V), where S is the number of sink tasks
+    pub fn longest_path_per_sink(&self) -> HashMap<TaskLabel<'a>, (Total<D>, Vec<TaskLabel<'a>>)> {
+        self.topological_order
+            .iter()
+            .filter(|task| adjacent(&self.task_graph, task).is_empty())
+            .map(|&sink| {
+                let mut path = vec![sink];
+                let mut task = sink;
+                while let Some(parents) = self.parent_tasks.get(&task) {
+                    task = *parents.iter().min().unwrap();
+                    path.push(task);
+                }
+                path.reverse();
+                (sink, (self.longest_duration_path_to_task[&sink], path))
+            })
+            .collect()
     }
-}
-
-#[derive(Debug)]
-struct CriticalPaths<'a> {
-    paths: Vec<Vec<TaskLabel<'a>>>,
-    duration: TotalDuration,
-}
 
-impl<'a> CriticalPaths<'a> {
-    // If there are multiple CPs, the ones that have more tasks on them come before in order.
-    // Else, we defer to lexicographical order of paths' task labels.
+    /// Whether every task depends on at most one other task, i.e., the schedule is made up of
+    /// trees rooted at the source tasks
+    /// Time: O(V + E)
+    pub fn is_forest(&self) -> bool {
+        let mut has_preceding_task = HashSet::new();
+        self.task_graph
+            .values()
+            .flatten()
+            .all(|&task| has_preceding_task.insert(task))
+    }
 
-    fn find_critical_paths(
-        parent_tasks: &HashMap<TaskLabel<'a>, Vec<TaskLabel<'a>>>,
-        longest_duration_path_to_task: &HashMap<TaskLabel<'a>, TotalDuration>,
-        sink_tasks: &[TaskLabel<'a>],
-    ) -> Self {
-        debug!("parent_tasks: {:?}", parent_tasks);
-        debug!(
-            "longest_duration_path_to_task: {:?}",
-            longest_duration_path_to_task
-        );
-        debug!("sink_tasks: {:?}", sink_tasks);
-        let critical_path_duration = sink_tasks
+    /// Tasks that neither depend on another task nor precede one, in topological order
+    /// Time: O(V + E)
+    pub fn isolated_tasks(&self) -> Vec<TaskLabel<'a>> {
+        let has_preceding_task = self.task_graph.values().flatten().collect::<HashSet<_>>();
+        self.topological_order
             .iter()
-            .map(|task| longest_duration_path_to_task[task])
-            .max()
-            .unwrap_or(0);
+            .filter(|&task| {
+                adjacent(&self.task_graph, task).is_empty() && !has_preceding_task.contains(task)
+            })
+            .cloned()
+            .collect()
+    }
 
-        // Derive CPs from each sink task
-        let mut critical_paths = sink_tasks
+    /// Task that takes the longest on its own, regardless of the tasks around it. Ties are broken
+    /// in favor of the smallest label.
+    /// Time: O(V)
+    pub fn longest_task(&self) -> Option<(TaskLabel<'a>, D)> {
+        self.task_durations
             .iter()
-            .filter(|&task| longest_duration_path_to_task[task] == critical_path_duration)
-            .map(|&task| {
-                let mut paths = Vec::new();
-                CriticalPaths::construct_paths(parent_tasks, &mut paths, &mut Vec::new(), task);
-                paths.iter_mut().for_each(|path| path.reverse());
-                paths
+            .map(|(&task, &duration)| (task, duration))
+            .max_by(|(task1, duration1), (task2, duration2)| {
+                duration1.cmp(duration2).then(task2.cmp(task1))
             })
-            .flatten()
-            .collect::<Vec<_>>();
+    }
 
-        // Paths with more tasks should come first because they provide more opportunities
-        // for optimization. Else, we defer to lexicographical ordering.
-        critical_paths.sort_unstable_by(|path1, path2| {
-            path2
-                .len()
-                .cmp(&path1.len())
-                .then(path1.iter().cmp(path2.iter()))
-                .then_with(|| panic!("There cannot be duplicate critical paths {:?}", path1))
-        });
-        CriticalPaths {
-            paths: critical_paths,
-            duration: critical_path_duration,
-        }
+    /// Summary on a single line, e.g., "tasks=8 parallelism=3 makespan=4 paths=6", for grepping
+    /// logs
+    pub fn oneline(&self) -> String {
+        format!(
+            "tasks={} parallelism={} makespan={} paths={}",
+            self.task_count,
+            self.max_parallelism,
+            self.minimum_completion_time,
+            self.critical_path_count
+        )
     }
 
-    // Time: O(n^m * m), where n is max_len(parent_tasks.values()) and m is the total number of
-    //       tasks on the CP. "*m" comes from path additions while cloning
-    // Space: O(m) for stack space
-    fn construct_paths(
-        parent_tasks: &HashMap<TaskLabel<'a>, Vec<TaskLabel<'a>>>,
-        paths: &mut Vec<Vec<TaskLabel<'a>>>,
+    /// Short narrative on what drives the minimum completion time, citing the first critical path
+    /// and the task taking the longest on it. Ties are broken in favor of the smallest label.
+    /// When there are too many critical paths to list them, the one `longest_path_per_sink`
+    /// traces back from the critical sink with the smallest label is cited instead.
+    /// Time: O(M), where M is the number of tasks on the first critical path, or O(S * V) when
+    /// the critical paths are not listed
+    pub fn explain(&self) -> String {
+        let traced_path;
+        let critical_path = match self.critical_paths.first() {
+            Some(critical_path) => critical_path,
+            None if self.critical_path_count > 0 => {
+                traced_path = self
+                    .longest_path_per_sink()
+                    .into_iter()
+                    .filter(|(_, (duration, _))| *duration == self.minimum_completion_time)
+                    .min_by_key(|&(sink, _)| sink)
+                    .map(|(_, (_, path))| path)
+                    .unwrap();
+                &traced_path
+            }
+            None => return String::from("There are no tasks to explain."),
+        };
+        let path = critical_path
+            .iter()
+            .map(|task| task.as_ref())
+            .collect::<Vec<_>>()
+            .join("->");
+        let mut explanation = format!(
+            "The minimum completion time of {} is driven by the path {}",
+            self.minimum_completion_time, path
+        );
+        let dominant_task = critical_path
+            .iter()
+            .map(|&task| (task, self.task_durations[&task]))
+            .max_by(|(task1, duration1), (task2, duration2)| {
+                duration1.cmp(duration2).then(task2.cmp(task1))
+            });
+        match dominant_task {
+            Some((task, duration)) if duration > D::default() => {
+                explanation.push_str(&format!(
+                    ", where {} ({}) dominates.",
+                    task.as_ref(),
+                    duration.total()
+                ));
+            }
+            _ => explanation.push_str(", where no task takes any time."),
+        }
+        if self.critical_path_count > 1 {
+            explanation.push_str(&format!(
+                " It is one of {} critical paths.",
+                self.critical_path_count
+            ));
+        }
+        explanation
+    }
+
+    /// Number of tasks on the path with the most tasks, regardless of durations. It can exceed the
+    /// number of tasks on the critical paths when a short chain of long tasks dominates the
+    /// schedule. Computed on demand.
+    /// Time: O(V + E)
+    pub fn longest_chain_len(&self) -> usize {
+        // number of tasks on the longest chain ending at a task, including the task itself
+        let mut longest_chain_to_task = HashMap::new();
+        for &task in &self.topological_order {
+            longest_chain_to_task.entry(task).or_insert(1usize);
+            let chain_len = longest_chain_to_task[&task];
+            for &next_task in adjacent(&self.task_graph, &task) {
+                let next_chain_len = longest_chain_to_task.entry(next_task).or_insert(1);
+                *next_chain_len = (*next_chain_len).max(chain_len + 1);
+            }
+        }
+        longest_chain_to_task.values().cloned().max().unwrap_or(0)
+    }
+
+    /// Tasks grouped into waves for staged execution, where each task is in the wave following the
+    /// last wave holding one of the tasks it depends on. Tasks of a wave can all start once the
+    /// previous waves complete, regardless of durations. Tasks that don't depend on any task make
+    /// up the first wave, and the labels are sorted within each wave. Computed on demand.
+    /// Time: O(V * logV + E)
+    pub fn execution_waves(&self) -> Vec<Vec<TaskLabel<'a>>> {
+        let mut wave_of_task = HashMap::new();
+        let mut waves: Vec<Vec<TaskLabel<'a>>> = Vec::new();
+        for &task in &self.topological_order {
+            let wave_idx = *wave_of_task.entry(task).or_insert(0usize);
+            for &next_task in adjacent(&self.task_graph, &task) {
+                let next_wave_idx = wave_of_task.entry(next_task).or_insert(0);
+                *next_wave_idx = (*next_wave_idx).max(wave_idx + 1);
+            }
+            if waves.len() <= wave_idx {
+                waves.resize_with(wave_idx + 1, Vec::new);
+            }
+            waves[wave_idx].push(task);
+        }
+        waves.iter_mut().for_each(|wave| wave.sort_unstable());
+        waves
+    }
+
+    /// Paths from a source task to a sink task that take at least `minimum_completion_time`
+    /// minus `within` to complete. A `within` of 0 yields the critical paths. Paths taking
+    /// longer come first; paths of the same duration are ordered like the critical paths.
+    /// Time: O(P * V + V + E), where P is the number of reported paths, since every explored
+    ///       path prefix can still be completed within the threshold
+    /// Space: O(V) besides the reported paths
+    pub fn near_critical_paths(&self, within: Total<D>) -> Vec<Vec<TaskLabel<'a>>> {
+        let threshold = if within >= self.minimum_completion_time {
+            Total::<D>::default()
+        } else {
+            self.minimum_completion_time - within
+        };
+        let longest_duration_path_from_task = self.longest_duration_path_from_task();
+        let inverse_task_graph = invert(&self.task_graph);
+        let mut paths = Vec::new();
+        for &task in &self.topological_order {
+            let source_task = adjacent(&inverse_task_graph, &task).is_empty();
+            if source_task && longest_duration_path_from_task[&task] >= threshold {
+                self.extend_near_critical_paths(
+                    &longest_duration_path_from_task,
+                    threshold,
+                    &mut vec![task],
+                    self.task_durations[&task].total(),
+                    &mut paths,
+                );
+            }
+        }
+        paths.sort_unstable_by(|(duration1, path1), (duration2, path2)| {
+            duration2
+                .cmp(duration1)
+                .then(path2.len().cmp(&path1.len()))
+                .then(path1.iter().cmp(path2.iter()))
+        });
+        paths.into_iter().map(|(_, path)| path).collect()
+    }
+
+    // Depth-first extension of a path prefix, skipping the tasks through which the path cannot
+    // reach the threshold
+    fn extend_near_critical_paths(
+        &self,
+        longest_duration_path_from_task: &HashMap<TaskLabel<'a>, Total<D>>,
+        threshold: Total<D>,
         temp_path: &mut Vec<TaskLabel<'a>>,
-        destination: TaskLabel<'a>,
+        temp_path_duration: Total<D>,
+        paths: &mut Vec<(Total<D>, Vec<TaskLabel<'a>>)>,
     ) {
-        let reached_source = !parent_tasks.contains_key(&destination);
-        if reached_source {
-            {
-                let path_with_single_task = temp_path.is_empty();
-                if path_with_single_task {
-                    temp_path.push(destination);
+        let task = temp_path[temp_path.len() - 1];
+        let adjacent_tasks = adjacent(&self.task_graph, &task);
+        if adjacent_tasks.is_empty() {
+            paths.push((temp_path_duration, temp_path.clone()));
+            return;
+        }
+        for &next_task in adjacent_tasks {
+            let duration_to_next_task = temp_path_duration + self.edge_weight(task, next_task);
+            if duration_to_next_task + longest_duration_path_from_task[&next_task] >= threshold {
+                temp_path.push(next_task);
+                self.extend_near_critical_paths(
+                    longest_duration_path_from_task,
+                    threshold,
+                    temp_path,
+                    duration_to_next_task + self.task_durations[&next_task].total(),
+                    paths,
+                );
+                temp_path.pop(); // unwinding the stack
+            }
+        }
+    }
+
+    /// How much longer the critical paths take than the longest path that is not critical, i.e.,
+    /// how much the critical paths can be shortened before another path becomes critical. A small
+    /// margin means that shortening the critical paths barely speeds up the schedule. None when
+    /// every path from a source task to a sink task is critical.
+    /// Time: O(V + E)
+    pub fn critical_margin(&self) -> Option<Total<D>> {
+        // the two longest distinct durations of the paths leading to each task, longest first
+        let mut longest_durations: HashMap<TaskLabel<'a>, [Option<Total<D>>; 2]> = HashMap::new();
+        let mut longest_complete_durations = [None, None];
+        let inverse_task_graph = invert(&self.task_graph);
+        for &task in &self.topological_order {
+            let duration = self.task_durations[&task].total();
+            let mut durations = [None, None];
+            let previous_tasks = adjacent(&inverse_task_graph, &task);
+            if previous_tasks.is_empty() {
+                durations[0] = Some(duration);
+            }
+            for &previous_task in previous_tasks {
+                let weight = self.edge_weight(previous_task, task);
+                for &previous_duration in longest_durations[&previous_task].iter().flatten() {
+                    keep_longest_distinct(&mut durations, previous_duration + weight + duration);
                 }
             }
-            paths.push(temp_path.clone());
-        } else {
-            {
-                let is_sink_task = temp_path.is_empty();
-                if is_sink_task {
-                    temp_path.push(destination);
+            if adjacent(&self.task_graph, &task).is_empty() {
+                for &complete_duration in durations.iter().flatten() {
+                    keep_longest_distinct(&mut longest_complete_durations, complete_duration);
                 }
             }
-            for &task in &parent_tasks[&destination] {
-                temp_path.push(task);
-                CriticalPaths::construct_paths(parent_tasks, paths, temp_path, task);
-                temp_path.pop(); // unwinding the stack
+            longest_durations.insert(task, durations);
+        }
+        longest_complete_durations[1].map(|duration| self.minimum_completion_time - duration)
+    }
+
+    /// How much each task can slip without delaying the completion of the schedule, i.e., the
+    /// total slack found by a backward pass from the sink tasks. Critical tasks have no slack.
+    /// Time: O(V + E)
+    pub fn total_slack(&self) -> HashMap<TaskLabel<'a>, Total<D>> {
+        self.longest_duration_path_from_task()
+            .into_iter()
+            .map(|(task, longest_following_duration)| {
+                // the longest path going through the task, counting the task only once
+                let longest_path_duration = self.longest_duration_path_to_task[&task]
+                    + longest_following_duration
+                    - self.task_durations[&task].total();
+                (task, self.minimum_completion_time - longest_path_duration)
+            })
+            .collect()
+    }
+
+    /// How much each task can slip without delaying any of the tasks depending on it, i.e., the
+    /// gap between the task's earliest finish and the earliest start of its successors, net of
+    /// the weight of the relation. Tasks without successors can slip until the schedule
+    /// completes. Never more than the total slack, and critical tasks have no free slack.
+    /// Time: O(V + E)
+    pub fn free_slack(&self) -> HashMap<TaskLabel<'a>, Total<D>> {
+        let earliest_times = self.earliest_times();
+        earliest_times
+            .iter()
+            .map(|(&task, &(_, earliest_finish))| {
+                let earliest_successor_start = adjacent(&self.task_graph, &task)
+                    .iter()
+                    .map(|&next_task| {
+                        earliest_times[&next_task].0 - self.edge_weight(task, next_task)
+                    })
+                    .min()
+                    .unwrap_or(self.minimum_completion_time);
+                (task, earliest_successor_start - earliest_finish)
+            })
+            .collect()
+    }
+
+    /// Number of tasks per slack bucket. Buckets are given by their inclusive upper bounds in
+    /// ascending order, and tasks with more slack than the last bound are counted in an extra
+    /// bucket at the end. E.g., [0, 5, 20] counts the tasks with no slack, with a slack of 1 to 5,
+    /// of 6 to 20, and of more than 20.
+    /// Time: O(V * logB + E), where B is the number of buckets
+    pub fn slack_histogram(&self, buckets: &[Total<D>]) -> Vec<usize> {
+        let mut counts = vec![0usize; buckets.len() + 1];
+        for slack in self.total_slack().values() {
+            let bucket_idx = buckets.partition_point(|bound| bound < slack);
+            counts[bucket_idx] += 1;
+        }
+        counts
+    }
+
+    /// (earliest start, earliest finish) of each task, i.e., when the task can start and complete
+    /// if it starts as soon as the tasks it depends on complete
+    /// Time: O(V)
+    pub fn earliest_times(&self) -> HashMap<TaskLabel<'a>, (Total<D>, Total<D>)> {
+        self.longest_duration_path_to_task
+            .iter()
+            .map(|(&task, &finish)| (task, (finish - self.task_durations[&task].total(), finish)))
+            .collect()
+    }
+
+    /// (level, time) pairs telling when each number of tasks, up to the peak, first executes at
+    /// once, e.g., to provision workers gradually. Tasks execute from their earliest start until
+    /// their earliest finish, and tasks taking no time execute at the instant they start, so a
+    /// schedule made of such tasks reaches its peak at time 0. Since a task doesn't execute while
+    /// it waits for the weights of its relations to be paid, the peak can differ from the maximum
+    /// parallelism.
+    /// Time: O(V * logV)
+    pub fn first_time_at_parallelism(&self) -> Vec<(usize, Total<D>)> {
+        // (time, whether the task starts, whether the task takes no time), so that tasks
+        // completing at a time stop executing before the tasks starting at that time
+        let mut events = Vec::with_capacity(2 * self.task_durations.len());
+        for (start, finish) in self.earliest_times().into_values() {
+            events.push((start, true, start == finish));
+            if start != finish {
+                events.push((finish, false, false));
+            }
+        }
+        events.sort_unstable();
+
+        let mut first_times = Vec::new();
+        let mut executing_count = 0usize;
+        for events_at_time in events.chunk_by(|left, right| left.0 == right.0) {
+            let mut instant_count = 0;
+            for &(_, starts, takes_no_time) in events_at_time {
+                if starts {
+                    executing_count += 1;
+                    instant_count += usize::from(takes_no_time);
+                } else {
+                    executing_count -= 1;
+                }
+            }
+            let time = events_at_time[0].0;
+            while first_times.len() < executing_count {
+                first_times.push((first_times.len() + 1, time));
+            }
+            executing_count -= instant_count;
+        }
+        first_times
+    }
+
+    /// Length of the longest path starting at each source task, i.e., each task that doesn't
+    /// depend on another task. The longest of them is the minimum completion time.
+    /// Time: O(V + E)
+    pub fn makespan_by_source(&self) -> HashMap<TaskLabel<'a>, Total<D>> {
+        let has_preceding_task = self.task_graph.values().flatten().collect::<HashSet<_>>();
+        let mut longest_duration_path_from_task = self.longest_duration_path_from_task();
+        longest_duration_path_from_task.retain(|task, _| !has_preceding_task.contains(task));
+        longest_duration_path_from_task
+    }
+
+    /// When the last task completes once the tasks are assigned to the given number of workers by
+    /// `assign_to_workers`, which is never earlier than the minimum completion time. Zero without
+    /// workers.
+    /// Time: same as `assign_to_workers`
+    pub fn makespan_with_workers(&self, workers: usize) -> Total<D> {
+        self.assign_to_workers(workers)
+            .iter()
+            .flatten()
+            .map(|&(task, start)| start + self.task_durations[&task].total())
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Assigns the tasks to the given number of workers by list scheduling: whenever a worker is
+    /// idle, it picks the ready task with the longest path ahead of it, ties broken in favor of
+    /// the smallest label. Returns (task, start time) pairs per worker, in the order the worker
+    /// executes them. With as many workers as the maximum parallelism, tasks start as early as
+    /// they can.
+    /// Time: O(V^2 + E * logV)
+    pub fn assign_to_workers(&self, workers: usize) -> Vec<Vec<(TaskLabel<'a>, Total<D>)>> {
+        let mut assignments = vec![Vec::new(); workers];
+        if workers == 0 {
+            return assignments;
+        }
+        let longest_duration_path_from_task = self.longest_duration_path_from_task();
+        let mut preceding_task_count = HashMap::<_, usize>::new();
+        for &next_task in self.task_graph.values().flatten() {
+            *preceding_task_count.entry(next_task).or_default() += 1;
+        }
+        // (task, time it can start at) for the tasks whose preceding tasks completed
+        let mut ready_tasks = self
+            .topological_order
+            .iter()
+            .filter(|task| !preceding_task_count.contains_key(task))
+            .map(|&task| (task, Total::<D>::default()))
+            .collect::<Vec<_>>();
+        let mut earliest_starts = HashMap::new();
+        let mut idle_workers = (0..workers).rev().collect::<Vec<_>>();
+        let mut running_tasks = BinaryHeap::new();
+        let mut time = Total::<D>::default();
+        loop {
+            while !idle_workers.is_empty() {
+                let next_ready_task = ready_tasks
+                    .iter()
+                    .enumerate()
+                    .filter(|&(_, &(_, start))| start <= time)
+                    .max_by(|&(_, (task1, _)), &(_, (task2, _))| {
+                        longest_duration_path_from_task[task1]
+                            .cmp(&longest_duration_path_from_task[task2])
+                            .then(task2.cmp(task1))
+                    })
+                    .map(|(idx, _)| idx);
+                let task = match next_ready_task {
+                    Some(idx) => ready_tasks.swap_remove(idx).0,
+                    None => break,
+                };
+                let worker = idle_workers.pop().unwrap();
+                assignments[worker].push((task, time));
+                let finish = time + self.task_durations[&task].total();
+                running_tasks.push(Reverse((finish, task, worker)));
+            }
+            // idle workers wait for the next task to complete, or to become startable
+            let next_start = if idle_workers.is_empty() {
+                None
+            } else {
+                ready_tasks
+                    .iter()
+                    .map(|&(_, start)| start)
+                    .filter(|&start| start > time)
+                    .min()
+            };
+            let next_finish = running_tasks.peek().map(|Reverse((finish, _, _))| *finish);
+            time = match (next_start, next_finish) {
+                (Some(start), Some(finish)) => start.min(finish),
+                (Some(start), None) => start,
+                (None, Some(finish)) => finish,
+                (None, None) => break,
+            };
+            while let Some(&Reverse((finish, task, worker))) = running_tasks.peek() {
+                if finish != time {
+                    break;
+                }
+                running_tasks.pop();
+                idle_workers.push(worker);
+                for &next_task in adjacent(&self.task_graph, &task) {
+                    let start = earliest_starts
+                        .entry(next_task)
+                        .or_insert_with(Total::<D>::default);
+                    *start = (*start).max(finish + self.edge_weight(task, next_task));
+                    let count = preceding_task_count.get_mut(&next_task).unwrap();
+                    *count -= 1;
+                    if *count == 0 {
+                        ready_tasks.push((next_task, earliest_starts[&next_task]));
+                    }
+                }
             }
+            // lower-numbered workers pick tasks first
+            idle_workers.sort_unstable_by(|worker1, worker2| worker2.cmp(worker1));
+        }
+        assignments
+    }
+
+    // Backward pass computing the longest time spent, including the task's own duration, along
+    // the paths leaving a task
+    fn longest_duration_path_from_task(&self) -> HashMap<TaskLabel<'a>, Total<D>> {
+        let mut longest_duration_path_from_task = HashMap::new();
+        for &task in self.topological_order.iter().rev() {
+            let longest_following_duration = adjacent(&self.task_graph, &task)
+                .iter()
+                .map(|&next_task| {
+                    self.edge_weight(task, next_task) + longest_duration_path_from_task[&next_task]
+                })
+                .max()
+                .unwrap_or_default();
+            longest_duration_path_from_task.insert(
+                task,
+                self.task_durations[&task].total() + longest_following_duration,
+            );
         }
+        longest_duration_path_from_task
+    }
+
+    /// Time it takes to go through the given tasks one after another, including the weights of
+    /// the relations between them
+    pub fn path_duration(&self, path: &[TaskLabel<'a>]) -> Total<D> {
+        let task_durations = path
+            .iter()
+            .map(|task| self.task_durations[task].total())
+            .fold(Total::<D>::default(), |total, duration| total + duration);
+        path.windows(2)
+            .map(|pair| self.edge_weight(pair[0], pair[1]))
+            .fold(task_durations, |total, weight| total + weight)
+    }
+
+    fn edge_weight(&self, from_task: TaskLabel<'a>, to_task: TaskLabel<'a>) -> Total<D> {
+        self.edge_weights
+            .get(&(from_task, to_task))
+            .map_or_else(Total::<D>::default, |weight| weight.total())
     }
 }
 
-fn serialize_path(
-    path: &[TaskLabel],
-    buffer: &mut dyn Write,
-    delimiter: &str,
-    max_label_len: usize,
-) -> std::fmt::Result {
-    let delimiter_len = delimiter.chars().count();
-    let mut buffered_char_count = 0usize;
-    let max_allowed_line_len = max_label_len + delimiter_len;
+impl<'a> ScheduleAnalysis<'a> {
+    /// How many times faster the schedule completes with unlimited task-runners than with a single
+    /// one, i.e., the total duration of the tasks over the minimum completion time. None when the
+    /// schedule completes instantly.
+    /// Time: O(V)
+    pub fn ideal_speedup(&self) -> Option<f64> {
+        if self.minimum_completion_time == 0 {
+            return None;
+        }
+        Some(self.total_work() as f64 / f64::from(self.minimum_completion_time))
+    }
 
-    let mut line_buffer = String::new();
-    let mut label_idx = 0usize;
-    while label_idx < path.len() {
-        let task = path[label_idx];
-        let task_len = task.chars().count();
-        let required_space = task_len + delimiter_len;
-        if buffered_char_count + required_space <= max_allowed_line_len {
-            line_buffer.push_str(task.as_ref());
-            let not_last_label = label_idx != path.len() - 1;
-            if not_last_label {
-                line_buffer.push_str(delimiter);
+    /// Total duration of the tasks, i.e., how long the schedule takes with a single task-runner
+    /// Time: O(V)
+    pub fn total_work(&self) -> u64 {
+        self.task_durations
+            .values()
+            .map(|&duration| u64::from(duration))
+            .sum()
+    }
+
+    /// How well the given number of workers are kept busy until the schedule completes, i.e., the
+    /// total duration of the tasks over the time the workers are available for, which is 1 when
+    /// no worker ever idles. The schedule completes when the assignment of
+    /// `assign_to_workers` does. None without workers or when the schedule completes instantly.
+    /// Time: same as `assign_to_workers`
+    pub fn efficiency(&self, workers: usize) -> Option<f64> {
+        let makespan = self.makespan_with_workers(workers);
+        if workers == 0 || makespan == 0 {
+            return None;
+        }
+        Some(self.total_work() as f64 / (workers as f64 * f64::from(makespan)))
+    }
+}
+
+impl<'a, D: ScheduleDuration> std::fmt::Display for ScheduleAnalysis<'a, D> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.write_text(f, &|path, buffer| {
+            serialize_path(path, buffer, "->", TaskLabel::MAX_LEN)
+        })
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum AnalysisError<'a> {
+    EmptyInput,
+    MissingDurations(Vec<TaskLabel<'a>>),
+    MissingOrders(Vec<TaskLabel<'a>>),
+    Cycle,
+    /// the duration of a path leading to the task does not fit in the total duration type
+    DurationOverflow(TaskLabel<'a>),
+    /// the task is declared with different durations
+    ConflictingDurations(TaskLabel<'a>),
+    /// the task depends on itself
+    SelfDependency(TaskLabel<'a>),
+}
+
+impl<'a> StdError for AnalysisError<'a> {}
+
+impl<'a> fmt::Display for AnalysisError<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        format_analysis_error(self, f)
+    }
+}
+
+fn format_analysis_error<'a>(err: &AnalysisError<'a>, f: &mut fmt::Formatter) -> fmt::Result {
+    match err {
+        AnalysisError::EmptyInput => write!(f, "Input is empty"),
+        AnalysisError::MissingDurations(vec) => {
+            write!(
+                f,
+                "Schedule is missing durations for: {:?}",
+                vec.iter().map(|tl| tl.as_ref()).collect::<Vec<_>>()
+            )
+        }
+        AnalysisError::MissingOrders(vec) => {
+            write!(
+                f,
+                "Schedule is missing orders for: {:?}",
+                vec.iter().map(|tl| tl.as_ref()).collect::<Vec<_>>()
+            )
+        }
+        AnalysisError::Cycle => write!(f, "There's a cycle in the schedule"),
+        AnalysisError::DurationOverflow(task) => write!(
+            f,
+            "Duration of the paths leading to {} is too large",
+            task.as_ref()
+        ),
+        AnalysisError::ConflictingDurations(task) => {
+            write!(f, "Conflicting durations for task: {}", task.as_ref())
+        }
+        AnalysisError::SelfDependency(task) => {
+            write!(
+                f,
+                "Labels cannot have a dependency on themselves: {}",
+                task.as_ref()
+            )
+        }
+    }
+}
+
+/// Produces an analysis of provided task schedule
+/// Time: O((V+E)logV) for topological sorting that uses a binary heap to figure out
+///       maximum parallel task execution, where V is the number of
+///       tasks and E is the number of relations between tasks. However,
+///       using a fibonacci heap could bring the cost down to O(E + VlogV).
+///       See https://docs.rs/rudac/0.8.3/rudac/heap/struct.FibonacciHeap.html
+///       We need to add to this the additional cost of multiple critical path construction,
+///       which is ~O(N^M * M). See "construct_path" for more explanation on that.
+/// Space: O(V). Might end up adding all tasks to the queue at once
+#[allow(dead_code)]
+pub fn analyze_schedule<'a, D: ScheduleDuration>(
+    task_orders: &HashSet<TaskOrder<'a>>,
+    task_durations: &HashMap<TaskLabel<'a>, D>,
+) -> Result<ScheduleAnalysis<'a, D>, AnalysisError<'a>> {
+    analyze_weighted_schedule(task_orders, task_durations, &HashMap::new())
+}
+
+/// Same as `analyze_schedule`, except that the duration of each task is looked up through the
+/// given function rather than a map, e.g., to fetch durations from a database or to compute them.
+/// The function is called once per task. Tasks it returns None for are reported as
+/// `MissingDurations`. Since only the tasks of the orders are looked up, there is no such thing as
+/// a duration missing an order.
+#[allow(dead_code)]
+pub fn analyze_schedule_with<'a, D, F>(
+    task_orders: &HashSet<TaskOrder<'a>>,
+    duration_fn: F,
+) -> Result<ScheduleAnalysis<'a, D>, AnalysisError<'a>>
+where
+    D: ScheduleDuration,
+    F: Fn(TaskLabel<'a>) -> Option<D>,
+{
+    if task_orders.is_empty() {
+        return Err(AnalysisError::EmptyInput);
+    }
+    let started = Instant::now();
+    let graph = Graph::new(task_orders);
+    let task_durations = graph
+        .preceding_task_count
+        .keys()
+        .filter_map(|&task| duration_fn(task).map(|duration| (task, duration)))
+        .collect();
+    analyze_task_graph(
+        graph,
+        started.elapsed(),
+        &task_durations,
+        &HashMap::new(),
+        None,
+        None,
+        &mut (),
+    )
+}
+
+/// Same as `analyze_schedule`, except that the relations between tasks can carry a cost, such as
+/// the time it takes to transfer data from one task to the next. A task can only start once the
+/// cost of each relation leading to it is paid. Relations without a weight cost nothing.
+pub fn analyze_weighted_schedule<'a, D: ScheduleDuration>(
+    task_orders: &HashSet<TaskOrder<'a>>,
+    task_durations: &HashMap<TaskLabel<'a>, D>,
+    edge_weights: &HashMap<(TaskLabel<'a>, TaskLabel<'a>), D>,
+) -> Result<ScheduleAnalysis<'a, D>, AnalysisError<'a>> {
+    if task_orders.is_empty() && task_durations.is_empty() {
+        return Err(AnalysisError::EmptyInput);
+    }
+    let started = Instant::now();
+    let graph = Graph::new(task_orders);
+    analyze_task_graph(
+        graph,
+        started.elapsed(),
+        task_durations,
+        edge_weights,
+        None,
+        None,
+        &mut (),
+    )
+}
+
+/// Same as `analyze_weighted_schedule`, except that critical paths made up of a single task are
+/// ordered by the given task positions, e.g., the order the tasks are declared in, rather than
+/// by their labels. Tasks without a position come after the ones with a position.
+pub fn analyze_weighted_schedule_in_order<'a, D: ScheduleDuration>(
+    task_orders: &HashSet<TaskOrder<'a>>,
+    task_durations: &HashMap<TaskLabel<'a>, D>,
+    edge_weights: &HashMap<(TaskLabel<'a>, TaskLabel<'a>), D>,
+    task_positions: &HashMap<TaskLabel<'a>, usize>,
+) -> Result<ScheduleAnalysis<'a, D>, AnalysisError<'a>> {
+    if task_orders.is_empty() && task_durations.is_empty() {
+        return Err(AnalysisError::EmptyInput);
+    }
+    let started = Instant::now();
+    let graph = Graph::new(task_orders);
+    analyze_task_graph(
+        graph,
+        started.elapsed(),
+        task_durations,
+        edge_weights,
+        Some(task_positions),
+        None,
+        &mut (),
+    )
+}
+
+/// Same as `analyze_weighted_schedule`, except that critical paths are not listed when there are
+/// more than `max_critical_paths` of them, only counted. Listing them takes exponential time and
+/// space in the worst case, e.g., on a lattice of diamonds. Single-task critical paths are ordered
+/// by the given task positions, if any. See `analyze_weighted_schedule_in_order`.
+pub fn analyze_weighted_schedule_with_max_paths<'a, D: ScheduleDuration>(
+    task_orders: &HashSet<TaskOrder<'a>>,
+    task_durations: &HashMap<TaskLabel<'a>, D>,
+    edge_weights: &HashMap<(TaskLabel<'a>, TaskLabel<'a>), D>,
+    task_positions: Option<&HashMap<TaskLabel<'a>, usize>>,
+    max_critical_paths: usize,
+) -> Result<ScheduleAnalysis<'a, D>, AnalysisError<'a>> {
+    if task_orders.is_empty() && task_durations.is_empty() {
+        return Err(AnalysisError::EmptyInput);
+    }
+    let started = Instant::now();
+    let graph = Graph::new(task_orders);
+    analyze_task_graph(
+        graph,
+        started.elapsed(),
+        task_durations,
+        edge_weights,
+        task_positions,
+        Some(max_critical_paths),
+        &mut (),
+    )
+}
+
+/// Same as `analyze_weighted_schedule`, except that the visitor is called for each task, in
+/// topological order, as the task completes at its earliest finish time along with the number of
+/// tasks executing right before, e.g., to accumulate metrics under a custom cost model.
+#[allow(dead_code)]
+pub fn analyze_with_visitor<'a, D: ScheduleDuration>(
+    task_orders: &HashSet<TaskOrder<'a>>,
+    task_durations: &HashMap<TaskLabel<'a>, D>,
+    edge_weights: &HashMap<(TaskLabel<'a>, TaskLabel<'a>), D>,
+    visitor: &mut impl ScheduleVisitor<TaskLabel<'a>, Total<D>>,
+) -> Result<ScheduleAnalysis<'a, D>, AnalysisError<'a>> {
+    if task_orders.is_empty() && task_durations.is_empty() {
+        return Err(AnalysisError::EmptyInput);
+    }
+    let started = Instant::now();
+    let graph = Graph::new(task_orders);
+    analyze_task_graph(
+        graph,
+        started.elapsed(),
+        task_durations,
+        edge_weights,
+        None,
+        None,
+        visitor,
+    )
+}
+
+/// Same as `analyze_schedule`, except that the tasks and their relations are given as an
+/// adjacency map rather than task orders, which saves building the map from scratch. The map is
+/// taken over by the analysis rather than copied. Every task
+/// needs to appear either as a key or in the list of a key, and tasks that are not related to
+/// any other task are keys with an empty list. Lists are expected not to contain duplicates.
+#[allow(dead_code)]
+pub fn analyze_graph<'a, D: ScheduleDuration>(
+    adjacency: HashMap<TaskLabel<'a>, Vec<TaskLabel<'a>>>,
+    task_durations: &HashMap<TaskLabel<'a>, D>,
+) -> Result<ScheduleAnalysis<'a, D>, AnalysisError<'a>> {
+    analyze_weighted_graph(adjacency, task_durations, &HashMap::new())
+}
+
+/// Weighted counterpart of `analyze_graph`. See `analyze_weighted_schedule`.
+#[allow(dead_code)]
+pub fn analyze_weighted_graph<'a, D: ScheduleDuration>(
+    adjacency: HashMap<TaskLabel<'a>, Vec<TaskLabel<'a>>>,
+    task_durations: &HashMap<TaskLabel<'a>, D>,
+    edge_weights: &HashMap<(TaskLabel<'a>, TaskLabel<'a>), D>,
+) -> Result<ScheduleAnalysis<'a, D>, AnalysisError<'a>> {
+    if adjacency.is_empty() && task_durations.is_empty() {
+        return Err(AnalysisError::EmptyInput);
+    }
+    let started = Instant::now();
+    let graph = Graph::from_adjacency(adjacency);
+    analyze_task_graph(
+        graph,
+        started.elapsed(),
+        task_durations,
+        edge_weights,
+        None,
+        None,
+        &mut (),
+    )
+}
+
+/// Same as `analyze_weighted_schedule`, except that each weakly connected component of the
+/// schedule, i.e., each group of tasks related to each other directly or through other tasks, is
+/// analyzed on its own. Components are ordered by their smallest label.
+/// Summing up the maximum parallelism of the components overestimates the maximum parallelism of
+/// the schedule, since the components don't necessarily peak at the same time. The maximum
+/// parallelism of the schedule is the number of tasks executing across all the components at its
+/// busiest time, which only the analysis of the whole schedule tells.
+/// Time: O(V * logV + E) for partitioning the schedule, in addition to the analysis of each
+///       component
+#[allow(dead_code)]
+pub fn analyze_components<'a, D: ScheduleDuration>(
+    task_orders: &HashSet<TaskOrder<'a>>,
+    task_durations: &HashMap<TaskLabel<'a>, D>,
+    edge_weights: &HashMap<(TaskLabel<'a>, TaskLabel<'a>), D>,
+) -> Result<Vec<ScheduleAnalysis<'a, D>>, AnalysisError<'a>> {
+    analyze_partitioned_schedule(task_orders, task_durations, edge_weights, None, None)
+}
+
+// Components are analyzed with the given task positions and maximum number of critical paths to
+// list, see `analyze_weighted_schedule_with_max_paths`
+fn analyze_partitioned_schedule<'a, D: ScheduleDuration>(
+    task_orders: &HashSet<TaskOrder<'a>>,
+    task_durations: &HashMap<TaskLabel<'a>, D>,
+    edge_weights: &HashMap<(TaskLabel<'a>, TaskLabel<'a>), D>,
+    task_positions: Option<&HashMap<TaskLabel<'a>, usize>>,
+    max_critical_paths: Option<usize>,
+) -> Result<Vec<ScheduleAnalysis<'a, D>>, AnalysisError<'a>> {
+    if task_orders.is_empty() && task_durations.is_empty() {
+        return Err(AnalysisError::EmptyInput);
+    }
+    if let Some(problem) = validate_schedule(task_orders, task_durations)
+        .into_iter()
+        .next()
+    {
+        return Err(problem);
+    }
+
+    // relations in both directions
+    let mut related_tasks: HashMap<TaskLabel<'a>, Vec<TaskLabel<'a>>> = HashMap::new();
+    for order in task_orders {
+        let first_related_tasks = related_tasks.entry(order.first()).or_default();
+        if let Some(second) = order.second() {
+            first_related_tasks.push(second);
+            related_tasks.entry(second).or_default().push(order.first());
+        }
+    }
+    let mut tasks = related_tasks.keys().cloned().collect::<Vec<_>>();
+    tasks.sort_unstable();
+    let mut component_indices = HashMap::new();
+    let mut component_count = 0;
+    for task in tasks {
+        if component_indices.contains_key(&task) {
+            continue;
+        }
+        let mut stack = vec![task];
+        component_indices.insert(task, component_count);
+        while let Some(next_task) = stack.pop() {
+            for &related_task in &related_tasks[&next_task] {
+                if component_indices
+                    .insert(related_task, component_count)
+                    .is_none()
+                {
+                    stack.push(related_task);
+                }
             }
-            buffered_char_count += required_space;
-            label_idx += 1;
-        } else {
-            writeln!(buffer, "{}", line_buffer)?;
-            line_buffer.clear();
-            buffered_char_count = 0;
         }
+        component_count += 1;
+    }
+
+    let mut components = (0..component_count)
+        .map(|_| (HashSet::new(), HashMap::new(), HashMap::new()))
+        .collect::<Vec<_>>();
+    for order in task_orders {
+        components[component_indices[&order.first()]]
+            .0
+            .insert(order.clone());
+    }
+    for (task, &duration) in task_durations {
+        components[component_indices[task]]
+            .1
+            .insert(*task, duration);
+    }
+    for (relation, &weight) in edge_weights {
+        if let Some(&component_idx) = component_indices.get(&relation.0) {
+            components[component_idx].2.insert(*relation, weight);
+        }
+    }
+    components
+        .iter()
+        .map(|(task_orders, task_durations, edge_weights)| {
+            let started = Instant::now();
+            let graph = Graph::new(task_orders);
+            analyze_task_graph(
+                graph,
+                started.elapsed(),
+                task_durations,
+                edge_weights,
+                task_positions,
+                max_critical_paths,
+                &mut (),
+            )
+        })
+        .collect()
+}
+
+/// Minimum completion time of a weighted schedule, without finding its critical paths. Durations
+/// are added up along the paths with checked adds, so that a path taking longer than `Total`
+/// can hold results in `AnalysisError::DurationOverflow` rather than a wrapped value.
+#[allow(dead_code)]
+pub fn try_minimum_completion_time<'a, D: ScheduleDuration>(
+    task_orders: &HashSet<TaskOrder<'a>>,
+    task_durations: &HashMap<TaskLabel<'a>, D>,
+    edge_weights: &HashMap<(TaskLabel<'a>, TaskLabel<'a>), D>,
+) -> Result<Total<D>, AnalysisError<'a>> {
+    if task_orders.is_empty() && task_durations.is_empty() {
+        return Err(AnalysisError::EmptyInput);
+    }
+    let Graph {
+        task_graph,
+        preceding_task_count,
+    } = Graph::new(task_orders);
+    let analysis = sort_topologically(
+        &task_graph,
+        &preceding_task_count,
+        task_durations,
+        edge_weights,
+        &mut (),
+    )?;
+    Ok(analysis
+        .sink_tasks
+        .iter()
+        .map(|task| analysis.longest_duration_path_to_task[task])
+        .max()
+        .unwrap_or_default())
+}
+
+// FNV-1a, which unlike the standard library's hasher is specified, so fingerprints are stable
+// across platforms and releases
+const FINGERPRINT_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FINGERPRINT_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Deterministic fingerprint of a schedule, e.g., to cache analyses or to detect changes. Orders
+/// and durations are hashed in label order, so schedules that differ only in the order of their
+/// records, white space or comments share their fingerprint.
+/// Time: O(V * logV + E * logE)
+#[allow(dead_code)]
+pub fn fingerprint<D: ScheduleDuration>(
+    task_orders: &HashSet<TaskOrder>,
+    task_durations: &HashMap<TaskLabel, D>,
+) -> u64 {
+    let mut orders = task_orders
+        .iter()
+        .map(|order| (order.first(), order.second()))
+        .collect::<Vec<_>>();
+    orders.sort_unstable();
+    let mut durations = task_durations
+        .iter()
+        .map(|(&task, &duration)| (task, duration.total()))
+        .collect::<Vec<_>>();
+    durations.sort_unstable();
+
+    // labels can't contain the separators, so that distinct schedules have distinct texts
+    let mut canonical_text = String::new();
+    for (first, second) in orders {
+        match second {
+            Some(second) => writeln!(canonical_text, "{}->{}", first.as_ref(), second.as_ref()),
+            None => writeln!(canonical_text, "{}", first.as_ref()),
+        }
+        .unwrap();
+    }
+    for (task, duration) in durations {
+        writeln!(canonical_text, "{}({})", task.as_ref(), duration).unwrap();
+    }
+    canonical_text
+        .bytes()
+        .fold(FINGERPRINT_OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(FINGERPRINT_PRIME)
+        })
+}
+
+fn analyze_task_graph<'a, D: ScheduleDuration>(
+    graph: Graph<'a>,
+    graph_building_time: StdDuration,
+    task_durations: &HashMap<TaskLabel<'a>, D>,
+    edge_weights: &HashMap<(TaskLabel<'a>, TaskLabel<'a>), D>,
+    task_positions: Option<&HashMap<TaskLabel<'a>, usize>>,
+    max_critical_paths: Option<usize>,
+    visitor: &mut dyn ScheduleVisitor<TaskLabel<'a>, Total<D>>,
+) -> Result<ScheduleAnalysis<'a, D>, AnalysisError<'a>> {
+    let Graph {
+        task_graph,
+        preceding_task_count,
+    } = graph;
+    debug!("created task_graph: {:?}", task_graph);
+    debug!("created preceding_task_count: {:?}", preceding_task_count);
+    let topological_sort_started = Instant::now();
+    let schedule_core::CoreAnalysis {
+        topological_order,
+        max_parallelism,
+        longest_duration_path_to_task,
+        parent_tasks,
+        sink_tasks,
+    } = sort_topologically(
+        &task_graph,
+        &preceding_task_count,
+        task_durations,
+        edge_weights,
+        visitor,
+    )?;
+    let longest_duration_path_to_task = longest_duration_path_to_task
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+    let parent_tasks = parent_tasks.into_iter().collect::<HashMap<_, _>>();
+    let topological_sort_time = topological_sort_started.elapsed();
+
+    trace!("finding critical paths...");
+    let critical_paths_started = Instant::now();
+    let CriticalPaths {
+        paths: critical_paths,
+        count: critical_path_count,
+        duration: critical_path_duration,
+    } = CriticalPaths::find_critical_paths(
+        &parent_tasks,
+        &longest_duration_path_to_task,
+        &sink_tasks,
+        &topological_order,
+        task_positions,
+        max_critical_paths,
+    );
+    debug!("critical paths:{:?}", critical_paths);
+    let stage_timings = vec![
+        ("graph building", graph_building_time),
+        ("topological sort", topological_sort_time),
+        ("critical paths", critical_paths_started.elapsed()),
+    ];
+    debug!("stage timings: {:?}", stage_timings);
+    Ok(ScheduleAnalysis {
+        max_parallelism,
+        task_count: preceding_task_count.len(),
+        critical_path_count,
+        minimum_completion_time: critical_path_duration,
+        critical_paths,
+        task_graph,
+        preceding_task_count,
+        topological_order,
+        task_durations: task_durations.clone(),
+        edge_weights: edge_weights.clone(),
+        stage_timings,
+        parent_tasks,
+        longest_duration_path_to_task,
+        task_descriptions: HashMap::new(),
+        task_values: HashMap::new(),
+        milestones: HashSet::new(),
+        path_order: PathOrder::default(),
+        task_positions: task_positions.cloned(),
+        max_critical_paths,
+    })
+}
+
+/// Problems that keep a schedule from being analyzed, other than cycles, which only show up
+/// while sorting the tasks: tasks missing a duration, then durations of tasks missing an order.
+/// Unlike the analysis, which stops at the first problem, all of them are returned.
+/// Time: O(V * logV + E)
+pub fn validate_schedule<'a, D: ScheduleDuration>(
+    task_orders: &HashSet<TaskOrder<'a>>,
+    task_durations: &HashMap<TaskLabel<'a>, D>,
+) -> Vec<AnalysisError<'a>> {
+    let Graph {
+        preceding_task_count,
+        ..
+    } = Graph::new(task_orders);
+    validate_task_graph(&preceding_task_count, task_durations)
+}
+
+fn validate_task_graph<'a, D: ScheduleDuration>(
+    preceding_task_count: &HashMap<TaskLabel<'a>, usize>,
+    task_durations: &HashMap<TaskLabel<'a>, D>,
+) -> Vec<AnalysisError<'a>> {
+    let mut problems = Vec::new();
+    let mut missing = preceding_task_count
+        .keys()
+        .filter(|&task| !task_durations.contains_key(task))
+        .cloned()
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        missing.sort_unstable();
+        problems.push(AnalysisError::MissingDurations(missing));
+    }
+
+    let mut missing = task_durations
+        .keys()
+        .filter(|&task| !preceding_task_count.contains_key(task))
+        .cloned()
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        missing.sort_unstable();
+        problems.push(AnalysisError::MissingOrders(missing));
+    }
+    problems
+}
+
+// Rejects tasks missing a duration or an order before sorting the tasks topologically
+fn sort_topologically<'a, D: ScheduleDuration>(
+    task_graph: &HashMap<TaskLabel<'a>, Vec<TaskLabel<'a>>>,
+    preceding_task_count: &HashMap<TaskLabel<'a>, usize>,
+    task_durations: &HashMap<TaskLabel<'a>, D>,
+    edge_weights: &HashMap<(TaskLabel<'a>, TaskLabel<'a>), D>,
+    visitor: &mut dyn ScheduleVisitor<TaskLabel<'a>, Total<D>>,
+) -> Result<schedule_core::CoreAnalysis<TaskLabel<'a>, Total<D>>, AnalysisError<'a>> {
+    if let Some(problem) = validate_task_graph(preceding_task_count, task_durations)
+        .into_iter()
+        .next()
+    {
+        return Err(problem);
+    }
+
+    // the core only knows about ordered maps
+    schedule_core::analyze_with_visitor(
+        &task_graph
+            .iter()
+            .map(|(&task, next_tasks)| (task, next_tasks.clone()))
+            .collect(),
+        &task_durations
+            .iter()
+            .map(|(&task, duration)| (task, duration.total()))
+            .collect(),
+        &edge_weights
+            .iter()
+            .map(|(&relation, weight)| (relation, weight.total()))
+            .collect(),
+        visitor,
+    )
+    .map_err(|err| match err {
+        schedule_core::CoreError::MissingDuration(task) => {
+            AnalysisError::MissingDurations(vec![task])
+        }
+        schedule_core::CoreError::Cycle => AnalysisError::Cycle,
+        schedule_core::CoreError::DurationOverflow(task) => AnalysisError::DurationOverflow(task),
+    })
+}
+
+#[derive(Debug)]
+struct Graph<'a> {
+    task_graph: HashMap<TaskLabel<'a>, Vec<TaskLabel<'a>>>, // task -> neighbors
+    preceding_task_count: HashMap<TaskLabel<'a>, usize>,    // task -> number of preceding tasks
+}
+
+impl<'a> Graph<'a> {
+    fn new(orders: &HashSet<TaskOrder<'a>>) -> Self {
+        let mut preceding_task_count = HashMap::new(); // aka, preceding_edge_count
+        let mut task_graph = HashMap::new();
+        for task_order in orders {
+            // make sure all nodes/tasks have an "incoming edge"/"preceding task" count,
+            // including the sources at the head of the graph
+            preceding_task_count
+                .entry(task_order.first())
+                .or_insert(0usize);
+            let adj_list = task_graph
+                .entry(task_order.first())
+                .or_insert_with(Vec::new);
+            task_order.second().iter().for_each(|&second| {
+                adj_list.push(second);
+                *preceding_task_count.entry(second).or_insert(0usize) += 1;
+            });
+        }
+        Graph {
+            task_graph,
+            preceding_task_count,
+        }
+    }
+
+    fn from_adjacency(adjacency: HashMap<TaskLabel<'a>, Vec<TaskLabel<'a>>>) -> Self {
+        let mut preceding_task_count = HashMap::new();
+        for (&task, adjacent_tasks) in &adjacency {
+            preceding_task_count.entry(task).or_insert(0usize);
+            for &adjacent_task in adjacent_tasks {
+                *preceding_task_count.entry(adjacent_task).or_insert(0usize) += 1;
+            }
+        }
+        Graph {
+            task_graph: adjacency,
+            preceding_task_count,
+        }
+    }
+}
+
+// Paths with more tasks should come first because they provide more opportunities for
+// optimization. Else, we defer to lexicographical ordering, or to the given task positions for
+// paths with a single task.
+fn compare_paths<'a>(
+    path1: &[TaskLabel<'a>],
+    path2: &[TaskLabel<'a>],
+    task_positions: Option<&HashMap<TaskLabel<'a>, usize>>,
+) -> Ordering {
+    let position = |task: &TaskLabel<'a>| {
+        task_positions.map(|positions| positions.get(task).cloned().unwrap_or(usize::MAX))
+    };
+    let single_task_paths = path1.len() == 1 && path2.len() == 1;
+    path2
+        .len()
+        .cmp(&path1.len())
+        .then_with(|| {
+            if single_task_paths {
+                position(&path1[0]).cmp(&position(&path2[0]))
+            } else {
+                Ordering::Equal
+            }
+        })
+        .then(path1.iter().cmp(path2.iter()))
+}
+
+#[derive(Debug)]
+struct CriticalPaths<'a, T> {
+    // empty when there are too many paths to list
+    paths: Vec<Vec<TaskLabel<'a>>>,
+    count: usize,
+    duration: T,
+}
+
+impl<'a, T: Copy + Ord + Default + fmt::Debug> CriticalPaths<'a, T> {
+    // If there are multiple CPs, the ones that have more tasks on them come before in order.
+    // Else, we defer to lexicographical order of paths' task labels.
+
+    fn find_critical_paths(
+        parent_tasks: &HashMap<TaskLabel<'a>, Vec<TaskLabel<'a>>>,
+        longest_duration_path_to_task: &HashMap<TaskLabel<'a>, T>,
+        sink_tasks: &[TaskLabel<'a>],
+        topological_order: &[TaskLabel<'a>],
+        task_positions: Option<&HashMap<TaskLabel<'a>, usize>>,
+        max_paths: Option<usize>,
+    ) -> Self {
+        debug!("parent_tasks: {:?}", parent_tasks);
+        debug!(
+            "longest_duration_path_to_task: {:?}",
+            longest_duration_path_to_task
+        );
+        debug!("sink_tasks: {:?}", sink_tasks);
+        let critical_path_duration = sink_tasks
+            .iter()
+            .map(|task| longest_duration_path_to_task[task])
+            .max()
+            .unwrap_or_default();
+        let critical_sink_tasks = sink_tasks
+            .iter()
+            .filter(|&task| longest_duration_path_to_task[task] == critical_path_duration)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        // Listing the paths takes exponential time in the worst case, unlike counting them
+        if let Some(max_paths) = max_paths {
+            let path_count =
+                Self::count_paths(parent_tasks, topological_order, &critical_sink_tasks);
+            if path_count > max_paths as u128 {
+                debug!("skipped listing {} critical paths", path_count);
+                return CriticalPaths {
+                    paths: Vec::new(),
+                    count: usize::try_from(path_count).unwrap_or(usize::MAX),
+                    duration: critical_path_duration,
+                };
+            }
+        }
+
+        // Derive CPs from each sink task
+        let mut critical_paths = critical_sink_tasks
+            .into_iter()
+            .flat_map(|task| {
+                let mut paths = Vec::new();
+                Self::construct_paths(parent_tasks, &mut paths, &mut Vec::new(), task);
+                paths.iter_mut().for_each(|path| path.reverse());
+                paths
+            })
+            .collect::<Vec<_>>();
+
+        critical_paths.sort_unstable_by(|path1, path2| compare_paths(path1, path2, task_positions));
+        // There cannot be duplicate critical paths unless a task is listed as a parent more than
+        // once. Collapsing them keeps the output sane should that ever happen.
+        let path_count = critical_paths.len();
+        critical_paths.dedup();
+        if critical_paths.len() != path_count {
+            debug!(
+                "collapsed {} duplicate critical paths",
+                path_count - critical_paths.len()
+            );
+        }
+        CriticalPaths {
+            count: critical_paths.len(),
+            paths: critical_paths,
+            duration: critical_path_duration,
+        }
+    }
+
+    // Number of paths leading to the given tasks along the parent tasks, which are expected to
+    // come before the tasks they precede in the topological order. Saturates at u128::MAX.
+    // Time: O(V + E)
+    fn count_paths(
+        parent_tasks: &HashMap<TaskLabel<'a>, Vec<TaskLabel<'a>>>,
+        topological_order: &[TaskLabel<'a>],
+        tasks: &[TaskLabel<'a>],
+    ) -> u128 {
+        let mut path_count_to_task = HashMap::with_capacity(topological_order.len());
+        for task in topological_order {
+            let path_count = match parent_tasks.get(task) {
+                Some(parents) => parents
+                    .iter()
+                    .enumerate()
+                    // duplicate parents do not lead to more paths
+                    .filter(|&(idx, parent)| !parents[..idx].contains(parent))
+                    .map(|(_, parent)| path_count_to_task[parent])
+                    .fold(0u128, u128::saturating_add),
+                None => 1,
+            };
+            path_count_to_task.insert(*task, path_count);
+        }
+        tasks
+            .iter()
+            .map(|task| path_count_to_task[task])
+            .fold(0, u128::saturating_add)
+    }
+
+    // Time: O(n^m * m), where n is max_len(parent_tasks.values()) and m is the total number of
+    //       tasks on the CP. "*m" comes from path additions while cloning
+    // Space: O(m) for stack space
+    fn construct_paths(
+        parent_tasks: &HashMap<TaskLabel<'a>, Vec<TaskLabel<'a>>>,
+        paths: &mut Vec<Vec<TaskLabel<'a>>>,
+        temp_path: &mut Vec<TaskLabel<'a>>,
+        destination: TaskLabel<'a>,
+    ) {
+        let reached_source = !parent_tasks.contains_key(&destination);
+        if reached_source {
+            {
+                let path_with_single_task = temp_path.is_empty();
+                if path_with_single_task {
+                    temp_path.push(destination);
+                }
+            }
+            paths.push(temp_path.clone());
+        } else {
+            {
+                let is_sink_task = temp_path.is_empty();
+                if is_sink_task {
+                    temp_path.push(destination);
+                }
+            }
+            for &task in &parent_tasks[&destination] {
+                temp_path.push(task);
+                Self::construct_paths(parent_tasks, paths, temp_path, task);
+                temp_path.pop(); // unwinding the stack
+            }
+        }
+    }
+}
+
+fn adjacent<'a, 'g>(
+    graph: &'g HashMap<TaskLabel<'a>, Vec<TaskLabel<'a>>>,
+    task: &TaskLabel<'a>,
+) -> &'g [TaskLabel<'a>] {
+    graph.get(task).map_or(&[], |tasks| tasks.as_slice())
+}
+
+// Reverses the direction of every relation, mapping each task to the tasks preceding it
+fn invert<'a>(
+    graph: &HashMap<TaskLabel<'a>, Vec<TaskLabel<'a>>>,
+) -> HashMap<TaskLabel<'a>, Vec<TaskLabel<'a>>> {
+    let mut inverse_graph = HashMap::new();
+    for (&from_task, adjacent_tasks) in graph {
+        inverse_graph.entry(from_task).or_insert_with(Vec::new);
+        for &to_task in adjacent_tasks {
+            inverse_graph
+                .entry(to_task)
+                .or_insert_with(Vec::new)
+                .push(from_task);
+        }
+    }
+    inverse_graph
+}
+
+/// Longest time spent, including the task's own duration, along the paths leading to each task.
+/// Computed independently of the topological sort by a memoized depth-first search over the
+/// preceding tasks, so that the two can be checked against each other. Assumes the graph is
+/// acyclic.
+/// Time: O(V + E)
+/// Space: O(V). Recursion goes as deep as the number of tasks on the longest path
+#[allow(dead_code)]
+fn longest_path_dfs<'a, D: ScheduleDuration>(
+    task_graph: &HashMap<TaskLabel<'a>, Vec<TaskLabel<'a>>>,
+    task_durations: &HashMap<TaskLabel<'a>, D>,
+    edge_weights: &HashMap<(TaskLabel<'a>, TaskLabel<'a>), D>,
+) -> HashMap<TaskLabel<'a>, Total<D>> {
+    fn visit<'a, D: ScheduleDuration>(
+        task: TaskLabel<'a>,
+        inverse_task_graph: &HashMap<TaskLabel<'a>, Vec<TaskLabel<'a>>>,
+        task_durations: &HashMap<TaskLabel<'a>, D>,
+        edge_weights: &HashMap<(TaskLabel<'a>, TaskLabel<'a>), D>,
+        longest_duration_path_to_task: &mut HashMap<TaskLabel<'a>, Total<D>>,
+    ) -> Total<D> {
+        if let Some(&duration) = longest_duration_path_to_task.get(&task) {
+            return duration;
+        }
+        let mut longest_preceding_duration = Total::<D>::default();
+        for &previous_task in adjacent(inverse_task_graph, &task) {
+            let edge_weight = edge_weights
+                .get(&(previous_task, task))
+                .map_or_else(Total::<D>::default, |weight| weight.total());
+            let preceding_duration = visit(
+                previous_task,
+                inverse_task_graph,
+                task_durations,
+                edge_weights,
+                longest_duration_path_to_task,
+            ) + edge_weight;
+            longest_preceding_duration = longest_preceding_duration.max(preceding_duration);
+        }
+        let duration = longest_preceding_duration + task_durations[&task].total();
+        longest_duration_path_to_task.insert(task, duration);
+        duration
+    }
+
+    let inverse_task_graph = invert(task_graph);
+    let mut longest_duration_path_to_task = HashMap::new();
+    for &task in inverse_task_graph.keys() {
+        visit(
+            task,
+            &inverse_task_graph,
+            task_durations,
+            edge_weights,
+            &mut longest_duration_path_to_task,
+        );
+    }
+    longest_duration_path_to_task
+}
+
+// Walks up the dominator tree from both tasks until they meet, None standing for the root
+fn nearest_common_dominator<'a>(
+    dominators: &HashMap<TaskLabel<'a>, (Option<TaskLabel<'a>>, usize)>,
+    mut task1: Option<TaskLabel<'a>>,
+    mut task2: Option<TaskLabel<'a>>,
+) -> Option<TaskLabel<'a>> {
+    let depth = |task: Option<TaskLabel<'a>>| task.map_or(0, |task| dominators[&task].1);
+    while task1 != task2 {
+        if depth(task1) >= depth(task2) {
+            task1 = task1.and_then(|task| dominators[&task].0);
+        } else {
+            task2 = task2.and_then(|task| dominators[&task].0);
+        }
+    }
+    task1
+}
+
+// Keeps the two longest distinct values seen so far, longest first
+fn keep_longest_distinct<T: Ord + Copy>(longest: &mut [Option<T>; 2], value: T) {
+    if Some(value) > longest[0] {
+        longest[1] = longest[0];
+        longest[0] = Some(value);
+    } else if Some(value) < longest[0] && Some(value) > longest[1] {
+        longest[1] = Some(value);
+    }
+}
+
+// Tasks that can be reached from the given task, excluding the task itself
+// Time: O(V + E)
+fn reachable_tasks<'a>(
+    graph: &HashMap<TaskLabel<'a>, Vec<TaskLabel<'a>>>,
+    task: TaskLabel<'a>,
+) -> HashSet<TaskLabel<'a>> {
+    let mut reachable = HashSet::new();
+    let mut stack = adjacent(graph, &task).to_vec();
+    while let Some(next_task) = stack.pop() {
+        if reachable.insert(next_task) {
+            stack.extend_from_slice(adjacent(graph, &next_task));
+        }
+    }
+    reachable
+}
+
+/// Keeps the first and the last tasks of a path with more than `max_depth` tasks, with an
+/// ellipsis standing in for the tasks in between, e.g., `A->B->...->Y->Z` for a depth of 4. The
+/// first half gets the extra task when the depth is odd.
+pub fn elide_path<'p, 'a>(
+    path: &'p [TaskLabel<'a>],
+    max_depth: Option<usize>,
+) -> Cow<'p, [TaskLabel<'a>]> {
+    match max_depth {
+        Some(max_depth) if path.len() > max_depth => {
+            let tail_len = max_depth / 2;
+            let mut elided_path = path[..max_depth - tail_len].to_vec();
+            elided_path.push(TaskLabel::new("..."));
+            elided_path.extend_from_slice(&path[path.len() - tail_len..]);
+            Cow::Owned(elided_path)
+        }
+        _ => Cow::Borrowed(path),
+    }
+}
+
+/// Writes the path on as many lines as needed for each line to hold at most `max_label_len`
+/// characters, not counting the delimiters. Labels longer than that are written whole on their
+/// own line.
+pub fn serialize_path(
+    path: &[TaskLabel],
+    buffer: &mut dyn Write,
+    delimiter: &str,
+    max_label_len: usize,
+) -> std::fmt::Result {
+    write_path(path, buffer, delimiter, max_label_len, &|_| None)
+}
+
+/// Same as `serialize_path`, except that each label is followed by the duration of its task in
+/// parentheses, e.g., A(5)->C(9). The durations count towards the length of the lines. Labels
+/// without a duration, such as the ellipsis of an elided path, are written as they are.
+pub fn serialize_path_with_durations<'a, D: ScheduleDuration>(
+    path: &[TaskLabel<'a>],
+    task_durations: &HashMap<TaskLabel<'a>, D>,
+    buffer: &mut dyn Write,
+    delimiter: &str,
+    max_label_len: usize,
+) -> std::fmt::Result {
+    write_path(path, buffer, delimiter, max_label_len, &|task| {
+        task_durations
+            .get(&task)
+            .map(|duration| format!("({})", duration.total()))
+    })
+}
+
+// Labels are followed by their annotation, if any, which is never truncated
+fn write_path<'a>(
+    path: &[TaskLabel<'a>],
+    buffer: &mut dyn Write,
+    delimiter: &str,
+    max_label_len: usize,
+    annotate: &dyn Fn(TaskLabel<'a>) -> Option<String>,
+) -> std::fmt::Result {
+    let delimiter_len = delimiter.chars().count();
+    let mut buffered_char_count = 0usize;
+    let max_allowed_line_len = max_label_len + delimiter_len;
+
+    let mut line_buffer = String::new();
+    let mut label_idx = 0usize;
+    while label_idx < path.len() {
+        let task = path[label_idx];
+        let annotation = annotate(task).unwrap_or_default();
+        let annotation_len = annotation.chars().count();
+        let task_len = task.chars().count() + annotation_len;
+        let required_space = task_len + delimiter_len;
+        // a label that doesn't fit on a line of its own goes on an empty line regardless, as
+        // flushing the empty line would not make room for it
+        if buffered_char_count + required_space <= max_allowed_line_len || line_buffer.is_empty() {
+            line_buffer.push_str(task.as_ref());
+            line_buffer.push_str(&annotation);
+            let not_last_label = label_idx != path.len() - 1;
+            if not_last_label {
+                line_buffer.push_str(delimiter);
+            }
+            buffered_char_count += task_len + delimiter_len;
+            label_idx += 1;
+        } else {
+            writeln!(buffer, "{}", line_buffer)?;
+            line_buffer.clear();
+            buffered_char_count = 0;
+        }
+    }
+    // flush out the remaining
+    writeln!(buffer, "{}", line_buffer)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::task::{TaskLabel, TaskRelation, TotalDuration};
+    use quickcheck::TestResult;
+    use std::convert::TryFrom;
+    use util::*;
+
+    #[test]
+    fn single_task_path_schedules() {
+        // single-task path
+        let ords = &["A".node()];
+        let durs = &[("A", 2)];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(analysis.max_parallelism, 1);
+        assert_eq!(analysis.task_count, 1);
+        assert_eq!(analysis.minimum_completion_time, 2);
+        assert_eq!(analysis.critical_path_count, 1);
+        assert_eq!(analysis.critical_paths, paths(&["A"]));
+
+        // two single-task paths
+        let ords = &["A".node(), "B".node()];
+        let durs = &[("A", 2), ("B", 3)];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(analysis.max_parallelism, 2);
+        assert_eq!(analysis.task_count, 2);
+        assert_eq!(analysis.minimum_completion_time, 3);
+        assert_eq!(analysis.critical_path_count, 1);
+        assert_eq!(analysis.critical_paths, paths(&["B"]));
+
+        // three paths, two of which are a single-task path
+        // A
+        // B
+        // D -> L
+        let ords = &["A".node(), "B".node(), "D".arrow("L")];
+        let durs = &[("A", 2), ("B", 3), ("D", 7), ("L", 1)];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(analysis.max_parallelism, 3);
+        assert_eq!(analysis.task_count, 4);
+        assert_eq!(analysis.minimum_completion_time, 8);
+        assert_eq!(analysis.critical_path_count, 1);
+        assert_eq!(analysis.critical_paths, paths(&["D->L"]));
+    }
+
+    #[test]
+    fn multiple_sources_and_multiple_sinks_path_schedules() {
+        // A -> C
+        // B -> D
+        let ords = &["A".arrow("C"), "B".arrow("D")];
+        let durs = &[("A", 5u16), ("B", 1), ("C", 9), ("D", 7)];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(analysis.max_parallelism, 2);
+        assert_eq!(analysis.task_count, 4);
+        assert_eq!(analysis.minimum_completion_time, 14);
+        assert_eq!(analysis.critical_path_count, 1);
+        assert_eq!(analysis.critical_paths, paths(&["A->C"]));
+
+        // A -> C
+        // B -> D
+        let ords = &["A".arrow("C"), "B".arrow("D")];
+        let durs = &[("A", 5u16), ("B", 7), ("C", 9), ("D", 8)];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(analysis.max_parallelism, 2);
+        assert_eq!(analysis.task_count, 4);
+        assert_eq!(analysis.minimum_completion_time, 15);
+        assert_eq!(analysis.critical_path_count, 1);
+        assert_eq!(analysis.critical_paths, paths(&["B->D"]));
+    }
+
+    #[test]
+    fn report_accurate_parallelism_as_time_progresses() {
+        //                /--> D
+        //               /
+        //  A --> B --> C --> E
+        //              \
+        //               \--> F
+        //  K
+        let ords = &[
+            "A".arrow("B"),
+            "B".arrow("C"),
+            "C".arrow("D"),
+            "C".arrow("E"),
+            "C".arrow("F"),
+            "K".node(),
+        ];
+        let durs = &[
+            ("A", 1u16),
+            ("B", 1),
+            ("C", 1),
+            ("D", 1),
+            ("E", 1),
+            ("F", 1),
+            ("K", 4),
+        ];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(
+            analysis.max_parallelism, 4,
+            "finding tasks D, E, F, K running together at the 4th \"tick\" requires 4 task-runners"
+        );
+        assert_eq!(analysis.task_count, 7);
+        assert_eq!(analysis.minimum_completion_time, 4);
+        assert_eq!(analysis.critical_path_count, 4);
+        assert_eq!(
+            analysis.critical_paths,
+            paths(&["A->B->C->D", "A->B->C->E", "A->B->C->F", "K"])
+        );
+        assert_eq!(
+            analysis.first_time_at_parallelism(),
+            vec![(1, 0), (2, 0), (3, 3), (4, 3)],
+            "the 4th task-runner is needed once C completes"
+        );
+
+        let ords = &[
+            "A".arrow("B"),
+            "B".arrow("C"),
+            "C".arrow("D"),
+            "C".arrow("E"),
+            "C".arrow("F"),
+            "K".node(),
+        ];
+        let durs = &[
+            ("A", 1u16),
+            ("B", 1),
+            ("C", 1),
+            ("D", 1),
+            ("E", 1),
+            ("F", 1),
+            ("K", 3),
+        ];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(
+            analysis.max_parallelism, 3,
+            "K finishes before we get to execute D, E, F at the 4th tick, thus at most 3 task-runners needed"
+        );
+        assert_eq!(analysis.task_count, 7);
+        assert_eq!(analysis.minimum_completion_time, 4);
+        assert_eq!(analysis.critical_path_count, 3);
+        assert_eq!(
+            analysis.critical_paths,
+            paths(&["A->B->C->D", "A->B->C->E", "A->B->C->F"])
+        );
+
+        let ords = &["A".arrow("B"), "A".arrow("C"), "K".node()];
+        let durs = &[("A", 0u16), ("B", 0), ("C", 0), ("K", 0)];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(
+            analysis.max_parallelism, 2,
+            "K completes before A frees up B and C, as K is followed by fewer tasks"
+        );
+        assert_eq!(analysis.task_count, 4);
+        assert_eq!(analysis.minimum_completion_time, 0);
+        assert_eq!(analysis.critical_path_count, 3);
+        assert_eq!(analysis.critical_paths, paths(&["A->B", "A->C", "K"]));
+    }
+
+    #[test]
+    fn single_source_and_multiple_sinks_path_schedules() {
+        //    /--> L -> Z
+        //   /
+        //  K
+        //   \
+        //    \--> T -> F
+        let ords = &[
+            "K".arrow("L"),
+            "K".arrow("T"),
+            "L".arrow("Z"),
+            "T".arrow("F"),
+        ];
+        let durs = &[("K", 1u16), ("L", 12), ("Z", 1), ("T", 5), ("F", 20)];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(analysis.max_parallelism, 2);
+        assert_eq!(analysis.task_count, 5);
+        assert_eq!(analysis.minimum_completion_time, 26);
+        assert_eq!(analysis.critical_path_count, 1);
+        assert_eq!(analysis.critical_paths, paths(&["K->T->F"]));
+        assert_eq!(
+            analysis.explain(),
+            "The minimum completion time of 26 is driven by the path K->T->F, where F (20) dominates."
+        );
+
+        // All CPs have equal duration, lexicographically smaller ones come
+        // first in order in the result set.
+        //    /--> B -> D ->- >H
+        //   /     \        /
+        //  A       > --- >F         -> I
+        //   \     /                /
+        //    \--> C -> G -------->
+        let ords = &[
+            "A".arrow("B"),
+            "A".arrow("C"),
+            "B".arrow("D"),
+            "B".arrow("F"),
+            "C".arrow("F"),
+            "C".arrow("G"),
+            "F".arrow("H"),
+            "D".arrow("H"),
+            "G".arrow("I"),
+        ];
+        let durs = &[
+            ("A", 1u16),
+            ("B", 1),
+            ("C", 1),
+            ("D", 1),
+            ("F", 1),
+            ("H", 1),
+            ("G", 1),
+            ("I", 1),
+        ];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(analysis.max_parallelism, 3);
+        assert_eq!(analysis.task_count, 8);
+        assert_eq!(analysis.minimum_completion_time, 4);
+        assert_eq!(analysis.critical_path_count, 4);
+        assert_eq!(
+            analysis.critical_paths,
+            paths(&["A->B->D->H", "A->B->F->H", "A->C->F->H", "A->C->G->I"])
+        );
+
+        // All CPs have equal duration, lexicographically smaller ones come first.
+        //    /--> B -> D ->- >H
+        //   /     \        /
+        //  A       > --- >F --->---> I
+        //   \     /                /
+        //    \--> C -> G -------->
+        let ords = &[
+            "A".arrow("B"),
+            "A".arrow("C"),
+            "B".arrow("D"),
+            "B".arrow("F"),
+            "C".arrow("F"),
+            "C".arrow("G"),
+            "F".arrow("H"),
+            "D".arrow("H"),
+            "G".arrow("I"),
+            "F".arrow("I"),
+        ];
+        let durs = &[
+            ("A", 1u16),
+            ("B", 1),
+            ("C", 1),
+            ("D", 1),
+            ("F", 1),
+            ("H", 1),
+            ("G", 1),
+            ("I", 1),
+        ];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(analysis.max_parallelism, 3);
+        assert_eq!(analysis.task_count, 8);
+        assert_eq!(analysis.minimum_completion_time, 4);
+        assert_eq!(analysis.critical_path_count, 6);
+        assert_eq!(
+            analysis.critical_paths,
+            paths(&[
+                "A->B->D->H",
+                "A->B->F->H",
+                "A->B->F->I",
+                "A->C->F->H",
+                "A->C->F->I",
+                "A->C->G->I"
+            ])
+        );
+
+        // All CPs have equal duration.
+        //    /--> B -> D ->- >H
+        //   /     \        /
+        //  A       > --- >F --->---> I --> K
+        //   \     /                /
+        //    \--> C -> G -------->
+        let ords = &[
+            "A".arrow("B"),
+            "A".arrow("C"),
+            "B".arrow("D"),
+            "B".arrow("F"),
+            "C".arrow("F"),
+            "C".arrow("G"),
+            "F".arrow("H"),
+            "D".arrow("H"),
+            "G".arrow("I"),
+            "F".arrow("I"),
+            "I".arrow("K"),
+        ];
+        let durs = &[
+            ("A", 1u16),
+            ("B", 1),
+            ("C", 1),
+            ("D", 1),
+            ("F", 1),
+            ("H", 1),
+            ("G", 1),
+            ("I", 1),
+            ("K", 0),
+        ];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(analysis.max_parallelism, 3);
+        assert_eq!(analysis.task_count, 9);
+        assert_eq!(analysis.minimum_completion_time, 4);
+        assert_eq!(analysis.critical_path_count, 6);
+        assert_eq!(
+            analysis.critical_paths,
+            paths(&[
+                "A->B->F->I->K",
+                "A->C->F->I->K",
+                "A->C->G->I->K",
+                "A->B->D->H",
+                "A->B->F->H",
+                "A->C->F->H"
+            ])
+        );
+    }
+
+    #[test]
+    fn multiple_sources_and_single_sink_path_schedules() {
+        // P -> T ->
+        //           \
+        // Z ------>  > D
+        //            /
+        //           /
+        // J ----->
+        let ords = &[
+            "P".arrow("T"),
+            "T".arrow("D"),
+            "Z".arrow("D"),
+            "J".arrow("D"),
+        ];
+        let durs = &[("P", 7u16), ("T", 19), ("D", 0), ("Z", 10), ("J", 26)];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(analysis.max_parallelism, 3);
+        assert_eq!(analysis.task_count, 5);
+        assert_eq!(analysis.minimum_completion_time, 26);
+        assert_eq!(analysis.critical_path_count, 2);
+        assert_eq!(analysis.critical_paths, paths(&["P->T->D", "J->D"]));
+
+        let makespan_by_source = analysis.makespan_by_source();
+        assert_eq!(makespan_by_source.len(), 3);
+        assert_eq!(makespan_by_source[&TaskLabel::new("P")], 26);
+        assert_eq!(makespan_by_source[&TaskLabel::new("Z")], 10);
+        assert_eq!(makespan_by_source[&TaskLabel::new("J")], 26);
+        assert_eq!(
+            makespan_by_source[&TaskLabel::new("J")],
+            analysis.minimum_completion_time
+        );
+    }
+
+    #[test]
+    fn zero_durations_and_no_task_ordering() {
+        let ords = &["A".node(), "B".node(), "C".node(), "D".node()];
+        let durs = &[("A", 0), ("B", 0), ("C", 0), ("D", 0)];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(analysis.max_parallelism, 4);
+        assert_eq!(analysis.task_count, 4);
+        assert_eq!(analysis.minimum_completion_time, 0);
+        assert_eq!(analysis.critical_path_count, 4);
+        assert_eq!(analysis.critical_paths, paths(&["A", "B", "C", "D"]));
+        assert_eq!(
+            analysis.first_time_at_parallelism(),
+            vec![(1, 0), (2, 0), (3, 0), (4, 0)]
+        );
+    }
+
+    #[test]
+    fn flexible_fusion() {
+        // A -> B, where A is being fused to B later
+        let ords = &["A".node(), "B".node(), "A".arrow("B")];
+        let durs = &[("A", 2), ("B", 1)];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(analysis.max_parallelism, 1);
+        assert_eq!(analysis.task_count, 2);
+        assert_eq!(analysis.minimum_completion_time, 3);
+        assert_eq!(analysis.critical_path_count, 1);
+        assert_eq!(analysis.critical_paths, paths(&["A->B"]));
+
+        // A -> B
+        let ords = &["A".arrow("B")];
+        let durs = &[("A", 2), ("B", 1)];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(analysis.max_parallelism, 1);
+        assert_eq!(analysis.task_count, 2);
+        assert_eq!(analysis.minimum_completion_time, 3);
+        assert_eq!(analysis.critical_path_count, 1);
+        assert_eq!(analysis.critical_paths, paths(&["A->B"]));
+
+        // A -> B -> D, where A and B is fused later
+        let ords = &["A".node(), "B".node(), "B".arrow("D"), "A".arrow("B")];
+        let durs = &[("A", 2), ("B", 1), ("D", 3)];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(analysis.max_parallelism, 1);
+        assert_eq!(analysis.task_count, 3);
+        assert_eq!(analysis.minimum_completion_time, 6);
+        assert_eq!(analysis.critical_path_count, 1);
+        assert_eq!(analysis.critical_paths, paths(&["A->B->D"]));
+    }
+
+    #[test]
+    fn weighted_relations() {
+        //    --5--> B
+        //   /
+        //  A
+        //   \
+        //    -----> C
+        let ords = &["A".arrow("B"), "A".arrow("C")];
+        let durs = &[("A", 2), ("B", 1), ("C", 4)];
+        let weights = &[(("A", "B"), 5)];
+        let analysis = analyze_weighted(ords, durs, weights).unwrap();
+        assert_eq!(analysis.minimum_completion_time, 8);
+        assert_eq!(analysis.critical_path_count, 1);
+        assert_eq!(analysis.critical_paths, paths(&["A->B"]));
+
+        // the weight ties the two paths
+        let weights = &[(("A", "B"), 3)];
+        let analysis = analyze_weighted(ords, durs, weights).unwrap();
+        assert_eq!(analysis.minimum_completion_time, 6);
+        assert_eq!(analysis.critical_paths, paths(&["A->B", "A->C"]));
+
+        // zero weights preserve the unweighted analysis
+        let weights = &[(("A", "B"), 0), (("A", "C"), 0)];
+        let analysis = analyze_weighted(ords, durs, weights).unwrap();
+        assert_eq!(analysis.minimum_completion_time, 6);
+        assert_eq!(analysis.critical_paths, paths(&["A->C"]));
+    }
+
+    #[test]
+    fn empty_input() {
+        let ords = &[];
+        let durs = &[];
+        let res = analyze(ords, durs);
+        assert!(matches!(res, Err(AnalysisError::EmptyInput)));
+    }
+
+    #[test]
+    fn missing_durations() {
+        let ords = &["A".node(), "B".node(), "D".arrow("L")];
+        let durs = &[("A", 2), ("L", 1)];
+        let res = analyze(ords, durs);
+        match res {
+            Err(AnalysisError::MissingDurations(vec)) => assert_eq!(vec, labels(&["B", "D"])),
+            other => assert!(matches!(other, Err(AnalysisError::MissingDurations(_)))),
+        }
+
+        let ords = &["A".node(), "B".node(), "D".arrow("L")];
+        let durs = &[];
+        let res = analyze(ords, durs);
+        match res {
+            Err(AnalysisError::MissingDurations(vec)) => {
+                assert_eq!(vec, labels(&["A", "B", "D", "L"]))
+            }
+            other => {
+                assert!(matches!(other, Err(AnalysisError::MissingDurations(_))));
+            }
+        }
+    }
+
+    #[test]
+    fn error_messages_name_bare_labels() {
+        let task = TaskLabel::new("Build");
+        for (err, message) in &[
+            (
+                AnalysisError::MissingDurations(vec![task]),
+                "Schedule is missing durations for: [\"Build\"]",
+            ),
+            (
+                AnalysisError::MissingOrders(vec![task]),
+                "Schedule is missing orders for: [\"Build\"]",
+            ),
+            (
+                AnalysisError::DurationOverflow(task),
+                "Duration of the paths leading to Build is too large",
+            ),
+            (
+                AnalysisError::ConflictingDurations(task),
+                "Conflicting durations for task: Build",
+            ),
+            (
+                AnalysisError::SelfDependency(task),
+                "Labels cannot have a dependency on themselves: Build",
+            ),
+        ] {
+            let err_message = err.to_string();
+            assert_eq!(err_message, *message);
+            assert!(!err_message.contains("TL(") && !err_message.contains("TaskLabel("));
+        }
+    }
+
+    #[test]
+    fn missing_orders() {
+        let ords = &["A".node(), "D".arrow("L")];
+        let durs = &[("A", 2), ("B", 3), ("D", 7), ("L", 1)];
+        let res = analyze(ords, durs);
+        match res {
+            Err(AnalysisError::MissingOrders(vec)) => assert_eq!(vec, labels(&["B"])),
+            other => assert!(matches!(other, Err(AnalysisError::MissingOrders(_)))),
+        }
+
+        let ords = &[];
+        let durs = &[("A", 2), ("L", 1)];
+        let res = analyze(ords, durs);
+        match res {
+            Err(AnalysisError::MissingOrders(vec)) => assert_eq!(vec, labels(&["A", "L"])),
+            other => assert!(matches!(other, Err(AnalysisError::MissingOrders(_)))),
+        }
+    }
+
+    #[test]
+    fn validating_schedules() {
+        let ords = ["A".node(), "D".arrow("L")].iter().cloned().collect();
+        let durs = [("A", 2u16), ("B", 3), ("L", 1)]
+            .iter()
+            .map(|&(task, duration)| (TaskLabel::new(task), duration))
+            .collect();
+        assert_eq!(
+            validate_schedule(&ords, &durs),
+            vec![
+                AnalysisError::MissingDurations(labels(&["D"])),
+                AnalysisError::MissingOrders(labels(&["B"])),
+            ]
+        );
+
+        let ords = ["A".arrow("B"), "B".arrow("A")].iter().cloned().collect();
+        let durs = [("A", 2u16), ("B", 3)]
+            .iter()
+            .map(|&(task, duration)| (TaskLabel::new(task), duration))
+            .collect();
+        assert_eq!(
+            validate_schedule(&ords, &durs),
+            vec![],
+            "cycles are not validated"
+        );
+    }
+
+    #[quickcheck]
+    fn simple_auto_generated_schedules(
+        gen_labels: HashSet<String>,
+        gen_durations: Vec<Duration>,
+    ) -> TestResult {
+        {
+            let gen_labels_len = gen_labels.len();
+            if !(20..=100).contains(&gen_labels_len) {
+                return TestResult::discard();
+            }
+
+            if gen_durations.len() < gen_labels_len {
+                return TestResult::discard();
+            }
+        }
+
+        let str_labels = gen_labels
+            .iter()
+            .filter(|s| TaskLabel::try_from(s.as_str()).is_ok())
+            .map(|s| s.as_str())
+            .collect::<Vec<_>>();
+
+        if str_labels.is_empty() {
+            return TestResult::discard();
+        }
+
+        let task_count = str_labels.len();
+        let durations = gen_durations
+            .iter()
+            .cloned()
+            .take(task_count)
+            .collect::<Vec<_>>();
+        let max_duration = *durations.iter().max().unwrap();
+        let mut critical_paths = str_labels
+            .iter()
+            .cloned()
+            .zip(gen_durations.iter().cloned())
+            .fold(
+                Vec::new(),
+                |mut paths: Vec<Vec<TaskLabel>>, (label, dur)| {
+                    if dur == max_duration {
+                        paths.push(vec![TaskLabel::new(label)]);
+                    }
+                    paths
+                },
+            );
+
+        critical_paths.sort_unstable_by(|path1, path2| {
+            path1
+                .iter()
+                .zip(path2.iter())
+                .map(|(str1, str2)| str1.cmp(str2))
+                .find(|cmp| *cmp != Ordering::Equal)
+                .unwrap()
+        });
+
+        let ords = str_labels.iter().map(|l| l.node()).collect::<Vec<_>>();
+        let durs = str_labels
+            .into_iter()
+            .zip(gen_durations.into_iter())
+            .collect::<Vec<_>>();
+        let analysis = analyze(&ords, &durs).unwrap();
+        assert_eq!(analysis.max_parallelism, task_count);
+        assert_eq!(analysis.task_count, task_count);
+        assert_eq!(
+            analysis.minimum_completion_time,
+            max_duration as TotalDuration
+        );
+        assert_eq!(analysis.critical_path_count, critical_paths.len());
+        assert_eq!(analysis.critical_paths, critical_paths);
+        TestResult::passed()
+    }
+
+    #[test]
+    fn cyclic_schedules() {
+        // A -> B -> A
+        let ords = &["A".arrow("B"), "B".arrow("A")];
+        let durs = &[("A", 5u16), ("B", 1)];
+        let res = analyze(ords, durs);
+        assert_eq!(res.unwrap_err(), AnalysisError::Cycle);
+
+        // A -> C
+        //        \
+        // B ----- -> D -> A
+        let ords = &[
+            "A".arrow("C"),
+            "B".arrow("D"),
+            "C".arrow("D"),
+            "D".arrow("A"),
+        ];
+        let durs = &[("A", 5u16), ("B", 1), ("C", 1), ("D", 7)];
+        let res = analyze(ords, durs);
+        assert_eq!(res.unwrap_err(), AnalysisError::Cycle);
+
+        // A -> C -> D -> B -> A
+        let ords = &[
+            "A".arrow("C"),
+            "C".arrow("D"),
+            "D".arrow("B"),
+            "B".arrow("A"),
+        ];
+        let durs = &[("A", 5u16), ("B", 1), ("C", 1), ("D", 7)];
+        let res = analyze(ords, durs);
+        assert_eq!(res.unwrap_err(), AnalysisError::Cycle);
+
+        //       --> L --->
+        //      /         |
+        // K -> ---> T --->
+        let ords = &[
+            "K".arrow("L"),
+            "K".arrow("T"),
+            "L".arrow("T"),
+            "T".arrow("L"),
+        ];
+        let durs = &[("K", 5u16), ("L", 1), ("T", 1)];
+        let res = analyze(ords, durs);
+        assert_eq!(res.unwrap_err(), AnalysisError::Cycle);
+    }
+
+    #[test]
+    fn topological_order() {
+        let ords = &example_1_orders();
+        let durs = &example_1_durations();
+        let analysis = analyze(ords, durs).unwrap();
+        let order = analysis.topological_order();
+        assert_eq!(order, &labels(&["Q", "J", "T", "K", "P", "N", "H", "I"]));
+        let position = |task: TaskLabel| order.iter().position(|&t| t == task).unwrap();
+        for task_order in ords {
+            if let Some(second) = task_order.second() {
+                assert!(position(task_order.first()) < position(second));
+            }
+        }
+    }
+
+    #[test]
+    fn redundant_relations() {
+        // A -> B -> C -> D
+        //  \        /   /
+        //   -------------
+        let ords = &[
+            "A".arrow("B"),
+            "B".arrow("C"),
+            "C".arrow("D"),
+            "A".arrow("C"),
+            "A".arrow("D"),
+        ];
+        let durs = &[("A", 1), ("B", 1), ("C", 1), ("D", 1)];
+        let analysis = analyze(ords, durs).unwrap();
+        let (a, b, c, d) = (
+            TaskLabel::new("A"),
+            TaskLabel::new("B"),
+            TaskLabel::new("C"),
+            TaskLabel::new("D"),
+        );
+        assert_eq!(analysis.redundant_relations(), vec![(a, c, b), (a, d, b)]);
+
+        let ords = &example_1_orders();
+        let durs = &example_1_durations();
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(analysis.redundant_relations(), vec![]);
+    }
+
+    #[test]
+    fn critical_cut_tasks() {
+        let ords = &example_1_orders();
+        let durs = &example_1_durations();
+        let analysis = analyze(ords, durs).unwrap();
+        // every task is reached through Q, K only through T, and P only through J
+        assert_eq!(analysis.critical_cut_tasks(), labels(&["J", "Q", "T"]));
+
+        // A -> B -> C -> D
+        let ords = &["A".arrow("B"), "B".arrow("C"), "C".arrow("D")];
+        let durs = &[("A", 1), ("B", 1), ("C", 1), ("D", 1)];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(analysis.critical_cut_tasks(), labels(&["A", "B", "C"]));
+
+        // A -> C -> D -> F
+        //     /      \
+        // B --        E
+        // C is only reached through either source, while D and F are only reached through C
+        let ords = &[
+            "A".arrow("C"),
+            "B".arrow("C"),
+            "C".arrow("D"),
+            "D".arrow("E"),
+            "D".arrow("F"),
+        ];
+        let durs = &[("A", 1), ("B", 1), ("C", 1), ("D", 1), ("E", 1), ("F", 1)];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(analysis.critical_cut_tasks(), labels(&["C", "D"]));
+
+        let ords = &["A".node(), "B".node()];
+        let durs = &[("A", 1), ("B", 1)];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(analysis.critical_cut_tasks(), vec![]);
+    }
+
+    #[test]
+    fn mergeable_groups() {
+        // C -> {D, E, F}, where G takes longer and H follows E
+        let ords = &[
+            "C".arrow("D"),
+            "C".arrow("E"),
+            "C".arrow("F"),
+            "C".arrow("G"),
+            "C".arrow("H"),
+            "H".arrow("I"),
+        ];
+        let durs = &[
+            ("C", 1),
+            ("D", 1),
+            ("E", 1),
+            ("F", 1),
+            ("G", 2),
+            ("H", 1),
+            ("I", 1),
+        ];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(analysis.mergeable_groups(), vec![labels(&["D", "E", "F"])]);
+
+        // a weight on one of the relations sets its task apart
+        let weights = &[(("C", "E"), 1)];
+        let analysis = analyze_weighted(ords, durs, weights).unwrap();
+        assert_eq!(analysis.mergeable_groups(), vec![labels(&["D", "F"])]);
+
+        // tasks without relations can be merged too, and each set of matching tasks makes up a
+        // group of its own
+        let ords = &["A".node(), "B".node(), "C".arrow("X"), "D".arrow("X")];
+        let durs = &[("A", 3), ("B", 3), ("C", 1), ("D", 1), ("X", 1)];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(
+            analysis.mergeable_groups(),
+            vec![labels(&["A", "B"]), labels(&["C", "D"])]
+        );
+
+        let ords = &example_1_orders();
+        let durs = &example_1_durations();
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(analysis.mergeable_groups(), Vec::<Vec<TaskLabel>>::new());
+    }
+
+    #[test]
+    fn critical_margin() {
+        let ords = &example_1_orders();
+        let durs = &example_1_durations();
+        let analysis = analyze(ords, durs).unwrap();
+        // each of the 6 paths is critical
+        assert_eq!(analysis.critical_margin(), None);
+
+        // the second path through A only trails by 1, even though another path is far shorter
+        let ords = &["A".arrow("B"), "A".arrow("C"), "D".arrow("C")];
+        let durs = &[("A", 5), ("B", 3), ("C", 2), ("D", 1)];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(analysis.critical_margin(), Some(1));
+
+        // relations count towards the duration of the paths
+        let analysis = analyze_weighted(ords, durs, &[(("A", "C"), 2)]).unwrap();
+        assert_eq!(analysis.minimum_completion_time(), 9);
+        assert_eq!(analysis.critical_margin(), Some(1));
+
+        // every path is critical
+        let ords = &["A".arrow("B"), "A".arrow("C"), "D".node()];
+        let durs = &[("A", 1), ("B", 2), ("C", 2), ("D", 3)];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(analysis.critical_margin(), None);
+    }
+
+    #[test]
+    fn value_progress_curve() {
+        let ords = &example_1_orders();
+        let durs = &example_1_durations();
+        let analysis = analyze(ords, durs).unwrap();
+        // each task is worth as much as the others, and the sinks complete last
+        assert_eq!(
+            analysis.value_progress_curve(),
+            vec![(0, 0.0), (1, 0.125), (2, 0.375), (3, 0.75), (4, 1.0)]
+        );
+
+        let task_values = [(TaskLabel::new("H"), 4.0), (TaskLabel::new("I"), 0.0)]
+            .iter()
+            .cloned()
+            .collect();
+        let analysis = analysis.with_task_values(task_values);
+        assert_eq!(analysis.task_value(TaskLabel::new("H")), 4.0);
+        assert_eq!(
+            analysis.value_progress_curve(),
+            vec![(0, 0.0), (1, 0.1), (2, 0.3), (3, 0.6), (4, 1.0)]
+        );
+
+        // tasks completing at once share a point, including the ones that take no time
+        let ords = &["A".arrow("B"), "C".node()];
+        let durs = &[("A", 0), ("B", 2), ("C", 2)];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(
+            analysis.value_progress_curve(),
+            vec![(0, 1.0 / 3.0), (2, 1.0)]
+        );
+        let task_values = durs
+            .iter()
+            .map(|&(task, _)| (TaskLabel::new(task), 0.0))
+            .collect();
+        let analysis = analysis.with_task_values(task_values);
+        assert!(analysis.value_progress_curve().is_empty());
+    }
+
+    #[test]
+    fn efficiency() {
+        let ords = &example_1_orders();
+        let durs = &example_1_durations();
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(analysis.total_work(), 8);
+        assert_eq!(analysis.makespan_with_workers(2), 5);
+        assert_eq!(analysis.efficiency(2), Some(0.8));
+        // a single worker never idles
+        assert_eq!(analysis.makespan_with_workers(1), 8);
+        assert_eq!(analysis.efficiency(1), Some(1.0));
+        assert_eq!(analysis.efficiency(0), None);
+        // extra workers only idle
+        assert_eq!(analysis.makespan_with_workers(8), 4);
+        assert_eq!(analysis.efficiency(8), Some(0.25));
+
+        let ords = &["A".arrow("B")];
+        let durs = &[("A", 0), ("B", 0)];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(analysis.efficiency(2), None);
+    }
+
+    #[test]
+    fn diamonds() {
+        let ords = &example_1_orders();
+        let durs = &example_1_durations();
+        let analysis = analyze(ords, durs).unwrap();
+        let diamonds = analysis.diamonds();
+        for expected in &[("Q", "N"), ("T", "H"), ("J", "I")] {
+            let diamond = (TaskLabel::new(expected.0), TaskLabel::new(expected.1));
+            assert!(diamonds.contains(&diamond), "missing {:?}", diamond);
+        }
+
+        // A -> B -> C, where A -> C is a shortcut rather than a diamond
+        let ords = &["A".arrow("B"), "B".arrow("C"), "A".arrow("C")];
+        let durs = &[("A", 1), ("B", 1), ("C", 1)];
+        let analysis = analyze(ords, durs).unwrap();
+        assert!(analysis.diamonds().is_empty());
     }
-    // flush out the remaining
-    writeln!(buffer, "{}", line_buffer)
-}
 
-#[cfg(test)]
-pub mod tests {
-    use super::*;
-    use crate::task::{TaskLabel, TaskRelation};
-    use quickcheck::TestResult;
-    use std::convert::TryFrom;
-    use util::*;
+    #[test]
+    fn reachable_task_counts() {
+        let ords = &example_1_orders();
+        let durs = &example_1_durations();
+        let analysis = analyze(ords, durs).unwrap();
+        let q = TaskLabel::new("Q");
+        assert_eq!(analysis.downstream_count(q), analysis.task_count - 1);
+        assert_eq!(analysis.upstream_count(q), 0);
+
+        let n = TaskLabel::new("N");
+        assert_eq!(analysis.downstream_count(n), 2, "H and I");
+        assert_eq!(analysis.upstream_count(n), 3, "Q, T and J");
+
+        let h = TaskLabel::new("H");
+        assert_eq!(analysis.downstream_count(h), 0);
+        assert_eq!(analysis.upstream_count(h), 5, "Q, T, J, K and N");
+
+        // unrelated tasks in different paths
+        let ords = &["A".node(), "B".arrow("C")];
+        let durs = &[("A", 1), ("B", 1), ("C", 1)];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(analysis.downstream_count(TaskLabel::new("A")), 0);
+        assert_eq!(analysis.upstream_count(TaskLabel::new("A")), 0);
+        assert_eq!(analysis.downstream_count(TaskLabel::new("B")), 1);
+        assert_eq!(analysis.upstream_count(TaskLabel::new("C")), 1);
+    }
 
     #[test]
-    fn single_task_path_schedules() {
-        // single-task path
-        let ords = &["A".node()];
-        let durs = &[("A", 2)];
+    fn earliest_times() {
+        let ords = &["K".arrow("L"), "K".arrow("T"), "T".arrow("F")];
+        let durs = &[("K", 1u16), ("L", 12), ("T", 5), ("F", 20)];
+        let weights = &[(("T", "F"), 2)];
+        let analysis = analyze_weighted(ords, durs, weights).unwrap();
+        let earliest_times = analysis.earliest_times();
+        assert_eq!(earliest_times[&TaskLabel::new("K")], (0, 1));
+        assert_eq!(earliest_times[&TaskLabel::new("L")], (1, 13));
+        assert_eq!(earliest_times[&TaskLabel::new("F")], (8, 28));
+    }
+
+    #[test]
+    fn topological_order_counts() {
+        let ords = &["A".arrow("B"), "B".arrow("C"), "C".arrow("D")];
+        let durs = &[("A", 1), ("B", 1), ("C", 1), ("D", 1)];
         let analysis = analyze(ords, durs).unwrap();
-        assert_eq!(analysis.max_parallelism, 1);
-        assert_eq!(analysis.task_count, 1);
-        assert_eq!(analysis.minimum_completion_time, 2);
-        assert_eq!(analysis.critical_path_count, 1);
-        assert_eq!(analysis.critical_paths, paths(&["A"]));
+        assert_eq!(analysis.topological_order_count(), Some(1));
 
-        // two single-task paths
-        let ords = &["A".node(), "B".node()];
-        let durs = &[("A", 2), ("B", 3)];
+        let ords = &["A".node(), "B".node(), "C".node(), "D".node(), "E".node()];
+        let durs = &[("A", 1), ("B", 1), ("C", 1), ("D", 1), ("E", 1)];
         let analysis = analyze(ords, durs).unwrap();
-        assert_eq!(analysis.max_parallelism, 2);
-        assert_eq!(analysis.task_count, 2);
-        assert_eq!(analysis.minimum_completion_time, 3);
-        assert_eq!(analysis.critical_path_count, 1);
-        assert_eq!(analysis.critical_paths, paths(&["B"]));
+        assert_eq!(analysis.topological_order_count(), Some(120));
 
-        // three paths, two of which are a single-task path
-        // A
-        // B
-        // D -> L
-        let ords = &["A".node(), "B".node(), "D".arrow("L")];
-        let durs = &[("A", 2), ("B", 3), ("D", 7), ("L", 1)];
+        // checked by enumerating the permutations of the tasks
+        let ords = &example_1_orders();
+        let durs = &example_1_durations();
         let analysis = analyze(ords, durs).unwrap();
-        assert_eq!(analysis.max_parallelism, 3);
-        assert_eq!(analysis.task_count, 4);
-        assert_eq!(analysis.minimum_completion_time, 8);
-        assert_eq!(analysis.critical_path_count, 1);
-        assert_eq!(analysis.critical_paths, paths(&["D->L"]));
+        assert_eq!(analysis.topological_order_count(), Some(42));
+
+        // 20! orders for the largest countable schedule
+        let labels = (0..=MAX_TASKS_TO_COUNT_ORDERS)
+            .map(|task_idx| format!("T{}", task_idx))
+            .collect::<Vec<_>>();
+        let ords = labels
+            .iter()
+            .map(|label| label.as_str().node())
+            .collect::<Vec<_>>();
+        let durs = labels
+            .iter()
+            .map(|label| (label.as_str(), 1))
+            .collect::<Vec<_>>();
+        let analysis = analyze(&ords, &durs).unwrap();
+        assert_eq!(analysis.topological_order_count(), None);
+        let analysis = analyze(&ords[1..], &durs[1..]).unwrap();
+        assert_eq!(
+            analysis.topological_order_count(),
+            Some((1..=20u128).product())
+        );
     }
 
     #[test]
-    fn multiple_sources_and_multiple_sinks_path_schedules() {
-        // A -> C
-        // B -> D
-        let ords = &["A".arrow("C"), "B".arrow("D")];
-        let durs = &[("A", 5u16), ("B", 1), ("C", 9), ("D", 7)];
+    fn precedence() {
+        let ords = &example_1_orders();
+        let durs = &example_1_durations();
         let analysis = analyze(ords, durs).unwrap();
-        assert_eq!(analysis.max_parallelism, 2);
-        assert_eq!(analysis.task_count, 4);
-        assert_eq!(analysis.minimum_completion_time, 14);
-        assert_eq!(analysis.critical_path_count, 1);
-        assert_eq!(analysis.critical_paths, paths(&["A->C"]));
+        let (q, h) = (TaskLabel::new("Q"), TaskLabel::new("H"));
+        assert!(analysis.precedes(q, h));
+        assert!(!analysis.precedes(h, q));
+        assert!(analysis.precedes(TaskLabel::new("J"), TaskLabel::new("I")));
+        // siblings
+        assert!(!analysis.precedes(TaskLabel::new("K"), TaskLabel::new("N")));
+        assert!(!analysis.precedes(q, q));
 
-        // A -> C
-        // B -> D
-        let ords = &["A".arrow("C"), "B".arrow("D")];
-        let durs = &[("A", 5u16), ("B", 7), ("C", 9), ("D", 8)];
+        // tasks in different components
+        let ords = &["A".arrow("B"), "C".arrow("D")];
+        let durs = &[("A", 1), ("B", 1), ("C", 1), ("D", 1)];
         let analysis = analyze(ords, durs).unwrap();
-        assert_eq!(analysis.max_parallelism, 2);
-        assert_eq!(analysis.task_count, 4);
-        assert_eq!(analysis.minimum_completion_time, 15);
-        assert_eq!(analysis.critical_path_count, 1);
-        assert_eq!(analysis.critical_paths, paths(&["B->D"]));
+        assert!(analysis.precedes(TaskLabel::new("A"), TaskLabel::new("B")));
+        assert!(!analysis.precedes(TaskLabel::new("A"), TaskLabel::new("D")));
+        assert!(!analysis.precedes(TaskLabel::new("D"), TaskLabel::new("A")));
     }
 
     #[test]
-    fn report_accurate_parallelism_as_time_progresses() {
-        //                /--> D
-        //               /
-        //  A --> B --> C --> E
-        //              \
-        //               \--> F
+    fn wide_durations() {
+        // A -> B, where each task takes longer than what u32 can hold
+        let orders = ["A".arrow("B")].iter().cloned().collect();
+        let task_durations = [
+            (TaskLabel::new("A"), u64::from(u32::MAX) + 1),
+            (TaskLabel::new("B"), u64::from(u32::MAX)),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+        let analysis = analyze_schedule(&orders, &task_durations).unwrap();
+        assert_eq!(
+            analysis.minimum_completion_time(),
+            2 * u64::from(u32::MAX) + 1
+        );
+        assert_eq!(analysis.critical_paths(), &paths(&["A->B"]));
+        assert_eq!(analysis.near_critical_paths(0), paths(&["A->B"]));
+        assert_eq!(
+            analysis.task_duration(TaskLabel::new("A")),
+            Some(u64::from(u32::MAX) + 1)
+        );
+    }
+
+    #[test]
+    fn overflowing_durations() {
+        // A -> B -> C, where A -> B takes all that u64 can hold
+        let orders = ["A".arrow("B"), "B".arrow("C")].iter().cloned().collect();
+        let task_durations = [
+            (TaskLabel::new("A"), u64::MAX - 1),
+            (TaskLabel::new("B"), 1),
+            (TaskLabel::new("C"), 1),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+        assert_eq!(
+            try_minimum_completion_time(&orders, &task_durations, &HashMap::new()),
+            Err(AnalysisError::DurationOverflow(TaskLabel::new("C")))
+        );
+        assert_eq!(
+            analyze_schedule(&orders, &task_durations).unwrap_err(),
+            AnalysisError::DurationOverflow(TaskLabel::new("C"))
+        );
+
+        // the weight of B -> C is what overflows
+        let task_durations = [
+            (TaskLabel::new("A"), u64::MAX - 2),
+            (TaskLabel::new("B"), 1),
+            (TaskLabel::new("C"), 0),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+        let edge_weights = [((TaskLabel::new("B"), TaskLabel::new("C")), 2)]
+            .iter()
+            .cloned()
+            .collect();
+        assert_eq!(
+            try_minimum_completion_time(&orders, &task_durations, &edge_weights),
+            Err(AnalysisError::DurationOverflow(TaskLabel::new("C")))
+        );
+        assert_eq!(
+            try_minimum_completion_time(&orders, &task_durations, &HashMap::new()),
+            Ok(u64::MAX - 1)
+        );
+    }
+
+    #[test]
+    fn longest_path_per_sink() {
+        //    /--> L -> Z
+        //   /
         //  K
+        //   \
+        //    \--> T -> F
         let ords = &[
-            "A".arrow("B"),
-            "B".arrow("C"),
-            "C".arrow("D"),
-            "C".arrow("E"),
-            "C".arrow("F"),
-            "K".node(),
-        ];
-        let durs = &[
-            ("A", 1u16),
-            ("B", 1),
-            ("C", 1),
-            ("D", 1),
-            ("E", 1),
-            ("F", 1),
-            ("K", 4),
+            "K".arrow("L"),
+            "K".arrow("T"),
+            "L".arrow("Z"),
+            "T".arrow("F"),
         ];
+        let durs = &[("K", 1u16), ("L", 12), ("Z", 1), ("T", 5), ("F", 20)];
         let analysis = analyze(ords, durs).unwrap();
+        let longest_paths = analysis.longest_path_per_sink();
+        assert_eq!(longest_paths.len(), 2);
         assert_eq!(
-            analysis.max_parallelism, 4,
-            "finding tasks D, E, F, K running together at the 4th \"tick\" requires 4 task-runners"
+            longest_paths[&TaskLabel::new("Z")],
+            (14, labels(&["K", "L", "Z"]))
         );
-        assert_eq!(analysis.task_count, 7);
-        assert_eq!(analysis.minimum_completion_time, 4);
-        assert_eq!(analysis.critical_path_count, 4);
         assert_eq!(
-            analysis.critical_paths,
-            paths(&["A->B->C->D", "A->B->C->E", "A->B->C->F", "K"])
+            longest_paths[&TaskLabel::new("F")],
+            (26, labels(&["K", "T", "F"]))
+        );
+
+        // tied paths to the sink and a task unrelated to others
+        let ords = &["B".arrow("C"), "A".arrow("C"), "D".node()];
+        let durs = &[("A", 1), ("B", 1), ("C", 1), ("D", 5)];
+        let analysis = analyze(ords, durs).unwrap();
+        let longest_paths = analysis.longest_path_per_sink();
+        assert_eq!(
+            longest_paths[&TaskLabel::new("C")],
+            (2, labels(&["A", "C"]))
+        );
+        assert_eq!(longest_paths[&TaskLabel::new("D")], (5, labels(&["D"])));
+    }
+
+    #[test]
+    fn critical_paths_in_given_order() {
+        let ords = &["D".node(), "B".node(), "C".node(), "A".arrow("E")];
+        let durs = &[("A", 0), ("B", 0), ("C", 0), ("D", 0), ("E", 0)];
+        let task_orders = ords.iter().cloned().collect();
+        let task_durations = durs
+            .iter()
+            .map(|&(task, duration)| (TaskLabel::new(task), duration))
+            .collect();
+        let task_positions = ["D", "B", "A", "E"]
+            .iter()
+            .enumerate()
+            .map(|(position, &task)| (TaskLabel::new(task), position))
+            .collect();
+        let analysis = analyze_weighted_schedule_in_order::<Duration>(
+            &task_orders,
+            &task_durations,
+            &HashMap::new(),
+            &task_positions,
+        )
+        .unwrap();
+        // C has no position
+        assert_eq!(analysis.critical_paths, paths(&["A->E", "D", "B", "C"]));
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(analysis.critical_paths, paths(&["A->E", "B", "C", "D"]));
+    }
+
+    #[test]
+    fn duplicate_critical_paths() {
+        // "A" is listed twice as a parent of "C", which leads to "A->C" twice
+        let parent_tasks = [
+            (TaskLabel::new("C"), labels(&["A", "B", "A"])),
+            (TaskLabel::new("D"), labels(&["C"])),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+        let longest_duration_path_to_task = [
+            (TaskLabel::new("A"), 1),
+            (TaskLabel::new("B"), 1),
+            (TaskLabel::new("C"), 2),
+            (TaskLabel::new("D"), 3),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+        let topological_order = labels(&["A", "B", "C", "D"]);
+        let critical_paths = CriticalPaths::find_critical_paths(
+            &parent_tasks,
+            &longest_duration_path_to_task,
+            &labels(&["D"]),
+            &topological_order,
+            None,
+            None,
+        );
+        assert_eq!(critical_paths.duration, 3);
+        assert_eq!(critical_paths.paths, paths(&["A->C->D", "B->C->D"]));
+        assert_eq!(critical_paths.count, 2);
+
+        // the duplicate is not counted either
+        for &(max_paths, listed_path_count) in &[(1, 0), (2, 2)] {
+            let critical_paths = CriticalPaths::find_critical_paths(
+                &parent_tasks,
+                &longest_duration_path_to_task,
+                &labels(&["D"]),
+                &topological_order,
+                None,
+                Some(max_paths),
+            );
+            assert_eq!(critical_paths.count, 2);
+            assert_eq!(critical_paths.paths.len(), listed_path_count);
+        }
+    }
+
+    #[test]
+    fn critical_path_strings() {
+        let ords = example_1_orders();
+        let durs = example_1_durations();
+        let analysis = analyze(&ords, &durs).unwrap();
+        assert_eq!(
+            analysis.critical_path_strings(),
+            vec![
+                "Q->J->N->H",
+                "Q->J->N->I",
+                "Q->J->P->I",
+                "Q->T->K->H",
+                "Q->T->N->H",
+                "Q->T->N->I",
+            ]
+        );
+
+        // long labels are not wrapped
+        let long_label = "L".repeat(TaskLabel::MAX_LEN);
+        let ords = &[long_label.as_str().arrow("B")];
+        let durs = &[(long_label.as_str(), 1), ("B", 1)];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(
+            analysis.critical_path_strings(),
+            vec![format!("{}->B", long_label)]
+        );
+    }
+
+    #[test]
+    fn most_detailed_critical_path() {
+        let ords = example_1_orders();
+        let durs = example_1_durations();
+        let analysis = analyze(&ords, &durs).unwrap();
+        let path = analysis.most_detailed_critical_path().unwrap();
+        assert_eq!(path.len(), 4);
+        assert_eq!(path, &labels(&["Q", "J", "N", "H"]));
+        let analysis = analysis.with_path_order(PathOrder::FirstTask);
+        assert_eq!(
+            analysis.most_detailed_critical_path(),
+            Some(&labels(&["Q", "J", "N", "H"]))
+        );
+
+        // A(2) -> B(2) takes as long as C(1) -> D(1) -> E(2)
+        let ords = &["A".arrow("B"), "C".arrow("D"), "D".arrow("E")];
+        let durs = &[("A", 2), ("B", 2), ("C", 1), ("D", 1), ("E", 2)];
+        let analysis = analyze(ords, durs)
+            .unwrap()
+            .with_path_order(PathOrder::Lexicographic);
+        assert_eq!(analysis.critical_paths(), &paths(&["A->B", "C->D->E"]));
+        assert_eq!(
+            analysis.most_detailed_critical_path(),
+            Some(&labels(&["C", "D", "E"]))
         );
+    }
 
+    #[test]
+    fn execution_waves() {
+        // A -> B -> C -> D
+        //             \-> E
+        //              \-> F
+        // K
         let ords = &[
             "A".arrow("B"),
             "B".arrow("C"),
@@ -543,427 +3393,875 @@ pub mod tests {
             "K".node(),
         ];
         let durs = &[
-            ("A", 1u16),
-            ("B", 1),
+            ("A", 1),
+            ("B", 5),
             ("C", 1),
             ("D", 1),
-            ("E", 1),
-            ("F", 1),
-            ("K", 3),
+            ("E", 3),
+            ("F", 2),
+            ("K", 10),
         ];
         let analysis = analyze(ords, durs).unwrap();
         assert_eq!(
-            analysis.max_parallelism, 3,
-            "K finishes before we get to execute D, E, F at the 4th tick, thus at most 3 task-runners needed"
+            analysis.execution_waves(),
+            vec![
+                labels(&["A", "K"]),
+                labels(&["B"]),
+                labels(&["C"]),
+                labels(&["D", "E", "F"])
+            ]
         );
-        assert_eq!(analysis.task_count, 7);
-        assert_eq!(analysis.minimum_completion_time, 4);
-        assert_eq!(analysis.critical_path_count, 3);
+
+        // a task waits for the last wave holding one of the tasks it depends on
+        let ords = example_1_orders();
+        let durs = example_1_durations();
+        let analysis = analyze(&ords, &durs).unwrap();
+        assert_eq!(
+            analysis.execution_waves(),
+            vec![
+                labels(&["Q"]),
+                labels(&["J", "T"]),
+                labels(&["K", "N", "P"]),
+                labels(&["H", "I"])
+            ]
+        );
+        let ords = &["A".arrow("B"), "B".arrow("C"), "A".arrow("C")];
+        let durs = &[("A", 1), ("B", 1), ("C", 1)];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(
+            analysis.execution_waves(),
+            vec![labels(&["A"]), labels(&["B"]), labels(&["C"])]
+        );
+    }
+
+    #[test]
+    fn visiting_tasks() {
+        // (task, earliest finish, parallelism)
+        #[derive(Default)]
+        struct Recorder(Vec<(String, TotalDuration, usize)>);
+        impl<'a> ScheduleVisitor<TaskLabel<'a>, TotalDuration> for Recorder {
+            fn on_task_scheduled(
+                &mut self,
+                task: TaskLabel<'a>,
+                earliest_finish: TotalDuration,
+                current_parallelism: usize,
+            ) {
+                self.0.push((
+                    String::from(task.as_ref()),
+                    earliest_finish,
+                    current_parallelism,
+                ));
+            }
+        }
+
+        let orders = example_1_orders().into_iter().collect();
+        let durations = example_1_durations()
+            .into_iter()
+            .map(|(task, duration)| (TaskLabel::new(task), duration))
+            .collect();
+        let mut recorder = Recorder::default();
+        let analysis =
+            analyze_with_visitor(&orders, &durations, &HashMap::new(), &mut recorder).unwrap();
+        let visited_tasks = recorder
+            .0
+            .iter()
+            .map(|(task, _, _)| TaskLabel::new(task))
+            .collect::<Vec<_>>();
+        assert_eq!(&visited_tasks, analysis.topological_order());
+        assert_eq!(
+            recorder.0,
+            [
+                ("Q", 1, 1),
+                ("J", 2, 2),
+                ("T", 2, 2),
+                ("K", 3, 3),
+                ("P", 3, 2),
+                ("N", 3, 1),
+                ("H", 4, 2),
+                ("I", 4, 1)
+            ]
+            .iter()
+            .map(|&(task, finish, parallelism)| (String::from(task), finish, parallelism))
+            .collect::<Vec<_>>()
+        );
+        let max_parallelism = recorder
+            .0
+            .iter()
+            .map(|&(_, _, parallelism)| parallelism)
+            .max();
+        assert_eq!(max_parallelism, Some(analysis.max_parallelism()));
+    }
+
+    #[test]
+    fn worker_assignments() {
+        fn check_assignments(analysis: &ScheduleAnalysis, workers: usize) -> TotalDuration {
+            let assignments = analysis.assign_to_workers(workers);
+            assert_eq!(assignments.len(), workers);
+            let starts = assignments
+                .iter()
+                .flatten()
+                .cloned()
+                .collect::<HashMap<_, _>>();
+            // every task is assigned exactly once
+            assert_eq!(
+                assignments.iter().map(|tasks| tasks.len()).sum::<usize>(),
+                analysis.task_count()
+            );
+            assert_eq!(starts.len(), analysis.task_count());
+            let finish = |task| starts[&task] + TotalDuration::from(analysis.task_durations[&task]);
+            for (&task, next_tasks) in analysis.task_graph() {
+                for &next_task in next_tasks {
+                    assert!(
+                        starts[&next_task] >= finish(task) + analysis.edge_weight(task, next_task),
+                        "{:?} starts before {:?} completes",
+                        next_task,
+                        task
+                    );
+                }
+            }
+            // a worker executes one task at a time
+            for tasks in &assignments {
+                for pair in tasks.windows(2) {
+                    assert!(pair[1].1 >= finish(pair[0].0));
+                }
+            }
+            starts.keys().map(|&task| finish(task)).max().unwrap()
+        }
+
+        let ords = &example_1_orders();
+        let durs = &example_1_durations();
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(check_assignments(&analysis, 1), 8);
+        assert_eq!(check_assignments(&analysis, 2), 5);
+        assert_eq!(check_assignments(&analysis, 3), 4);
+        assert_eq!(
+            analysis.assign_to_workers(2)[0],
+            vec![
+                (TaskLabel::new("Q"), 0),
+                (TaskLabel::new("J"), 1),
+                (TaskLabel::new("K"), 2),
+                (TaskLabel::new("P"), 3),
+                (TaskLabel::new("I"), 4)
+            ]
+        );
+        assert!(analysis.assign_to_workers(0).is_empty());
+
+        let ords = &["A".arrow("C"), "B".arrow("C"), "D".node()];
+        let durs = &[("A", 1), ("B", 2), ("C", 1), ("D", 2)];
+        let weights = &[(("A", "C"), 3)];
+        let analysis = analyze_weighted(ords, durs, weights).unwrap();
+        assert_eq!(check_assignments(&analysis, 1), 6);
+        // the worker idles until the weight of A -> C is paid
+        assert_eq!(check_assignments(&analysis, 2), 5);
+    }
+
+    #[test]
+    fn path_orders() {
+        //  K(1) -> M(1) -> N(1)
+        //       \-> L(2)
+        //  B(3)
+        let ords = &["K".arrow("M"), "M".arrow("N"), "K".arrow("L"), "B".node()];
+        let durs = &[("K", 1), ("M", 1), ("N", 1), ("L", 2), ("B", 3)];
+        for &(order, expected_paths) in &[
+            (PathOrder::Length, ["K->M->N", "K->L", "B"]),
+            (PathOrder::Lexicographic, ["B", "K->L", "K->M->N"]),
+            (PathOrder::FirstTask, ["B", "K->M->N", "K->L"]),
+        ] {
+            let analysis = analyze(ords, durs).unwrap().with_path_order(order);
+            assert_eq!(
+                analysis.critical_paths(),
+                &paths(&expected_paths),
+                "{:?}",
+                order
+            );
+            // the order does not depend on the order the paths were put in before
+            for &previous_order in &[PathOrder::Lexicographic, PathOrder::FirstTask] {
+                let analysis = analyze(ords, durs)
+                    .unwrap()
+                    .with_path_order(previous_order)
+                    .with_path_order(order);
+                assert_eq!(
+                    analysis.critical_paths(),
+                    &paths(&expected_paths),
+                    "{:?} after {:?}",
+                    order,
+                    previous_order
+                );
+            }
+        }
+
+        // the paths of example 1 have as many tasks and start with the same task, which leaves
+        // all the orders lexicographical
+        let ords = &example_1_orders();
+        let durs = &example_1_durations();
+        for &order in &[
+            PathOrder::Length,
+            PathOrder::Lexicographic,
+            PathOrder::FirstTask,
+        ] {
+            let analysis = analyze(ords, durs).unwrap().with_path_order(order);
+            assert_eq!(
+                analysis.critical_paths(),
+                &paths(&[
+                    "Q->J->N->H",
+                    "Q->J->N->I",
+                    "Q->J->P->I",
+                    "Q->T->K->H",
+                    "Q->T->N->H",
+                    "Q->T->N->I"
+                ])
+            );
+        }
+        assert_eq!("lex".parse(), Ok(PathOrder::Lexicographic));
+        assert!("random".parse::<PathOrder>().is_err());
+    }
+
+    #[test]
+    fn too_many_critical_paths() {
+        // S0 -> A1 -> S1 -> A2 -> S2 ... with Bi next to each Ai, which doubles the number of
+        // paths at each level
+        fn lattice_labels(level_count: usize) -> Vec<(String, String, String)> {
+            (0..=level_count)
+                .map(|level| {
+                    (
+                        format!("S{}", level),
+                        format!("A{}", level),
+                        format!("B{}", level),
+                    )
+                })
+                .collect()
+        }
+        #[allow(clippy::type_complexity)]
+        fn lattice<'a>(
+            labels: &'a [(String, String, String)],
+        ) -> (HashSet<TaskOrder<'a>>, HashMap<TaskLabel<'a>, Duration>) {
+            let mut task_orders = HashSet::new();
+            let mut task_durations = HashMap::new();
+            task_durations.insert(TaskLabel::new(&labels[0].0), 1);
+            for pair in labels.windows(2) {
+                let (source, _, _) = &pair[0];
+                let (sink, a, b) = &pair[1];
+                for middle in &[a, b] {
+                    task_orders.insert(source.as_str().arrow(middle.as_str()));
+                    task_orders.insert(middle.as_str().arrow(sink.as_str()));
+                    task_durations.insert(TaskLabel::new(middle), 1);
+                }
+                task_durations.insert(TaskLabel::new(sink), 1);
+            }
+            (task_orders, task_durations)
+        }
+
+        // listing 2^40 paths would not complete
+        let labels = lattice_labels(40);
+        let (task_orders, task_durations) = lattice(&labels);
+        let analysis = analyze_weighted_schedule_with_max_paths(
+            &task_orders,
+            &task_durations,
+            &HashMap::new(),
+            None,
+            1000,
+        )
+        .unwrap();
+        assert_eq!(analysis.critical_path_count(), 1 << 40);
+        assert!(analysis.critical_paths().is_empty());
+        assert!(!analysis.critical_paths_listed());
+        assert_eq!(analysis.minimum_completion_time(), 81);
+        assert!(analysis
+            .explain()
+            .starts_with("The minimum completion time of 81 is driven by the path "));
+        assert!(analysis
+            .explain()
+            .ends_with(" It is one of 1099511627776 critical paths."));
+
+        let labels = lattice_labels(3);
+        let (task_orders, task_durations) = lattice(&labels);
+        let analysis = analyze_weighted_schedule_with_max_paths(
+            &task_orders,
+            &task_durations,
+            &HashMap::new(),
+            None,
+            8,
+        )
+        .unwrap();
+        assert_eq!(analysis.critical_path_count(), 8);
+        assert_eq!(analysis.critical_paths().len(), 8);
+        assert!(analysis.critical_paths_listed());
+
+        // the explanation traces a critical path back even though none is listed
+        let analysis = analyze_weighted_schedule_with_max_paths(
+            &task_orders,
+            &task_durations,
+            &HashMap::new(),
+            None,
+            4,
+        )
+        .unwrap();
+        assert!(!analysis.critical_paths_listed());
         assert_eq!(
-            analysis.critical_paths,
-            paths(&["A->B->C->D", "A->B->C->E", "A->B->C->F"])
+            analysis.explain(),
+            "The minimum completion time of 7 is driven by the path S0->A1->S1->A2->S2->A3->S3, \
+             where A1 (1) dominates. It is one of 8 critical paths."
         );
+    }
 
-        let ords = &["A".arrow("B"), "A".arrow("C"), "K".node()];
-        let durs = &[("A", 0u16), ("B", 0), ("C", 0), ("K", 0)];
+    #[test]
+    fn longest_paths_agree_with_dfs() {
+        let test_folder = format!("{}/resources/test", env!("CARGO_MANIFEST_DIR"));
+        let mut analyzed_file_count = 0;
+        for entry in std::fs::read_dir(&test_folder).unwrap() {
+            let path = entry.unwrap().path();
+            let path_name = path.to_string_lossy();
+            if !path_name.ends_with(".tasks.in") {
+                continue;
+            }
+            // included fragments have no expected output of their own
+            match std::fs::read_to_string(path_name.replace(".tasks.in", ".schedule.out")) {
+                Ok(expected_output) if expected_output.starts_with("task_count") => {}
+                _ => continue,
+            }
+            let content = std::fs::read_to_string(&path).unwrap();
+            let included_contents = crate::parser::IncludedContents::new();
+            let analysis = crate::processor::process_with_includes(
+                &content,
+                crate::parser::Source::File(&path),
+                &included_contents,
+                &Default::default(),
+            )
+            .unwrap();
+            assert_eq!(
+                longest_path_dfs(
+                    &analysis.task_graph,
+                    &analysis.task_durations,
+                    &analysis.edge_weights
+                ),
+                analysis.longest_duration_path_to_task,
+                "{}",
+                path.display()
+            );
+            analyzed_file_count += 1;
+        }
+        assert!(analyzed_file_count >= 10);
+
+        let ords = &example_1_orders();
+        let durs = &example_1_durations();
         let analysis = analyze(ords, durs).unwrap();
-        assert!(
-            analysis.max_parallelism == 2 || analysis.max_parallelism == 3,
-            "Time does not exist; edge case!!!"
+        let longest_paths = longest_path_dfs(
+            &analysis.task_graph,
+            &analysis.task_durations,
+            &analysis.edge_weights,
         );
-        assert_eq!(analysis.task_count, 4);
-        assert_eq!(analysis.minimum_completion_time, 0);
-        assert_eq!(analysis.critical_path_count, 3);
-        assert_eq!(analysis.critical_paths, paths(&["A->B", "A->C", "K"]));
+        assert_eq!(longest_paths, analysis.longest_duration_path_to_task);
+        assert_eq!(longest_paths[&TaskLabel::new("I")], 4);
     }
 
     #[test]
-    fn single_source_and_multiple_sinks_path_schedules() {
-        //    /--> L -> Z
-        //   /
-        //  K
-        //   \
-        //    \--> T -> F
-        let ords = &[
-            "K".arrow("L"),
-            "K".arrow("T"),
-            "L".arrow("Z"),
-            "T".arrow("F"),
-        ];
-        let durs = &[("K", 1u16), ("L", 12), ("Z", 1), ("T", 5), ("F", 20)];
-        let analysis = analyze(ords, durs).unwrap();
-        assert_eq!(analysis.max_parallelism, 2);
-        assert_eq!(analysis.task_count, 5);
-        assert_eq!(analysis.minimum_completion_time, 26);
-        assert_eq!(analysis.critical_path_count, 1);
-        assert_eq!(analysis.critical_paths, paths(&["K->T->F"]));
+    fn random_schedules_agree_with_references() {
+        for &(seed, task_count, extra_relations) in &[
+            (1, 2, 0),
+            (2, 8, 6),
+            (3, 12, 12),
+            (4, 14, 20),
+            (5, 60, 120),
+            (6, 400, 1200),
+        ] {
+            let schedule = RandomSchedule::generate(seed, task_count, extra_relations);
+            let analysis = schedule.analyze().unwrap();
+            assert_eq!(analysis.task_count, task_count, "seed {}", seed);
+            let longest_paths = longest_path_dfs(
+                &analysis.task_graph,
+                &analysis.task_durations,
+                &analysis.edge_weights,
+            );
+            assert_eq!(
+                longest_paths, analysis.longest_duration_path_to_task,
+                "seed {}",
+                seed
+            );
+            assert_eq!(
+                longest_paths.values().max().copied().unwrap(),
+                analysis.minimum_completion_time,
+                "seed {}",
+                seed
+            );
+            let positions = analysis
+                .topological_order
+                .iter()
+                .enumerate()
+                .map(|(position, &task)| (task, position))
+                .collect::<HashMap<_, _>>();
+            for (task, next_tasks) in &analysis.task_graph {
+                assert!(
+                    next_tasks
+                        .iter()
+                        .all(|next_task| positions[task] < positions[next_task]),
+                    "seed {}",
+                    seed
+                );
+            }
+            assert!((1..=task_count).contains(&analysis.max_parallelism));
 
-        // All CPs have equal duration, lexicographically smaller ones come
-        // first in order in the result set.
-        //    /--> B -> D ->- >H
-        //   /     \        /
-        //  A       > --- >F         -> I
-        //   \     /                /
-        //    \--> C -> G -------->
+            // enumerating the paths only completes on small schedules
+            if task_count <= 14 {
+                let mut critical_paths = schedule
+                    .all_paths()
+                    .into_iter()
+                    .filter(|&(_, duration)| duration == analysis.minimum_completion_time)
+                    .map(|(path, _)| path)
+                    .collect::<Vec<_>>();
+                critical_paths.sort_unstable();
+                let mut found_critical_paths = analysis.critical_paths.clone();
+                found_critical_paths.sort_unstable();
+                assert_eq!(found_critical_paths, critical_paths, "seed {}", seed);
+                assert_eq!(analysis.critical_path_count, critical_paths.len());
+                let critical_margin = schedule
+                    .all_paths()
+                    .into_iter()
+                    .map(|(_, duration)| duration)
+                    .filter(|&duration| duration < analysis.minimum_completion_time)
+                    .max()
+                    .map(|duration| analysis.minimum_completion_time - duration);
+                assert_eq!(analysis.critical_margin(), critical_margin, "seed {}", seed);
+            }
+        }
+    }
+
+    #[test]
+    fn slack() {
+        //  A(2) -> B(10) -> D(1)
+        //       \-> C(3) -/
+        //  E(5)
+        //  F(1) -> G(1)
         let ords = &[
             "A".arrow("B"),
             "A".arrow("C"),
             "B".arrow("D"),
-            "B".arrow("F"),
-            "C".arrow("F"),
-            "C".arrow("G"),
-            "F".arrow("H"),
-            "D".arrow("H"),
-            "G".arrow("I"),
+            "C".arrow("D"),
+            "E".node(),
+            "F".arrow("G"),
         ];
         let durs = &[
-            ("A", 1u16),
-            ("B", 1),
-            ("C", 1),
+            ("A", 2),
+            ("B", 10),
+            ("C", 3),
             ("D", 1),
+            ("E", 5),
             ("F", 1),
-            ("H", 1),
             ("G", 1),
-            ("I", 1),
         ];
         let analysis = analyze(ords, durs).unwrap();
-        assert_eq!(analysis.max_parallelism, 3);
-        assert_eq!(analysis.task_count, 8);
-        assert_eq!(analysis.minimum_completion_time, 4);
-        assert_eq!(analysis.critical_path_count, 4);
-        assert_eq!(
-            analysis.critical_paths,
-            paths(&["A->B->D->H", "A->B->F->H", "A->C->F->H", "A->C->G->I"])
-        );
+        let total_slack = analysis.total_slack();
+        for &(task, slack) in &[
+            ("A", 0),
+            ("B", 0),
+            ("C", 7),
+            ("D", 0),
+            ("E", 8),
+            ("F", 11),
+            ("G", 11),
+        ] {
+            assert_eq!(total_slack[&TaskLabel::new(task)], slack, "{}", task);
+        }
+        assert_eq!(analysis.slack_histogram(&[0, 5, 20]), vec![3, 0, 4, 0]);
+        assert_eq!(analysis.slack_histogram(&[0, 7, 8]), vec![3, 1, 1, 2]);
+        assert_eq!(analysis.slack_histogram(&[]), vec![7]);
+    }
 
-        // All CPs have equal duration, lexicographically smaller ones come first.
-        //    /--> B -> D ->- >H
-        //   /     \        /
-        //  A       > --- >F --->---> I
-        //   \     /                /
-        //    \--> C -> G -------->
+    #[test]
+    fn free_slack() {
+        //  A(2) -> B(10) -> D(1)
+        //       \-> C(3) -/
+        //  F(1) -> G(1)
         let ords = &[
             "A".arrow("B"),
             "A".arrow("C"),
             "B".arrow("D"),
-            "B".arrow("F"),
-            "C".arrow("F"),
-            "C".arrow("G"),
-            "F".arrow("H"),
-            "D".arrow("H"),
-            "G".arrow("I"),
-            "F".arrow("I"),
+            "C".arrow("D"),
+            "F".arrow("G"),
         ];
+        let durs = &[("A", 2), ("B", 10), ("C", 3), ("D", 1), ("F", 1), ("G", 1)];
+        let analysis = analyze(ords, durs).unwrap();
+        let free_slack = analysis.free_slack();
+        let total_slack = analysis.total_slack();
+        // F can slip as much as G can, but G starts as soon as F completes
+        assert_eq!(total_slack[&TaskLabel::new("F")], 11);
+        assert_eq!(free_slack[&TaskLabel::new("F")], 0);
+        for &(task, slack) in &[("A", 0), ("B", 0), ("C", 7), ("D", 0), ("G", 11)] {
+            assert_eq!(free_slack[&TaskLabel::new(task)], slack, "{}", task);
+        }
+
+        let ords = &["A".arrow("C"), "B".arrow("C")];
+        let durs = &[("A", 1), ("B", 2), ("C", 1)];
+        let weights = &[(("A", "C"), 3)];
+        let analysis = analyze_weighted(ords, durs, weights).unwrap();
+        assert_eq!(analysis.free_slack()[&TaskLabel::new("A")], 0);
+        assert_eq!(analysis.free_slack()[&TaskLabel::new("B")], 2);
+    }
+
+    #[test]
+    fn weighted_slack() {
+        let ords = &["A".arrow("C"), "B".arrow("C")];
+        let durs = &[("A", 1), ("B", 2), ("C", 1)];
+        let weights = &[(("A", "C"), 3)];
+        let analysis = analyze_weighted(ords, durs, weights).unwrap();
+        let total_slack = analysis.total_slack();
+        assert_eq!(total_slack[&TaskLabel::new("A")], 0);
+        assert_eq!(total_slack[&TaskLabel::new("B")], 2);
+        assert_eq!(total_slack[&TaskLabel::new("C")], 0);
+    }
+
+    #[test]
+    fn critical_parents() {
+        let ords = &example_1_orders();
         let durs = &[
-            ("A", 1u16),
-            ("B", 1),
-            ("C", 1),
-            ("D", 1),
-            ("F", 1),
+            ("Q", 1),
+            ("T", 1),
+            ("J", 2),
+            ("K", 1),
+            ("N", 1),
+            ("P", 1),
             ("H", 1),
-            ("G", 1),
             ("I", 1),
         ];
         let analysis = analyze(ords, durs).unwrap();
-        assert_eq!(analysis.max_parallelism, 3);
-        assert_eq!(analysis.task_count, 8);
-        assert_eq!(analysis.minimum_completion_time, 4);
-        assert_eq!(analysis.critical_path_count, 6);
+        let critical_parents = analysis.critical_parents();
+        // N depends on T as well, but J finishes later
+        assert_eq!(critical_parents[&TaskLabel::new("N")], labels(&["J"]));
+        let mut parents = critical_parents[&TaskLabel::new("I")].clone();
+        parents.sort_unstable();
+        assert_eq!(parents, labels(&["N", "P"]));
+        assert_eq!(critical_parents.get(&TaskLabel::new("Q")), None);
+    }
+
+    #[test]
+    fn analyzing_components() {
+        // A
+        // B
+        // D -> L
+        let ords = &["A".node(), "B".node(), "D".arrow("L")];
+        let durs = &[("A", 2), ("B", 3), ("D", 7), ("L", 1)];
+        let analysis = analyze(ords, durs).unwrap();
+        let components = analysis.components().unwrap();
+        assert_eq!(components.len(), 3);
         assert_eq!(
-            analysis.critical_paths,
-            paths(&[
-                "A->B->D->H",
-                "A->B->F->H",
-                "A->B->F->I",
-                "A->C->F->H",
-                "A->C->F->I",
-                "A->C->G->I"
-            ])
+            components
+                .iter()
+                .map(|component| (
+                    component.task_count,
+                    component.minimum_completion_time,
+                    component.max_parallelism
+                ))
+                .collect::<Vec<_>>(),
+            vec![(1, 2), (1, 3), (2, 8)]
+                .into_iter()
+                .map(|(task_count, makespan)| (task_count, makespan, 1))
+                .collect::<Vec<_>>()
         );
+        assert_eq!(components[2].critical_paths, paths(&["D->L"]));
+        assert_eq!(analysis.max_parallelism, 3);
+        assert_eq!(analysis.minimum_completion_time, 8);
 
-        // All CPs have equal duration.
-        //    /--> B -> D ->- >H
-        //   /     \        /
-        //  A       > --- >F --->---> I --> K
-        //   \     /                /
-        //    \--> C -> G -------->
+        // X -> Y      P -> Q
+        //   \           \
+        //    -> Z         -> R
+        // both components peak with 2 tasks, but not at the same time
         let ords = &[
+            "X".arrow("Y"),
+            "X".arrow("Z"),
+            "P".arrow("Q"),
+            "P".arrow("R"),
+        ];
+        let durs = &[("X", 1), ("Y", 1), ("Z", 1), ("P", 3), ("Q", 1), ("R", 1)];
+        let analysis = analyze(ords, durs).unwrap();
+        let components = analysis.components().unwrap();
+        assert_eq!(components[0].topological_order[0], TaskLabel::new("P"));
+        assert_eq!(components[0].max_parallelism, 2);
+        assert_eq!(components[1].max_parallelism, 2);
+        assert_eq!(analysis.max_parallelism, 3, "Y, Z and P execute at once");
+
+        // components are analyzed the same way as the schedule, keeping what their tasks carry
+        let task_orders = [
             "A".arrow("B"),
             "A".arrow("C"),
             "B".arrow("D"),
-            "B".arrow("F"),
-            "C".arrow("F"),
-            "C".arrow("G"),
-            "F".arrow("H"),
-            "D".arrow("H"),
-            "G".arrow("I"),
-            "F".arrow("I"),
-            "I".arrow("K"),
-        ];
-        let durs = &[
-            ("A", 1u16),
-            ("B", 1),
-            ("C", 1),
-            ("D", 1),
-            ("F", 1),
-            ("H", 1),
-            ("G", 1),
-            ("I", 1),
-            ("K", 0),
-        ];
+            "C".arrow("D"),
+        ]
+        .iter()
+        .cloned()
+        .chain(std::iter::once("X".node()))
+        .collect();
+        let task_durations = ["A", "B", "C", "D", "X"]
+            .iter()
+            .map(|&task| (TaskLabel::new(task), 1u16))
+            .collect();
+        let analysis = analyze_weighted_schedule_with_max_paths(
+            &task_orders,
+            &task_durations,
+            &HashMap::new(),
+            None,
+            1,
+        )
+        .unwrap()
+        .with_task_descriptions([(TaskLabel::new("A"), "first")].iter().cloned().collect())
+        .with_milestones([TaskLabel::new("X")].iter().cloned().collect());
+        let components = analysis.components().unwrap();
+        assert!(!components[0].critical_paths_listed());
+        assert_eq!(components[0].critical_path_count(), 2);
+        assert_eq!(components[0].task_descriptions().len(), 1);
+        assert!(components[1].task_descriptions().is_empty());
+        assert!(components[1].is_milestone(TaskLabel::new("X")));
+
+        let err = analyze_components(
+            &["A".arrow("B")].iter().cloned().collect(),
+            &[(TaskLabel::new("A"), 1u16)].iter().cloned().collect(),
+            &HashMap::new(),
+        )
+        .unwrap_err();
+        assert_eq!(err, AnalysisError::MissingDurations(labels(&["B"])));
+    }
+
+    #[test]
+    fn isolated_tasks() {
+        let ords = &["A".node(), "B".node(), "D".arrow("L")];
+        let durs = &[("A", 1), ("B", 2), ("D", 1), ("L", 1)];
         let analysis = analyze(ords, durs).unwrap();
-        assert_eq!(analysis.max_parallelism, 3);
-        assert_eq!(analysis.task_count, 9);
-        assert_eq!(analysis.minimum_completion_time, 4);
-        assert_eq!(analysis.critical_path_count, 6);
+        let isolated_tasks = analysis
+            .isolated_tasks()
+            .into_iter()
+            .collect::<HashSet<_>>();
         assert_eq!(
-            analysis.critical_paths,
-            paths(&[
-                "A->B->F->I->K",
-                "A->C->F->I->K",
-                "A->C->G->I->K",
-                "A->B->D->H",
-                "A->B->F->H",
-                "A->C->F->H"
-            ])
+            isolated_tasks,
+            vec![TaskLabel::new("A"), TaskLabel::new("B")]
+                .into_iter()
+                .collect()
         );
+
+        let ords = &["A".arrow("B")];
+        let durs = &[("A", 1), ("B", 1)];
+        assert!(analyze(ords, durs).unwrap().isolated_tasks().is_empty());
     }
 
     #[test]
-    fn multiple_sources_and_single_sink_path_schedules() {
-        // P -> T ->
-        //           \
-        // Z ------>  > D
-        //            /
-        //           /
-        // J ----->
+    fn forests() {
+        let ords = &["A".arrow("C"), "B".arrow("D")];
+        let durs = &[("A", 1), ("B", 1), ("C", 1), ("D", 1)];
+        assert!(analyze(ords, durs).unwrap().is_forest());
+
+        let ords = &["A".arrow("B"), "A".arrow("C"), "E".node()];
+        let durs = &[("A", 1), ("B", 1), ("C", 1), ("E", 1)];
+        assert!(analyze(ords, durs).unwrap().is_forest());
+
+        // D has three preceding tasks
         let ords = &[
             "P".arrow("T"),
             "T".arrow("D"),
             "Z".arrow("D"),
             "J".arrow("D"),
         ];
-        let durs = &[("P", 7u16), ("T", 19), ("D", 0), ("Z", 10), ("J", 26)];
-        let analysis = analyze(ords, durs).unwrap();
-        assert_eq!(analysis.max_parallelism, 3);
-        assert_eq!(analysis.task_count, 5);
-        assert_eq!(analysis.minimum_completion_time, 26);
-        assert_eq!(analysis.critical_path_count, 2);
-        assert_eq!(analysis.critical_paths, paths(&["P->T->D", "J->D"]));
+        let durs = &[("P", 1), ("T", 1), ("D", 1), ("Z", 1), ("J", 1)];
+        assert!(!analyze(ords, durs).unwrap().is_forest());
     }
 
     #[test]
-    fn zero_durations_and_no_task_ordering() {
-        let ords = &["A".node(), "B".node(), "C".node(), "D".node()];
-        let durs = &[("A", 0), ("B", 0), ("C", 0), ("D", 0)];
+    fn longest_task() {
+        let ords = &["A".arrow("B"), "C".node(), "D".node()];
+        let durs = &[("A", 3), ("B", 1), ("C", 5), ("D", 5)];
         let analysis = analyze(ords, durs).unwrap();
-        assert_eq!(analysis.max_parallelism, 4);
-        assert_eq!(analysis.task_count, 4);
-        assert_eq!(analysis.minimum_completion_time, 0);
-        assert_eq!(analysis.critical_path_count, 4);
-        assert_eq!(analysis.critical_paths, paths(&["A", "B", "C", "D"]));
+        assert_eq!(analysis.longest_task(), Some((TaskLabel::new("C"), 5)));
     }
 
     #[test]
-    fn flexible_fusion() {
-        // A -> B, where A is being fused to B later
-        let ords = &["A".node(), "B".node(), "A".arrow("B")];
-        let durs = &[("A", 2), ("B", 1)];
-        let analysis = analyze(ords, durs).unwrap();
-        assert_eq!(analysis.max_parallelism, 1);
-        assert_eq!(analysis.task_count, 2);
-        assert_eq!(analysis.minimum_completion_time, 3);
-        assert_eq!(analysis.critical_path_count, 1);
-        assert_eq!(analysis.critical_paths, paths(&["A->B"]));
-
-        // A -> B
-        let ords = &["A".arrow("B")];
-        let durs = &[("A", 2), ("B", 1)];
-        let analysis = analyze(ords, durs).unwrap();
-        assert_eq!(analysis.max_parallelism, 1);
-        assert_eq!(analysis.task_count, 2);
-        assert_eq!(analysis.minimum_completion_time, 3);
-        assert_eq!(analysis.critical_path_count, 1);
-        assert_eq!(analysis.critical_paths, paths(&["A->B"]));
-
-        // A -> B -> D, where A and B is fused later
-        let ords = &["A".node(), "B".node(), "B".arrow("D"), "A".arrow("B")];
-        let durs = &[("A", 2), ("B", 1), ("D", 3)];
+    fn longest_chain_len() {
+        let ords = &example_1_orders();
+        let durs = &example_1_durations();
         let analysis = analyze(ords, durs).unwrap();
-        assert_eq!(analysis.max_parallelism, 1);
-        assert_eq!(analysis.task_count, 3);
-        assert_eq!(analysis.minimum_completion_time, 6);
-        assert_eq!(analysis.critical_path_count, 1);
-        assert_eq!(analysis.critical_paths, paths(&["A->B->D"]));
-    }
-
-    #[test]
-    fn empty_input() {
-        let ords = &[];
-        let durs = &[];
-        let res = analyze(ords, durs);
-        assert!(matches!(res, Err(AnalysisError::EmptyInput)));
+        assert_eq!(
+            analysis.longest_chain_len(),
+            analysis.critical_paths[0].len()
+        );
+
+        // A(10) -> D(1) is critical, but B -> C -> D has more tasks
+        let ords = &["A".arrow("D"), "B".arrow("C"), "C".arrow("D")];
+        let durs = &[("A", 10), ("B", 1), ("C", 1), ("D", 1)];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(analysis.critical_paths, paths(&["A->D"]));
+        assert_eq!(analysis.longest_chain_len(), 3);
+
+        let ords = &["A".node()];
+        let durs = &[("A", 0)];
+        assert_eq!(analyze(ords, durs).unwrap().longest_chain_len(), 1);
     }
 
     #[test]
-    fn missing_durations() {
-        let ords = &["A".node(), "B".node(), "D".arrow("L")];
-        let durs = &[("A", 2), ("L", 1)];
-        let res = analyze(ords, durs);
-        match res {
-            Err(AnalysisError::MissingDurations(vec)) => assert_eq!(vec, labels(&["B", "D"])),
-            other => assert!(matches!(other, Err(AnalysisError::MissingDurations(_)))),
-        }
+    fn critical_tasks() {
+        let ords = &example_1_orders();
+        let durs = &example_1_durations();
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(
+            analysis.critical_tasks(),
+            labels(&["H", "I", "J", "K", "N", "P", "Q", "T"])
+        );
+        assert_eq!(analysis.task_duration(TaskLabel::new("Q")), Some(1));
+        assert_eq!(analysis.task_duration(TaskLabel::new("Z")), None);
 
-        let ords = &["A".node(), "B".node(), "D".arrow("L")];
-        let durs = &[];
-        let res = analyze(ords, durs);
-        match res {
-            Err(AnalysisError::MissingDurations(vec)) => {
-                assert_eq!(vec, labels(&["A", "B", "D", "L"]))
-            }
-            other => {
-                assert!(matches!(other, Err(AnalysisError::MissingDurations(_))));
-            }
-        }
+        let ords = &["A".arrow("B"), "C".node()];
+        let durs = &[("A", 1), ("B", 1), ("C", 1)];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(analysis.critical_tasks(), labels(&["A", "B"]));
     }
 
     #[test]
-    fn missing_orders() {
-        let ords = &["A".node(), "D".arrow("L")];
-        let durs = &[("A", 2), ("B", 3), ("D", 7), ("L", 1)];
-        let res = analyze(ords, durs);
-        match res {
-            Err(AnalysisError::MissingOrders(vec)) => assert_eq!(vec, labels(&["B"])),
-            other => assert!(matches!(other, Err(AnalysisError::MissingOrders(_)))),
-        }
+    fn duration_function() {
+        let ords = [
+            "a".arrow("bbb"),
+            "a".arrow("cc"),
+            "bbb".arrow("dddd"),
+            "cc".arrow("dddd"),
+        ]
+        .iter()
+        .cloned()
+        .collect::<HashSet<_>>();
+        let analysis =
+            analyze_schedule_with(&ords, |task| Some(task.as_ref().len() as Duration)).unwrap();
+        assert_eq!(analysis.task_count, 4);
+        assert_eq!(analysis.max_parallelism, 2);
+        assert_eq!(analysis.minimum_completion_time, 8);
+        assert_eq!(analysis.critical_paths, paths(&["a->bbb->dddd"]));
+        assert_eq!(analysis.task_duration(TaskLabel::new("cc")), Some(2));
 
-        let ords = &[];
-        let durs = &[("A", 2), ("L", 1)];
-        let res = analyze(ords, durs);
-        match res {
-            Err(AnalysisError::MissingOrders(vec)) => assert_eq!(vec, labels(&["A", "L"])),
-            other => assert!(matches!(other, Err(AnalysisError::MissingOrders(_)))),
-        }
+        let res = analyze_schedule_with(&ords, |task| {
+            Some(task.as_ref().len() as Duration).filter(|&duration| duration % 2 == 1)
+        });
+        let missing_tasks = labels(&["cc", "dddd"]);
+        assert!(matches!(res, Err(AnalysisError::MissingDurations(ref v)) if v == &missing_tasks));
+
+        let res = analyze_schedule_with(&HashSet::new(), |_| Some(1 as Duration));
+        assert!(matches!(res, Err(AnalysisError::EmptyInput)));
     }
 
-    #[quickcheck]
-    fn simple_auto_generated_schedules(
-        gen_labels: HashSet<String>,
-        gen_durations: Vec<Duration>,
-    ) -> TestResult {
-        {
-            let gen_labels_len = gen_labels.len();
-            if !(20..=100).contains(&gen_labels_len) {
-                return TestResult::discard();
-            }
+    #[test]
+    fn adjacency_map_input() {
+        let ords = &example_1_orders();
+        let durs = &example_1_durations();
+        let expected = analyze(ords, durs).unwrap();
 
-            if gen_durations.len() < gen_labels_len {
-                return TestResult::discard();
+        let mut adjacency = HashMap::new();
+        for task_order in ords {
+            let adjacent_tasks = adjacency.entry(task_order.first()).or_insert_with(Vec::new);
+            if let Some(second) = task_order.second() {
+                adjacent_tasks.push(second);
             }
         }
-
-        let str_labels = gen_labels
+        let task_durations = durs
             .iter()
-            .filter(|s| TaskLabel::try_from(s.as_str()).is_ok())
-            .map(|s| s.as_str())
-            .collect::<Vec<_>>();
-
-        if str_labels.is_empty() {
-            return TestResult::discard();
-        }
+            .map(|&(task, duration)| (TaskLabel::new(task), duration))
+            .collect();
+        let analysis = analyze_graph(adjacency, &task_durations).unwrap();
+        assert_eq!(analysis.task_count, expected.task_count);
+        assert_eq!(analysis.max_parallelism, expected.max_parallelism);
+        assert_eq!(
+            analysis.minimum_completion_time,
+            expected.minimum_completion_time
+        );
+        assert_eq!(analysis.critical_paths, expected.critical_paths);
+        assert_eq!(analysis.topological_order, expected.topological_order);
 
-        let task_count = str_labels.len();
-        let durations = gen_durations
+        // sink tasks do not need to be keys
+        let adjacency = [(TaskLabel::new("A"), labels(&["B"]))]
             .iter()
             .cloned()
-            .take(task_count)
-            .collect::<Vec<_>>();
-        let max_duration = *durations.iter().max().unwrap();
-        let mut critical_paths = str_labels
+            .collect();
+        let task_durations = [(TaskLabel::new("A"), 1u16), (TaskLabel::new("B"), 2)]
             .iter()
             .cloned()
-            .zip(gen_durations.iter().cloned())
-            .fold(
-                Vec::new(),
-                |mut paths: Vec<Vec<TaskLabel>>, (label, dur)| {
-                    if dur == max_duration {
-                        paths.push(vec![TaskLabel::new(label)]);
-                    }
-                    paths
-                },
-            );
-
-        critical_paths.sort_unstable_by(|path1, path2| {
-            path1
-                .iter()
-                .zip(path2.iter())
-                .map(|(str1, str2)| str1.cmp(str2))
-                .find(|cmp| *cmp != Ordering::Equal)
-                .unwrap()
-        });
+            .collect();
+        let analysis = analyze_graph(adjacency, &task_durations).unwrap();
+        assert_eq!(analysis.minimum_completion_time, 3);
+        assert_eq!(analysis.critical_paths, paths(&["A->B"]));
 
-        let ords = str_labels.iter().map(|l| l.node()).collect::<Vec<_>>();
-        let durs = str_labels
-            .into_iter()
-            .zip(gen_durations.into_iter())
-            .collect::<Vec<_>>();
-        let analysis = analyze(&ords, &durs).unwrap();
-        assert_eq!(analysis.max_parallelism, task_count);
-        assert_eq!(analysis.task_count, task_count);
         assert_eq!(
-            analysis.minimum_completion_time,
-            max_duration as TotalDuration
+            analyze_graph::<Duration>(HashMap::new(), &HashMap::new()).unwrap_err(),
+            AnalysisError::EmptyInput
         );
-        assert_eq!(analysis.critical_path_count, critical_paths.len());
-        assert_eq!(analysis.critical_paths, critical_paths);
-        TestResult::passed()
     }
 
     #[test]
-    fn cyclic_schedules() {
-        // A -> B -> A
-        let ords = &["A".arrow("B"), "B".arrow("A")];
-        let durs = &[("A", 5u16), ("B", 1)];
-        let res = analyze(ords, durs);
-        assert_eq!(res.unwrap_err(), AnalysisError::Cycle);
+    fn near_critical_paths() {
+        let ords = &example_1_orders();
+        let durs = &example_1_durations();
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(&analysis.near_critical_paths(0), analysis.critical_paths());
 
-        // A -> C
-        //        \
-        // B ----- -> D -> A
+        // A(3) -> B(1) -> D(1), A -> C(2) -> D, E(1)
         let ords = &[
+            "A".arrow("B"),
             "A".arrow("C"),
             "B".arrow("D"),
             "C".arrow("D"),
-            "D".arrow("A"),
+            "E".node(),
         ];
-        let durs = &[("A", 5u16), ("B", 1), ("C", 1), ("D", 7)];
-        let res = analyze(ords, durs);
-        assert_eq!(res.unwrap_err(), AnalysisError::Cycle);
+        let durs = &[("A", 3), ("B", 1), ("C", 2), ("D", 1), ("E", 1)];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(&analysis.near_critical_paths(0), analysis.critical_paths());
+        assert_eq!(
+            analysis.near_critical_paths(1),
+            paths(&["A->C->D", "A->B->D"])
+        );
+        assert_eq!(
+            analysis.near_critical_paths(5),
+            paths(&["A->C->D", "A->B->D", "E"])
+        );
+        assert_eq!(analysis.path_duration(&labels(&["A", "B", "D"])), 5);
+    }
 
-        // A -> C -> D -> B -> A
-        let ords = &[
-            "A".arrow("C"),
-            "C".arrow("D"),
-            "D".arrow("B"),
-            "B".arrow("A"),
-        ];
-        let durs = &[("A", 5u16), ("B", 1), ("C", 1), ("D", 7)];
-        let res = analyze(ords, durs);
-        assert_eq!(res.unwrap_err(), AnalysisError::Cycle);
+    #[test]
+    fn weighted_near_critical_paths() {
+        let ords = &["A".arrow("C"), "B".arrow("C")];
+        let durs = &[("A", 1), ("B", 2), ("C", 1)];
+        let weights = &[(("A", "C"), 3)];
+        let analysis = analyze_weighted(ords, durs, weights).unwrap();
+        assert_eq!(analysis.critical_paths(), &paths(&["A->C"]));
+        assert_eq!(analysis.near_critical_paths(1), paths(&["A->C"]));
+        assert_eq!(analysis.near_critical_paths(2), paths(&["A->C", "B->C"]));
+    }
 
-        //       --> L --->
-        //      /         |
-        // K -> ---> T --->
-        let ords = &[
-            "K".arrow("L"),
-            "K".arrow("T"),
-            "L".arrow("T"),
-            "T".arrow("L"),
-        ];
-        let durs = &[("K", 5u16), ("L", 1), ("T", 1)];
-        let res = analyze(ords, durs);
-        assert_eq!(res.unwrap_err(), AnalysisError::Cycle);
+    #[test]
+    fn single_critical_path_rendering() {
+        let ords = &["A".arrow("C"), "B".arrow("D")];
+        let durs = &[("A", 5u16), ("B", 1), ("C", 9), ("D", 7)];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(
+            analysis.to_string(),
+            "task_count: 4\n\
+             max_parallelism: 2\n\
+             minimum_completion_time: 14\n\
+             critical_path_count: 1\n\
+             critical_paths:\n\
+             1)\n\
+             A->C\n"
+        );
+    }
+
+    #[test]
+    fn multiple_critical_paths_rendering() {
+        let ords = &["A".arrow("B"), "A".arrow("C"), "K".node()];
+        let durs = &[("A", 1u16), ("B", 2), ("C", 2), ("K", 3)];
+        let analysis = analyze(ords, durs).unwrap();
+        assert_eq!(
+            analysis.to_string(),
+            "task_count: 4\n\
+             max_parallelism: 3\n\
+             minimum_completion_time: 3\n\
+             critical_path_count: 3\n\
+             critical_paths:\n\
+             1)\n\
+             A->B\n\
+             \n\
+             2)\n\
+             A->C\n\
+             \n\
+             3)\n\
+             K\n"
+        );
     }
 
     #[test]
@@ -991,6 +4289,101 @@ pub mod tests {
         assert_eq!(vec_str[0], "BB");
     }
 
+    #[test]
+    fn path_elision() {
+        let chain = (b'A'..=b'Z')
+            .map(|letter| (letter as char).to_string())
+            .collect::<Vec<_>>();
+        let path = chain
+            .iter()
+            .map(|label| TaskLabel::new(label))
+            .collect::<Vec<_>>();
+        let mut buf = String::new();
+        serialize_path(
+            &elide_path(&path, Some(4)),
+            &mut buf,
+            "->",
+            TaskLabel::MAX_LEN,
+        )
+        .unwrap();
+        assert_eq!(buf, "A->B->...->Y->Z\n");
+        assert_eq!(
+            elide_path(&path, Some(3)).as_ref(),
+            &labels(&["A", "B", "...", "Z"])[..]
+        );
+        assert_eq!(elide_path(&path, Some(26)).as_ref(), &path[..]);
+        assert_eq!(elide_path(&path, None).as_ref(), &path[..]);
+
+        let orders = chain
+            .windows(2)
+            .map(|pair| TaskLabel::new(&pair[0]).arrow(TaskLabel::new(&pair[1])))
+            .collect::<HashSet<_>>();
+        let durations = path.iter().map(|&task| (task, 1 as Duration)).collect();
+        let analysis = analyze_schedule(&orders, &durations).unwrap();
+        assert_eq!(analysis.critical_paths()[0].len(), 26);
+        assert_eq!(analysis.minimum_completion_time(), 26);
+        let mut buf = String::new();
+        analysis
+            .write_text(&mut buf, &|path, buffer| {
+                serialize_path(&elide_path(path, Some(4)), buffer, "->", TaskLabel::MAX_LEN)
+            })
+            .unwrap();
+        assert!(buf.ends_with("critical_paths:\n1)\nA->B->...->Y->Z\n"));
+    }
+
+    #[test]
+    fn path_serialization_with_durations() {
+        let ords = &["A".arrow("C"), "B".arrow("C")];
+        let durs = &[("A", 5), ("B", 2), ("C", 9)];
+        let analysis = analyze(ords, durs).unwrap();
+        let path = &analysis.critical_paths()[0].clone();
+        let mut buf = String::new();
+        serialize_path_with_durations(
+            path,
+            analysis.task_durations(),
+            &mut buf,
+            "->",
+            TaskLabel::MAX_LEN,
+        )
+        .unwrap();
+        assert_eq!(buf, "A(5)->C(9)\n");
+
+        // durations count towards the length of the lines
+        let mut buf = String::new();
+        serialize_path_with_durations(path, &analysis.task_durations, &mut buf, "->", 4).unwrap();
+        assert_eq!(buf, "A(5)->\nC(9)\n");
+        let mut buf = String::new();
+        serialize_path_with_durations(path, &analysis.task_durations, &mut buf, "->", 10).unwrap();
+        assert_eq!(buf, "A(5)->C(9)\n");
+
+        // the ellipsis of an elided path has no duration
+        let ords = &["A".arrow("B"), "B".arrow("C")];
+        let durs = &[("A", 1), ("B", 2), ("C", 3)];
+        let analysis = analyze(ords, durs).unwrap();
+        let mut buf = String::new();
+        serialize_path_with_durations(
+            &elide_path(&analysis.critical_paths()[0], Some(2)),
+            analysis.task_durations(),
+            &mut buf,
+            "->",
+            TaskLabel::MAX_LEN,
+        )
+        .unwrap();
+        assert_eq!(buf, "A(1)->...->C(3)\n");
+    }
+
+    #[test]
+    fn path_serialization_long_labels() {
+        let path = labels(&["B", "DDDDDD", "C", "EE"]);
+        let mut buf = String::new();
+        serialize_path(&path, &mut buf, "->", 3).unwrap();
+        assert_eq!(buf, "B->\nDDDDDD->\nC->\nEE\n");
+
+        let mut buf = String::new();
+        serialize_path(&labels(&["DDDDDD"]), &mut buf, "->", 3).unwrap();
+        assert_eq!(buf, "DDDDDD\n");
+    }
+
     #[quickcheck]
     fn path_serialization_with_generated_input(vec: Vec<String>) -> TestResult {
         let path_strs = vec
@@ -1168,6 +4561,207 @@ pub mod tests {
             )
         }
 
+        // Q -> T -> K -> H
+        //  \    \     /
+        //   \    > N <
+        //    \  /     \
+        //     J -> P -> I
+        pub fn example_1_orders() -> Vec<TaskOrder<'static>> {
+            vec![
+                "Q".node(),
+                "Q".arrow("T"),
+                "Q".arrow("J"),
+                "T".arrow("K"),
+                "T".arrow("N"),
+                "J".arrow("N"),
+                "J".arrow("P"),
+                "K".arrow("H"),
+                "N".arrow("H"),
+                "N".arrow("I"),
+                "P".arrow("I"),
+            ]
+        }
+
+        pub fn example_1_durations() -> Vec<(&'static str, Duration)> {
+            ["Q", "T", "J", "K", "N", "P", "H", "I"]
+                .iter()
+                .map(|&task| (task, 1))
+                .collect()
+        }
+
+        pub fn analyze_weighted<'a, I, J, K>(
+            task_orders: I,
+            task_durations: J,
+            edge_weights: K,
+        ) -> Result<ScheduleAnalysis<'a>, AnalysisError<'a>>
+        where
+            I: IntoIterator<Item = &'a TaskOrder<'a>>,
+            J: IntoIterator<Item = &'a (&'a str, Duration)>,
+            K: IntoIterator<Item = &'a ((&'a str, &'a str), Duration)>,
+        {
+            analyze_weighted_schedule(
+                &task_orders.into_iter().cloned().collect(),
+                &task_durations
+                    .into_iter()
+                    .map(|&(s, d)| (TaskLabel::new(s), d))
+                    .collect(),
+                &edge_weights
+                    .into_iter()
+                    .map(|&((s1, s2), w)| ((TaskLabel::new(s1), TaskLabel::new(s2)), w))
+                    .collect(),
+            )
+        }
+
+        // SplitMix64, which is enough to generate schedules deterministically without a
+        // dependency
+        pub struct SeededRandom(u64);
+
+        impl SeededRandom {
+            pub fn new(seed: u64) -> Self {
+                SeededRandom(seed)
+            }
+
+            pub fn next(&mut self) -> u64 {
+                self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+                let mut z = self.0;
+                z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+                z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+                z ^ (z >> 31)
+            }
+
+            // in [0, bound)
+            pub fn below(&mut self, bound: usize) -> usize {
+                (self.next() % bound as u64) as usize
+            }
+        }
+
+        /// Connected DAG with relations between tasks given by their indices, where the tasks
+        /// come in a topological order. Labels are shuffled, so that the label order tells
+        /// nothing about the topological order.
+        pub struct RandomSchedule {
+            pub labels: Vec<String>,
+            pub durations: Vec<Duration>,
+            pub relations: Vec<(usize, usize)>,
+            pub weights: Vec<((usize, usize), Duration)>,
+        }
+
+        impl RandomSchedule {
+            /// Each task but the first depends on a random earlier task, and `extra_relations`
+            /// more relations between random tasks are added on top, some of which are weighted.
+            /// Durations are between 0 and 9.
+            pub fn generate(seed: u64, task_count: usize, extra_relations: usize) -> Self {
+                let mut random = SeededRandom::new(seed);
+                let mut ids = (0..task_count).collect::<Vec<_>>();
+                for idx in (1..ids.len()).rev() {
+                    ids.swap(idx, random.below(idx + 1));
+                }
+                let labels = ids.iter().map(|id| format!("T{}", id)).collect();
+                let durations = (0..task_count)
+                    .map(|_| random.below(10) as Duration)
+                    .collect();
+                let mut relations = (1..task_count)
+                    .map(|task_idx| (random.below(task_idx), task_idx))
+                    .collect::<Vec<_>>();
+                for _ in 0..extra_relations {
+                    let first = random.below(task_count);
+                    let second = random.below(task_count);
+                    if first != second {
+                        relations.push((first.min(second), first.max(second)));
+                    }
+                }
+                relations.sort_unstable();
+                relations.dedup();
+                let weights = relations
+                    .iter()
+                    .filter_map(|&relation| {
+                        if random.below(4) == 0 {
+                            Some((relation, random.below(5) as Duration))
+                        } else {
+                            None
+                        }
+                    })
+                    .collect();
+                RandomSchedule {
+                    labels,
+                    durations,
+                    relations,
+                    weights,
+                }
+            }
+
+            pub fn analyze(&self) -> Result<ScheduleAnalysis<'_>, AnalysisError<'_>> {
+                let label = |task_idx: usize| TaskLabel::new(&self.labels[task_idx]);
+                let mut task_orders = (0..self.labels.len())
+                    .map(|task_idx| label(task_idx).node())
+                    .collect::<HashSet<_>>();
+                task_orders.extend(
+                    self.relations
+                        .iter()
+                        .map(|&(first, second)| label(first).arrow(label(second))),
+                );
+                analyze_weighted_schedule(
+                    &task_orders,
+                    &self
+                        .durations
+                        .iter()
+                        .enumerate()
+                        .map(|(task_idx, &duration)| (label(task_idx), duration))
+                        .collect(),
+                    &self
+                        .weights
+                        .iter()
+                        .map(|&((first, second), weight)| ((label(first), label(second)), weight))
+                        .collect(),
+                )
+            }
+
+            /// Paths from the tasks without a preceding task to the tasks without a following
+            /// task, along with their durations, by enumerating all of them
+            /// Time: O(2^V) in the worst case
+            pub fn all_paths(&self) -> Vec<(Vec<TaskLabel<'_>>, TotalDuration)> {
+                fn extend<'a>(
+                    schedule: &'a RandomSchedule,
+                    path: &mut Vec<usize>,
+                    duration: TotalDuration,
+                    paths: &mut Vec<(Vec<TaskLabel<'a>>, TotalDuration)>,
+                ) {
+                    let task_idx = *path.last().unwrap();
+                    let duration = duration + TotalDuration::from(schedule.durations[task_idx]);
+                    let next_tasks = schedule
+                        .relations
+                        .iter()
+                        .filter(|&&(first, _)| first == task_idx)
+                        .map(|&(_, second)| second)
+                        .collect::<Vec<_>>();
+                    if next_tasks.is_empty() {
+                        let labels = path
+                            .iter()
+                            .map(|&task_idx| TaskLabel::new(&schedule.labels[task_idx]))
+                            .collect();
+                        paths.push((labels, duration));
+                    }
+                    for next_task in next_tasks {
+                        let weight = schedule
+                            .weights
+                            .iter()
+                            .find(|&&(relation, _)| relation == (task_idx, next_task))
+                            .map_or(0, |&(_, weight)| TotalDuration::from(weight));
+                        path.push(next_task);
+                        extend(schedule, path, duration + weight, paths);
+                        path.pop();
+                    }
+                }
+
+                let mut paths = Vec::new();
+                for source in (0..self.labels.len()).filter(|&task_idx| {
+                    self.relations.iter().all(|&(_, second)| second != task_idx)
+                }) {
+                    extend(self, &mut vec![source], 0, &mut paths);
+                }
+                paths
+            }
+        }
+
         pub fn labels<'a, I>(strs: I) -> Vec<TaskLabel<'a>>
         where
             I: IntoIterator<Item = &'a &'a str>,
@@ -1180,11 +4774,7 @@ pub mod tests {
             I: IntoIterator<Item = &'a &'a str>,
         {
             strs.into_iter()
-                .map(|&str| {
-                    str.split("->")
-                        .map(|str| TaskLabel::new(str))
-                        .collect::<Vec<_>>()
-                })
+                .map(|&str| str.split("->").map(TaskLabel::new).collect::<Vec<_>>())
                 .collect()
         }
     }
@@ -0,0 +1,101 @@
+use crate::analyzer::ScheduleAnalysis;
+use crate::parser::ParsedData;
+use crate::task::TaskLabel;
+use std::collections::BTreeMap;
+
+/// Relations that add nothing to the schedule, which tells apart
+/// - relations declared more than once, e.g., both as `B(1) after [A]` and `A -> B`, which are
+///   merged before the analysis
+/// - relations implied by other relations, e.g., `A -> C` along with `A -> B` and `B -> C`, which
+///   are kept by the analysis, but don't constrain the schedule any further
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct StructuralAudit<'a> {
+    /// ((task, dependent task), number of declarations), in label order
+    pub duplicate_relations: Vec<((TaskLabel<'a>, TaskLabel<'a>), usize)>,
+    /// (task, dependent task, intermediate task), in label order. See
+    /// `ScheduleAnalysis::redundant_relations`.
+    pub redundant_relations: Vec<(TaskLabel<'a>, TaskLabel<'a>, TaskLabel<'a>)>,
+}
+
+impl<'a> StructuralAudit<'a> {
+    /// Duplicates are found in the records as they are declared, while redundant relations are
+    /// found in the analyzed schedule
+    /// Time: O(R * logR + E * (V + E)), where R is the number of declared relations
+    pub fn new(data: &ParsedData<'a>, analysis: &ScheduleAnalysis<'a>) -> Self {
+        let mut declaration_counts = BTreeMap::new();
+        for &(first, second) in data.task_orders() {
+            if let Some(second) = second {
+                *declaration_counts.entry((first, second)).or_insert(0) += 1;
+            }
+        }
+        StructuralAudit {
+            duplicate_relations: declaration_counts
+                .into_iter()
+                .filter(|&(_, count)| count > 1)
+                .collect(),
+            redundant_relations: analysis.redundant_relations(),
+        }
+    }
+
+    pub fn warnings(&self) -> Vec<String> {
+        let duplicate_warnings = self
+            .duplicate_relations
+            .iter()
+            .map(|((first, second), count)| {
+                format!(
+                    "Duplicate relation: {} -> {}, declared {} times",
+                    first.as_ref(),
+                    second.as_ref(),
+                    count
+                )
+            });
+        let redundant_warnings =
+            self.redundant_relations
+                .iter()
+                .map(|(first, second, intermediate)| {
+                    format!(
+                        "Redundant relation: {} -> {}, implied by {} -> {} -> {}",
+                        first.as_ref(),
+                        second.as_ref(),
+                        first.as_ref(),
+                        intermediate.as_ref(),
+                        second.as_ref()
+                    )
+                });
+        duplicate_warnings.chain(redundant_warnings).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ScheduleParser;
+    use crate::processor::process;
+    use std::fs;
+
+    #[test]
+    fn auditing_schedule_from_file_32() {
+        let unparsed_content = fs::read_to_string(format!(
+            "{}/resources/test/example32.tasks.in",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap();
+        let data = ScheduleParser::parse_content(&unparsed_content).unwrap();
+        let analysis = process(&unparsed_content).unwrap();
+        let audit = StructuralAudit::new(&data, &analysis);
+        let (a, b, c) = (
+            TaskLabel::new("A"),
+            TaskLabel::new("B"),
+            TaskLabel::new("C"),
+        );
+        assert_eq!(audit.duplicate_relations, vec![((a, b), 2)]);
+        assert_eq!(audit.redundant_relations, vec![(a, c, b)]);
+        assert_eq!(
+            audit.warnings(),
+            vec![
+                "Duplicate relation: A -> B, declared 2 times",
+                "Redundant relation: A -> C, implied by A -> B -> C",
+            ]
+        );
+    }
+}
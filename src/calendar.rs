@@ -0,0 +1,224 @@
+use crate::analyzer::ScheduleAnalysis;
+use chrono::{DateTime, Duration as ChronoDuration, NaiveDateTime};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// content lines longer than this many octets are folded, excluding the line break
+const MAX_LINE_LEN: usize = 75;
+const DATE_TIME_FORMAT: &str = "%Y%m%dT%H%M%S";
+
+/// What a unit of duration stands for on the calendar
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum TimeUnit {
+    Seconds,
+    Minutes,
+    #[default]
+    Hours,
+    Days,
+}
+
+impl TimeUnit {
+    fn to_duration(self, units: i64) -> ChronoDuration {
+        match self {
+            TimeUnit::Seconds => ChronoDuration::seconds(units),
+            TimeUnit::Minutes => ChronoDuration::minutes(units),
+            TimeUnit::Hours => ChronoDuration::hours(units),
+            TimeUnit::Days => ChronoDuration::days(units),
+        }
+    }
+}
+
+impl FromStr for TimeUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "seconds" => Ok(TimeUnit::Seconds),
+            "minutes" => Ok(TimeUnit::Minutes),
+            "hours" => Ok(TimeUnit::Hours),
+            "days" => Ok(TimeUnit::Days),
+            unknown => Err(format!("Unknown time unit: {}", unknown)),
+        }
+    }
+}
+
+/// Where the schedule is placed on the calendar
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct CalendarSettings {
+    /// when the tasks without preceding tasks start, in local time
+    pub start: NaiveDateTime,
+    pub unit: TimeUnit,
+    /// when the calendar is created, in UTC
+    pub stamp: NaiveDateTime,
+}
+
+/// Current date and time in UTC, to stamp calendars with
+pub fn utc_now() -> NaiveDateTime {
+    let since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    DateTime::from_timestamp(since_epoch.as_secs() as i64, 0)
+        .map(|now| now.naive_utc())
+        .unwrap_or_default()
+}
+
+/// Parses "2024-01-31T09:00" with optional seconds
+pub fn parse_start(value: &str) -> Result<NaiveDateTime, String> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M"))
+        .map_err(|_| {
+            format!(
+                "Expected a date and time such as 2024-01-31T09:00, found: {}",
+                value
+            )
+        })
+}
+
+/// iCalendar (RFC 5545) with an event per task, in topological order. Each task starts as soon
+/// as the tasks it depends on complete. Times are floating, i.e., they are in the local time of
+/// whoever opens the calendar. Schedules running past the latest date that can be represented
+/// are rejected.
+pub fn render_calendar(
+    analysis: &ScheduleAnalysis,
+    settings: CalendarSettings,
+) -> Result<String, String> {
+    let earliest_times = analysis.earliest_times();
+    let date_time_at = |task: &str, offset| {
+        settings
+            .start
+            .checked_add_signed(settings.unit.to_duration(i64::from(offset)))
+            .map(|date_time| date_time.format(DATE_TIME_FORMAT).to_string())
+            .ok_or_else(|| format!("Task does not fit on the calendar: {}", task))
+    };
+    let stamp = settings.stamp.format(DATE_TIME_FORMAT);
+    let mut lines = vec![
+        String::from("BEGIN:VCALENDAR"),
+        String::from("VERSION:2.0"),
+        String::from("PRODID:-//analyze-task-schedule//EN"),
+    ];
+    for task in analysis.topological_order() {
+        let (start, finish) = earliest_times[task];
+        lines.push(String::from("BEGIN:VEVENT"));
+        lines.push(format!("UID:{}@analyze-task-schedule", escape_text(task)));
+        // has to be in UTC, unlike the other times
+        lines.push(format!("DTSTAMP:{}Z", stamp));
+        lines.push(format!("DTSTART:{}", date_time_at(task, start)?));
+        lines.push(format!("DTEND:{}", date_time_at(task, finish)?));
+        lines.push(format!("SUMMARY:{}", escape_text(task)));
+        lines.push(String::from("END:VEVENT"));
+    }
+    lines.push(String::from("END:VCALENDAR"));
+    Ok(lines
+        .iter()
+        .map(|line| fold_line(line))
+        .collect::<Vec<_>>()
+        .join("\r\n"))
+}
+
+// Backslashes, semicolons, commas and line breaks are escaped in text values
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Long lines continue on the next line after a space. Multi-octet characters are not split.
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut line_len = 0;
+    for c in line.chars() {
+        if line_len + c.len_utf8() > MAX_LINE_LEN {
+            folded.push_str("\r\n ");
+            // the leading space counts towards the length of the continuation line
+            line_len = 1;
+        }
+        folded.push(c);
+        line_len += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processor::process;
+
+    #[test]
+    fn calendar_rendering() {
+        let analysis = process("K(1)\nL(12) after [K]\nT(5) after [K]\nF(20) after [T]").unwrap();
+        let settings = CalendarSettings {
+            start: parse_start("2024-01-31T09:00").unwrap(),
+            unit: TimeUnit::Hours,
+            stamp: parse_start("2024-01-30T17:45:12").unwrap(),
+        };
+        let calendar = render_calendar(&analysis, settings).unwrap();
+        let lines = calendar.split("\r\n").collect::<Vec<_>>();
+        assert_eq!(lines.first(), Some(&"BEGIN:VCALENDAR"));
+        assert_eq!(lines.last(), Some(&"END:VCALENDAR"));
+        assert_eq!(
+            lines.iter().filter(|&&line| line == "BEGIN:VEVENT").count(),
+            4
+        );
+        let f_event = calendar
+            .split("BEGIN:VEVENT")
+            .find(|event| event.contains("SUMMARY:F"))
+            .unwrap();
+        // F starts once K and T complete, 6 hours in
+        assert!(f_event.contains("DTSTART:20240131T150000\r\n"));
+        assert!(f_event.contains("DTEND:20240201T110000\r\n"));
+        // the stamp is when the calendar is created rather than when the schedule starts
+        assert!(f_event.contains("DTSTAMP:20240130T174512Z\r\n"));
+
+        let settings = CalendarSettings {
+            unit: TimeUnit::Minutes,
+            ..settings
+        };
+        let calendar = render_calendar(&analysis, settings).unwrap();
+        assert!(calendar.contains("DTSTART:20240131T090600\r\nDTEND:20240131T092600"));
+
+        // F ends past the latest date that can be represented when the schedule starts near it
+        let settings = CalendarSettings {
+            start: NaiveDateTime::MAX - ChronoDuration::days(20),
+            unit: TimeUnit::Days,
+            ..settings
+        };
+        assert_eq!(
+            render_calendar(&analysis, settings).unwrap_err(),
+            "Task does not fit on the calendar: F"
+        );
+    }
+
+    #[test]
+    fn text_escaping_and_line_folding() {
+        assert_eq!(escape_text("a,b;c\\d\ne"), "a\\,b\\;c\\\\d\\ne");
+        assert_eq!(fold_line("SUMMARY:A"), "SUMMARY:A");
+
+        let line = format!("SUMMARY:{}", "太".repeat(30));
+        let folded = fold_line(&line);
+        let folded_lines = folded.split("\r\n").collect::<Vec<_>>();
+        assert_eq!(folded_lines.len(), 2);
+        assert!(folded_lines.iter().all(|line| line.len() <= MAX_LINE_LEN));
+        assert!(folded_lines[1].starts_with(' '));
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+
+    #[test]
+    fn start_parsing() {
+        assert_eq!(
+            parse_start("2024-01-31T09:00").unwrap(),
+            parse_start("2024-01-31T09:00:00").unwrap()
+        );
+        assert!(parse_start("2024-01-31").is_err());
+        assert_eq!("days".parse(), Ok(TimeUnit::Days));
+        assert!("weeks".parse::<TimeUnit>().is_err());
+    }
+}
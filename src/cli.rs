@@ -0,0 +1,1088 @@
+use crate::calendar;
+use crate::processor::ProcessOptions;
+use crate::remote;
+use crate::render::{Format, PathStyle, View, DEFAULT_PRECISION, MAX_PRECISION};
+use crate::task::{Duration, TotalDuration};
+use std::iter::Peekable;
+use std::slice::Iter;
+
+pub const USAGE: &str = "usage: ./analyze-task-schedule [options] file|url
+       ./analyze-task-schedule [options] --input-glob pattern
+options:
+    --set task=duration              override the duration of a task, can be repeated
+    --scale pattern=factor           multiply the durations of the tasks matching pattern, where
+                                     a trailing * matches any suffix, can be repeated
+    --strict-labels                  warn about labels that are likely to be typos
+    --strict                         warn about tasks declared more than once with the same duration
+    --strict-numbers                 reject durations and weights with leading zeros, such as 022
+    --record-separator newline|semicolon
+                                     what separates records, defaults to newline
+    --audit                          warn about relations declared more than once and relations
+                                     implied by other relations
+    --lenient                        report all the problems keeping the schedule from being
+                                     analyzed, rather than the first one
+    --require-positive-makespan      reject schedules that take no time to complete
+    --warn-zero-critical             warn about critical tasks that take no time
+    --profile                        print the time spent in each stage to stderr
+    --preserve-order                 order critical paths with a single task as they are declared
+    --max-duration N                 reject tasks taking longer than N
+    --infer-leaf-duration N          give the tasks that are only referenced as dependencies a
+                                     duration of N rather than rejecting them
+    --require-tree                   reject schedules where a task depends on more than one task
+    --allowed-labels labels_file     reject labels that are not listed in labels_file, one per line
+    --durations-overlay csv_file     override durations with the label,duration lines of csv_file,
+                                     can be repeated, later files winning
+    --deadline N                     report whether the schedule can complete by N
+    --strict-deadline N              reject schedules that cannot complete by N
+    --max-paths N                    only count the critical paths when there are more than N
+    --sort-paths length|lex|first    order of the critical paths: longest first (default),
+                                     lexicographical, or by their first task
+    --max-depth N                    print paths with more than N tasks as their first and last
+                                     tasks with ... in between
+    --with-durations                 print the duration of each task of a path after its label,
+                                     such as A(5)->C(9)
+    --format text|json|jsonl|ics|adjacency
+                                     output format, defaults to text
+    --start YYYY-MM-DDTHH:MM         when the schedule starts on the calendar, required by ics
+    --time-unit seconds|minutes|hours|days
+                                     what a unit of duration stands for on the calendar,
+                                     defaults to hours
+    --order forward|reverse          print tasks in topological order instead of the analysis
+    --near N                         print paths taking at most N less than the critical paths
+    --top-paths-by-duration          print the longest path leading to each sink task
+    --tree                           print the critical paths as a tree of their shared prefixes
+    --metrics                        print statistics about the tasks instead of the analysis
+    --precision N                    print floating-point metrics with N decimals, 2 by default,
+                                     17 at most
+    --slack-histogram                print the number of tasks per slack bucket
+    --value-progress                 print the share of the value of the schedule delivered by each
+                                     time tasks complete
+    --explain                        print what drives the minimum completion time
+    --assign N                       print the tasks assigned to N workers and when they start
+    --workers N                      print how well N workers are kept busy until the schedule
+                                     completes
+    --waves                          print the tasks grouped into waves, where each wave can start
+                                     once the previous waves complete
+    --path N                         print only the Nth critical path, starting from 1
+    --components                     analyze each group of related tasks on its own
+    --verify                         confirm that the schedule is acyclic and complete, along with
+                                     its number of tasks and ordering constraints
+    --oneline                        print the task count, maximum parallelism, minimum completion
+                                     time and critical path count on a single line
+    --diff other_file                print how the analysis of other_file differs from file's
+    --watch                          analyze the file again whenever it changes
+    --list-tasks                     print each task with its duration, sorted by label, without
+                                     analyzing the schedule
+    --duration-histogram             print the number of tasks per duration bucket, where buckets
+                                     double in size, without analyzing the schedule
+    --input-glob pattern             analyze each file matching pattern on its own and print a
+                                     row per file with its minimum completion time, maximum
+                                     parallelism and whether it could be analyzed
+    -o, --output path                write the output to path instead of stdout, replacing the
+                                     file if it exists
+    --github                         also print errors as GitHub Actions annotations pointing to
+                                     the lines they are found on, and a summary on success. JSON
+                                     formats require --output";
+
+#[derive(Debug)]
+pub struct Options {
+    /// file to analyze, or the pattern of the files to analyze with `input_glob`
+    pub file_path: String,
+    pub process_options: ProcessOptions,
+    pub format: Format,
+    pub view: View,
+    /// how paths are written out in text
+    pub path_style: PathStyle,
+    /// schedule to compare the analyzed schedule with
+    pub diff_path: Option<String>,
+    /// analyze the file again whenever it changes
+    pub watch: bool,
+    /// file listing the labels the schedule can use
+    pub allowed_labels_path: Option<String>,
+    /// `label,duration` files whose durations replace the durations found in the input, in order
+    pub duration_overlay_paths: Vec<String>,
+    /// analyze the files matching `file_path` and report on all of them at once
+    pub input_glob: bool,
+    /// list the tasks along with their durations instead of analyzing the schedule
+    pub list_tasks: bool,
+    /// print the number of tasks per duration bucket instead of analyzing the schedule
+    pub duration_histogram: bool,
+    /// file to write the output to instead of stdout
+    pub output_path: Option<String>,
+    /// also print errors and a summary as GitHub Actions workflow commands
+    pub github: bool,
+}
+
+/// Parses command line arguments, excluding the program name
+pub fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut file_path = None;
+    let mut process_options = ProcessOptions::default();
+    let mut format = Format::default();
+    let mut view = View::default();
+    let mut path_style = PathStyle::default();
+    let mut diff_path = None;
+    let mut watch = false;
+    let mut allowed_labels_path = None;
+    let mut duration_overlay_paths = Vec::new();
+    let mut input_glob = None;
+    let mut list_tasks = false;
+    let mut duration_histogram = false;
+    let mut output_path = None;
+    let mut github = false;
+    let mut start = None;
+    let mut time_unit = None;
+    let mut precision = None;
+    let mut args_iter = args.iter().peekable();
+    while let Some(arg) = args_iter.next() {
+        match arg.as_str() {
+            "--set" => {
+                let value = next_value(&mut args_iter, arg)?;
+                process_options
+                    .duration_overrides
+                    .push(parse_duration_override(value)?);
+            }
+            "--scale" => {
+                let value = next_value(&mut args_iter, arg)?;
+                process_options
+                    .duration_scales
+                    .push(parse_duration_scale(value)?);
+            }
+            "--strict-labels" => process_options.strict_labels = true,
+            "--strict" => process_options.strict = true,
+            "--strict-numbers" => process_options.strict_numbers = true,
+            "--record-separator" => {
+                process_options.record_separator = next_value(&mut args_iter, arg)?.parse()?
+            }
+            "--lenient" => process_options.lenient = true,
+            "--audit" => process_options.audit = true,
+            "--require-positive-makespan" => process_options.require_positive_makespan = true,
+            "--warn-zero-critical" => process_options.warn_zero_critical = true,
+            "--profile" => process_options.profile = true,
+            "--preserve-order" => process_options.preserve_order = true,
+            "--require-tree" => process_options.require_tree = true,
+            "--allowed-labels" => {
+                allowed_labels_path = Some(next_value(&mut args_iter, arg)?.to_string());
+            }
+            "--durations-overlay" => {
+                duration_overlay_paths.push(next_value(&mut args_iter, arg)?.to_string());
+            }
+            "--max-duration" => {
+                let value = next_value(&mut args_iter, arg)?;
+                let max_duration = value.parse::<Duration>().map_err(|_| {
+                    format!("Expected a duration for --max-duration, found: {}", value)
+                })?;
+                process_options.max_duration = Some(max_duration);
+            }
+            "--infer-leaf-duration" => {
+                let value = next_value(&mut args_iter, arg)?;
+                let leaf_duration = value.parse::<Duration>().map_err(|_| {
+                    format!(
+                        "Expected a duration for --infer-leaf-duration, found: {}",
+                        value
+                    )
+                })?;
+                process_options.infer_leaf_duration = Some(leaf_duration);
+            }
+            "--deadline" | "--strict-deadline" => {
+                let value = next_value(&mut args_iter, arg)?;
+                let deadline = value
+                    .parse::<TotalDuration>()
+                    .map_err(|_| format!("Expected a duration for {}, found: {}", arg, value))?;
+                process_options.deadline = Some(deadline);
+                process_options.strict_deadline = arg == "--strict-deadline";
+            }
+            "--max-paths" => {
+                let value = next_value(&mut args_iter, arg)?;
+                let max_paths = value
+                    .parse()
+                    .map_err(|_| format!("Expected a number for --max-paths, found: {}", value))?;
+                process_options.max_paths = Some(max_paths);
+            }
+            "--max-depth" => {
+                let value = next_value(&mut args_iter, arg)?;
+                let max_depth = match value.parse() {
+                    Ok(max_depth) if max_depth > 1 => max_depth,
+                    _ => {
+                        return Err(format!(
+                            "Expected a number greater than 1 for --max-depth, found: {}",
+                            value
+                        ))
+                    }
+                };
+                path_style.max_depth = Some(max_depth);
+            }
+            "--with-durations" => path_style.durations = true,
+            "--sort-paths" => {
+                process_options.path_order = next_value(&mut args_iter, arg)?.parse()?
+            }
+            "--format" => format = next_value(&mut args_iter, arg)?.parse()?,
+            "--start" => start = Some(calendar::parse_start(next_value(&mut args_iter, arg)?)?),
+            "--time-unit" => time_unit = Some(next_value(&mut args_iter, arg)?.parse()?),
+            // the direction is required, so that it cannot be mistaken for the file to analyze
+            "--order" => {
+                let reverse = match next_value(&mut args_iter, arg)? {
+                    "forward" => false,
+                    "reverse" => true,
+                    value => {
+                        return Err(format!(
+                            "Expected forward or reverse for --order, found: {}",
+                            value
+                        ))
+                    }
+                };
+                view = View::Order { reverse };
+            }
+            "--near" => {
+                let value = next_value(&mut args_iter, arg)?;
+                let within = value
+                    .parse()
+                    .map_err(|_| format!("Expected a duration for --near, found: {}", value))?;
+                view = View::NearCritical { within };
+            }
+            "--top-paths-by-duration" => view = View::SinkPaths,
+            "--tree" => view = View::CriticalPathTree,
+            "--metrics" => {
+                view = View::Metrics {
+                    precision: DEFAULT_PRECISION,
+                }
+            }
+            "--precision" => {
+                let value = next_value(&mut args_iter, arg)?;
+                let decimals = value
+                    .parse()
+                    .map_err(|_| format!("Expected a number for --precision, found: {}", value))?;
+                if decimals > MAX_PRECISION {
+                    return Err(format!(
+                        "--precision cannot be larger than {}, found: {}",
+                        MAX_PRECISION, value
+                    ));
+                }
+                precision = Some(decimals);
+            }
+            "--explain" => view = View::Explanation,
+            "--oneline" => view = View::OneLine,
+            "--waves" => view = View::Waves,
+            "--components" => view = View::Components,
+            "--verify" => view = View::Verification,
+            "--assign" => {
+                let value = next_value(&mut args_iter, arg)?;
+                let workers = match value.parse() {
+                    Ok(workers) if workers > 0 => workers,
+                    _ => {
+                        return Err(format!(
+                            "Expected a positive number of workers for --assign, found: {}",
+                            value
+                        ))
+                    }
+                };
+                view = View::Assignment { workers };
+            }
+            "--workers" => {
+                let value = next_value(&mut args_iter, arg)?;
+                let workers = match value.parse() {
+                    Ok(workers) if workers > 0 => workers,
+                    _ => {
+                        return Err(format!(
+                            "Expected a positive number of workers for --workers, found: {}",
+                            value
+                        ))
+                    }
+                };
+                view = View::Efficiency { workers };
+            }
+            "--path" => {
+                let value = next_value(&mut args_iter, arg)?;
+                let number = match value.parse() {
+                    Ok(number) if number > 0 => number,
+                    _ => {
+                        return Err(format!(
+                            "Expected a positive number for --path, found: {}",
+                            value
+                        ))
+                    }
+                };
+                view = View::CriticalPath { number };
+            }
+            "--slack-histogram" => view = View::SlackHistogram,
+            "--value-progress" => view = View::ValueProgress,
+            "--diff" => diff_path = Some(next_value(&mut args_iter, arg)?.to_string()),
+            "--watch" => watch = true,
+            "--list-tasks" => list_tasks = true,
+            "--duration-histogram" => duration_histogram = true,
+            "--input-glob" => input_glob = Some(next_value(&mut args_iter, arg)?.to_string()),
+            "-o" | "--output" => output_path = Some(next_value(&mut args_iter, arg)?.to_string()),
+            "--github" => github = true,
+            option if option.starts_with("--") => {
+                return Err(format!("Unknown option: {}", option));
+            }
+            path => {
+                if file_path.replace(path.to_string()).is_some() {
+                    return Err(String::from("Only one file can be analyzed at a time"));
+                }
+            }
+        }
+    }
+    match &mut format {
+        Format::Ics(settings) => {
+            settings.start = start.ok_or("--format ics requires --start")?;
+            settings.unit = time_unit.unwrap_or_default();
+            settings.stamp = calendar::utc_now();
+            if view != View::Analysis || diff_path.is_some() || input_glob.is_some() {
+                return Err(String::from("--format ics only renders the analysis"));
+            }
+        }
+        Format::Adjacency
+            if view != View::Analysis || diff_path.is_some() || input_glob.is_some() =>
+        {
+            return Err(String::from(
+                "--format adjacency only renders the task graph",
+            ));
+        }
+        _ if start.is_some() || time_unit.is_some() => {
+            return Err(String::from("--start and --time-unit require --format ics"));
+        }
+        _ => (),
+    }
+    match (&mut view, precision) {
+        (View::Metrics { precision }, Some(decimals)) => *precision = decimals,
+        (_, Some(_)) => return Err(String::from("--precision requires --metrics")),
+        _ => (),
+    }
+    if watch && diff_path.is_some() {
+        return Err(String::from("--watch cannot be combined with --diff"));
+    }
+    if watch && output_path.is_some() {
+        return Err(String::from("--watch cannot be combined with --output"));
+    }
+    if list_tasks && (watch || diff_path.is_some() || input_glob.is_some()) {
+        return Err(String::from(
+            "--list-tasks cannot be combined with --watch, --diff or --input-glob",
+        ));
+    }
+    if duration_histogram && (list_tasks || watch || diff_path.is_some() || input_glob.is_some()) {
+        return Err(String::from(
+            "--duration-histogram cannot be combined with --list-tasks, --watch, --diff or \
+             --input-glob",
+        ));
+    }
+    if list_tasks && view != View::Analysis {
+        return Err(String::from("--list-tasks only lists the declared tasks"));
+    }
+    if duration_histogram && view != View::Analysis {
+        return Err(String::from(
+            "--duration-histogram only renders the durations of the declared tasks",
+        ));
+    }
+    if input_glob.is_some() && (watch || diff_path.is_some() || view != View::Analysis) {
+        return Err(String::from(
+            "--input-glob only renders an aggregate report",
+        ));
+    }
+    if diff_path.is_some() && view != View::Analysis {
+        return Err(String::from(
+            "--diff only renders the differences between the schedules",
+        ));
+    }
+    let (file_path, input_glob) = match (file_path, input_glob) {
+        (Some(file_path), None) => (file_path, false),
+        (None, Some(pattern)) => (pattern, true),
+        (Some(_), Some(_)) => {
+            return Err(String::from("--input-glob cannot be combined with a file"));
+        }
+        (None, None) => return Err(String::from("Missing file to analyze")),
+    };
+    // annotations are printed along with the output, which JSON consumers could not parse then
+    if github && output_path.is_none() && matches!(format, Format::Json | Format::JsonLines) {
+        return Err(String::from(
+            "--github cannot be combined with --format json or jsonl without --output",
+        ));
+    }
+    if watch && remote::is_url(&file_path) {
+        return Err(String::from("--watch cannot be used with a URL"));
+    }
+    Ok(Options {
+        file_path,
+        process_options,
+        format,
+        view,
+        path_style,
+        diff_path,
+        watch,
+        allowed_labels_path,
+        duration_overlay_paths,
+        input_glob,
+        list_tasks,
+        duration_histogram,
+        output_path,
+        github,
+    })
+}
+
+fn next_value<'a>(
+    args_iter: &mut Peekable<Iter<'a, String>>,
+    option: &str,
+) -> Result<&'a str, String> {
+    args_iter
+        .next()
+        .map(|value| value.as_str())
+        .ok_or_else(|| format!("Missing value for option: {}", option))
+}
+
+// "A=10" -> ("A", 10)
+fn parse_duration_override(value: &str) -> Result<(String, Duration), String> {
+    let invalid_override = || format!("Expected task=duration, found: {}", value);
+    let (task, duration) = value.split_once('=').ok_or_else(invalid_override)?;
+    if task.is_empty() {
+        return Err(invalid_override());
+    }
+    let duration = duration
+        .parse::<Duration>()
+        .map_err(|_| invalid_override())?;
+    Ok((task.to_string(), duration))
+}
+
+// "build.*=1.5" -> ("build.*", 1.5)
+fn parse_duration_scale(value: &str) -> Result<(String, f64), String> {
+    let invalid_scale = || format!("Expected pattern=factor, found: {}", value);
+    let (pattern, factor) = value.split_once('=').ok_or_else(invalid_scale)?;
+    if pattern.is_empty() {
+        return Err(invalid_scale());
+    }
+    match factor.parse::<f64>() {
+        Ok(factor) if factor.is_finite() && factor >= 0.0 => Ok((pattern.to_string(), factor)),
+        _ => Err(invalid_scale()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::PathOrder;
+    use crate::parser::RecordSeparator;
+
+    fn args(strs: &[&str]) -> Vec<String> {
+        strs.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn file_path_only() {
+        let options = parse_args(&args(&["example.tasks.in"])).unwrap();
+        assert_eq!(options.file_path, "example.tasks.in");
+        assert!(options.process_options.duration_overrides.is_empty());
+        assert!(!options.process_options.strict_labels);
+        assert_eq!(options.format, Format::Text);
+        assert_eq!(options.view, View::Analysis);
+        assert_eq!(options.diff_path, None);
+    }
+
+    #[test]
+    fn diff_path() {
+        let options =
+            parse_args(&args(&["example.tasks.in", "--diff", "example22.tasks.in"])).unwrap();
+        assert_eq!(options.file_path, "example.tasks.in");
+        assert_eq!(options.diff_path.as_deref(), Some("example22.tasks.in"));
+        assert!(parse_args(&args(&["example.tasks.in", "--diff"])).is_err());
+        for view in &["--metrics", "--oneline", "--tree"] {
+            let err = parse_args(&args(&[
+                "example.tasks.in",
+                "--diff",
+                "other.tasks.in",
+                view,
+            ]));
+            assert_eq!(
+                err.unwrap_err(),
+                "--diff only renders the differences between the schedules"
+            );
+        }
+    }
+
+    #[test]
+    fn calendar_format() {
+        let options = parse_args(&args(&[
+            "example.tasks.in",
+            "--format",
+            "ics",
+            "--start",
+            "2024-01-31T09:00",
+            "--time-unit",
+            "days",
+        ]))
+        .unwrap();
+        let settings = match options.format {
+            Format::Ics(settings) => settings,
+            format => panic!("Unexpected format: {:?}", format),
+        };
+        assert_eq!(
+            settings.start,
+            calendar::parse_start("2024-01-31T09:00").unwrap()
+        );
+        assert_eq!(settings.unit, calendar::TimeUnit::Days);
+
+        let err = parse_args(&args(&["example.tasks.in", "--format", "ics"])).unwrap_err();
+        assert_eq!(err, "--format ics requires --start");
+        let err = parse_args(&args(&[
+            "example.tasks.in",
+            "--format",
+            "ics",
+            "--start",
+            "2024-01-31T09:00",
+            "--metrics",
+        ]))
+        .unwrap_err();
+        assert_eq!(err, "--format ics only renders the analysis");
+        let err =
+            parse_args(&args(&["example.tasks.in", "--start", "2024-01-31T09:00"])).unwrap_err();
+        assert_eq!(err, "--start and --time-unit require --format ics");
+    }
+
+    #[test]
+    fn adjacency_format() {
+        let options = parse_args(&args(&["--format", "adjacency", "example.tasks.in"])).unwrap();
+        assert_eq!(options.format, Format::Adjacency);
+        let err = parse_args(&args(&[
+            "--format",
+            "adjacency",
+            "--waves",
+            "example.tasks.in",
+        ]));
+        assert_eq!(
+            err.unwrap_err(),
+            "--format adjacency only renders the task graph"
+        );
+    }
+
+    #[test]
+    fn watch() {
+        let options = parse_args(&args(&["--watch", "example.tasks.in"])).unwrap();
+        assert!(options.watch);
+        assert!(!parse_args(&args(&["example.tasks.in"])).unwrap().watch);
+        let err = parse_args(&args(&[
+            "example.tasks.in",
+            "--watch",
+            "--diff",
+            "example22.tasks.in",
+        ]))
+        .unwrap_err();
+        assert_eq!(err, "--watch cannot be combined with --diff");
+    }
+
+    #[test]
+    fn output_path() {
+        let options = parse_args(&args(&["-o", "analysis.json", "example.tasks.in"])).unwrap();
+        assert_eq!(options.output_path.as_deref(), Some("analysis.json"));
+        let options = parse_args(&args(&["example.tasks.in", "--output", "analysis.txt"])).unwrap();
+        assert_eq!(options.output_path.as_deref(), Some("analysis.txt"));
+        assert_eq!(
+            parse_args(&args(&["example.tasks.in"]))
+                .unwrap()
+                .output_path,
+            None
+        );
+        let err = parse_args(&args(&[
+            "-o",
+            "analysis.txt",
+            "--watch",
+            "example.tasks.in",
+        ]));
+        assert_eq!(err.unwrap_err(), "--watch cannot be combined with --output");
+    }
+
+    #[test]
+    fn github_annotations() {
+        assert!(!parse_args(&args(&["example.tasks.in"])).unwrap().github);
+        let options = parse_args(&args(&["--github", "example.tasks.in"])).unwrap();
+        assert!(options.github);
+        let err = parse_args(&args(&[
+            "--github",
+            "--format",
+            "jsonl",
+            "example.tasks.in",
+        ]));
+        assert_eq!(
+            err.unwrap_err(),
+            "--github cannot be combined with --format json or jsonl without --output"
+        );
+        assert!(parse_args(&args(&[
+            "--github",
+            "--format",
+            "json",
+            "-o",
+            "analysis.json",
+            "example.tasks.in"
+        ]))
+        .is_ok());
+    }
+
+    #[test]
+    fn url() {
+        let url = "https://example.com/schedule.tasks.in";
+        let options = parse_args(&args(&[url])).unwrap();
+        assert_eq!(options.file_path, url);
+        let err = parse_args(&args(&["--watch", url]));
+        assert_eq!(err.unwrap_err(), "--watch cannot be used with a URL");
+    }
+
+    #[test]
+    fn duration_histogram() {
+        let options = parse_args(&args(&["--duration-histogram", "example.tasks.in"])).unwrap();
+        assert!(options.duration_histogram);
+        assert!(
+            !parse_args(&args(&["example.tasks.in"]))
+                .unwrap()
+                .duration_histogram
+        );
+        let err = parse_args(&args(&[
+            "--duration-histogram",
+            "--list-tasks",
+            "example.tasks.in",
+        ]));
+        assert_eq!(
+            err.unwrap_err(),
+            "--duration-histogram cannot be combined with --list-tasks, --watch, --diff or \
+             --input-glob"
+        );
+        let err = parse_args(&args(&[
+            "--duration-histogram",
+            "--metrics",
+            "example.tasks.in",
+        ]));
+        assert_eq!(
+            err.unwrap_err(),
+            "--duration-histogram only renders the durations of the declared tasks"
+        );
+    }
+
+    #[test]
+    fn list_tasks() {
+        let options = parse_args(&args(&["--list-tasks", "example.tasks.in"])).unwrap();
+        assert!(options.list_tasks);
+        assert!(!parse_args(&args(&["example.tasks.in"])).unwrap().list_tasks);
+        let err = parse_args(&args(&["--list-tasks", "--watch", "example.tasks.in"]));
+        assert_eq!(
+            err.unwrap_err(),
+            "--list-tasks cannot be combined with --watch, --diff or --input-glob"
+        );
+        let err = parse_args(&args(&["--list-tasks", "--oneline", "example.tasks.in"]));
+        assert_eq!(
+            err.unwrap_err(),
+            "--list-tasks only lists the declared tasks"
+        );
+    }
+
+    #[test]
+    fn input_glob() {
+        let options = parse_args(&args(&["--input-glob", "schedules/*.tasks.in"])).unwrap();
+        assert!(options.input_glob);
+        assert_eq!(options.file_path, "schedules/*.tasks.in");
+        assert!(!parse_args(&args(&["example.tasks.in"])).unwrap().input_glob);
+
+        let err = parse_args(&args(&["--input-glob", "*.tasks.in", "example.tasks.in"]));
+        assert_eq!(
+            err.unwrap_err(),
+            "--input-glob cannot be combined with a file"
+        );
+        let err = parse_args(&args(&["--input-glob", "*.tasks.in", "--tree"]));
+        assert_eq!(
+            err.unwrap_err(),
+            "--input-glob only renders an aggregate report"
+        );
+    }
+
+    #[test]
+    fn require_positive_makespan() {
+        let options =
+            parse_args(&args(&["example.tasks.in", "--require-positive-makespan"])).unwrap();
+        assert!(options.process_options.require_positive_makespan);
+    }
+
+    #[test]
+    fn warn_zero_critical() {
+        let options = parse_args(&args(&["--warn-zero-critical", "example.tasks.in"])).unwrap();
+        assert!(options.process_options.warn_zero_critical);
+    }
+
+    #[test]
+    fn profile() {
+        let options = parse_args(&args(&["example.tasks.in", "--profile"])).unwrap();
+        assert!(options.process_options.profile);
+    }
+
+    #[test]
+    fn preserve_order() {
+        let options = parse_args(&args(&["--preserve-order", "example.tasks.in"])).unwrap();
+        assert!(options.process_options.preserve_order);
+    }
+
+    #[test]
+    fn max_duration() {
+        let options = parse_args(&args(&["--max-duration", "600", "example.tasks.in"])).unwrap();
+        assert_eq!(options.process_options.max_duration, Some(600));
+        assert!(parse_args(&args(&["--max-duration", "x", "example.tasks.in"])).is_err());
+        assert!(parse_args(&args(&["--max-duration", "70000", "example.tasks.in"])).is_err());
+    }
+
+    #[test]
+    fn deadline() {
+        let options = parse_args(&args(&["example.tasks.in", "--deadline", "30"])).unwrap();
+        assert_eq!(options.process_options.deadline, Some(30));
+        assert!(!options.process_options.strict_deadline);
+        let options = parse_args(&args(&["--strict-deadline", "30", "example.tasks.in"])).unwrap();
+        assert_eq!(options.process_options.deadline, Some(30));
+        assert!(options.process_options.strict_deadline);
+        let err = parse_args(&args(&["--deadline", "soon", "example.tasks.in"])).unwrap_err();
+        assert_eq!(err, "Expected a duration for --deadline, found: soon");
+    }
+
+    #[test]
+    fn max_paths() {
+        let options = parse_args(&args(&["example.tasks.in", "--max-paths", "1000"])).unwrap();
+        assert_eq!(options.process_options.max_paths, Some(1000));
+        let err = parse_args(&args(&["example.tasks.in", "--max-paths", "many"])).unwrap_err();
+        assert_eq!(err, "Expected a number for --max-paths, found: many");
+    }
+
+    #[test]
+    fn max_path_depth() {
+        let options = parse_args(&args(&["example.tasks.in", "--max-depth", "4"])).unwrap();
+        assert_eq!(options.path_style.max_depth, Some(4));
+        let err = parse_args(&args(&["example.tasks.in", "--max-depth", "1"])).unwrap_err();
+        assert_eq!(
+            err,
+            "Expected a number greater than 1 for --max-depth, found: 1"
+        );
+    }
+
+    #[test]
+    fn path_durations() {
+        let options = parse_args(&args(&["example.tasks.in"])).unwrap();
+        assert!(!options.path_style.durations);
+        let options = parse_args(&args(&["example.tasks.in", "--with-durations"])).unwrap();
+        assert!(options.path_style.durations);
+    }
+
+    #[test]
+    fn path_order() {
+        let options = parse_args(&args(&["example.tasks.in"])).unwrap();
+        assert_eq!(options.process_options.path_order, PathOrder::Length);
+        let options = parse_args(&args(&["example.tasks.in", "--sort-paths", "first"])).unwrap();
+        assert_eq!(options.process_options.path_order, PathOrder::FirstTask);
+        let err = parse_args(&args(&["example.tasks.in", "--sort-paths", "random"])).unwrap_err();
+        assert_eq!(err, "Unknown path order: random");
+    }
+
+    #[test]
+    fn allowed_labels_path() {
+        let options = parse_args(&args(&[
+            "example.tasks.in",
+            "--allowed-labels",
+            "labels.in",
+        ]))
+        .unwrap();
+        assert_eq!(options.allowed_labels_path.as_deref(), Some("labels.in"));
+        assert!(parse_args(&args(&["example.tasks.in", "--allowed-labels"])).is_err());
+    }
+
+    #[test]
+    fn duration_overlay_paths() {
+        let options = parse_args(&args(&[
+            "--durations-overlay",
+            "staging.csv",
+            "example.tasks.in",
+            "--durations-overlay",
+            "production.csv",
+        ]))
+        .unwrap();
+        assert_eq!(
+            options.duration_overlay_paths,
+            vec!["staging.csv", "production.csv"]
+        );
+        assert!(parse_args(&args(&["example.tasks.in", "--durations-overlay"])).is_err());
+    }
+
+    #[test]
+    fn require_tree() {
+        let options = parse_args(&args(&["example.tasks.in", "--require-tree"])).unwrap();
+        assert!(options.process_options.require_tree);
+    }
+
+    #[test]
+    fn topological_order_view() {
+        let options = parse_args(&args(&["--order", "forward", "example.tasks.in"])).unwrap();
+        assert_eq!(options.file_path, "example.tasks.in");
+        assert_eq!(options.view, View::Order { reverse: false });
+        let options = parse_args(&args(&["example.tasks.in", "--order", "reverse"])).unwrap();
+        assert_eq!(options.view, View::Order { reverse: true });
+        // a file named after a direction is not taken as the direction
+        let options = parse_args(&args(&["--order", "reverse", "reverse"])).unwrap();
+        assert_eq!(options.file_path, "reverse");
+        let err = parse_args(&args(&["--order", "example.tasks.in"])).unwrap_err();
+        assert_eq!(
+            err,
+            "Expected forward or reverse for --order, found: example.tasks.in"
+        );
+    }
+
+    #[test]
+    fn near_critical_paths_view() {
+        let options = parse_args(&args(&["--near", "2", "example.tasks.in"])).unwrap();
+        assert_eq!(options.view, View::NearCritical { within: 2 });
+        assert!(parse_args(&args(&["--near", "-1", "example.tasks.in"])).is_err());
+        assert!(parse_args(&args(&["example.tasks.in", "--near"])).is_err());
+    }
+
+    #[test]
+    fn sink_paths_view() {
+        let options = parse_args(&args(&["--top-paths-by-duration", "example.tasks.in"])).unwrap();
+        assert_eq!(options.view, View::SinkPaths);
+    }
+
+    #[test]
+    fn critical_path_tree_view() {
+        let options = parse_args(&args(&["example.tasks.in", "--tree"])).unwrap();
+        assert_eq!(options.view, View::CriticalPathTree);
+    }
+
+    #[test]
+    fn metrics_view() {
+        let options = parse_args(&args(&["example.tasks.in", "--metrics"])).unwrap();
+        assert_eq!(
+            options.view,
+            View::Metrics {
+                precision: DEFAULT_PRECISION
+            }
+        );
+        let options = parse_args(&args(&[
+            "example.tasks.in",
+            "--precision",
+            "4",
+            "--metrics",
+        ]))
+        .unwrap();
+        assert_eq!(options.view, View::Metrics { precision: 4 });
+
+        let err = parse_args(&args(&["example.tasks.in", "--precision", "4"])).unwrap_err();
+        assert_eq!(err, "--precision requires --metrics");
+        let err = parse_args(&args(&[
+            "example.tasks.in",
+            "--metrics",
+            "--precision",
+            "-1",
+        ]))
+        .unwrap_err();
+        assert_eq!(err, "Expected a number for --precision, found: -1");
+        let options = parse_args(&args(&[
+            "example.tasks.in",
+            "--metrics",
+            "--precision",
+            "17",
+        ]))
+        .unwrap();
+        assert_eq!(options.view, View::Metrics { precision: 17 });
+        let err = parse_args(&args(&[
+            "example.tasks.in",
+            "--metrics",
+            "--precision",
+            "400",
+        ]))
+        .unwrap_err();
+        assert_eq!(err, "--precision cannot be larger than 17, found: 400");
+    }
+
+    #[test]
+    fn explanation_view() {
+        let options = parse_args(&args(&["example.tasks.in", "--explain"])).unwrap();
+        assert_eq!(options.view, View::Explanation);
+    }
+
+    #[test]
+    fn assignment_view() {
+        let options = parse_args(&args(&["example.tasks.in", "--assign", "3"])).unwrap();
+        assert_eq!(options.view, View::Assignment { workers: 3 });
+        let err = parse_args(&args(&["example.tasks.in", "--assign", "0"])).unwrap_err();
+        assert_eq!(
+            err,
+            "Expected a positive number of workers for --assign, found: 0"
+        );
+    }
+
+    #[test]
+    fn efficiency_view() {
+        let options = parse_args(&args(&["example.tasks.in", "--workers", "2"])).unwrap();
+        assert_eq!(options.view, View::Efficiency { workers: 2 });
+        let err = parse_args(&args(&["example.tasks.in", "--workers", "none"])).unwrap_err();
+        assert_eq!(
+            err,
+            "Expected a positive number of workers for --workers, found: none"
+        );
+    }
+
+    #[test]
+    fn critical_path_view() {
+        let options = parse_args(&args(&["example.tasks.in", "--path", "3"])).unwrap();
+        assert_eq!(options.view, View::CriticalPath { number: 3 });
+        let err = parse_args(&args(&["example.tasks.in", "--path", "0"])).unwrap_err();
+        assert_eq!(err, "Expected a positive number for --path, found: 0");
+    }
+
+    #[test]
+    fn verification_view() {
+        let options = parse_args(&args(&["example.tasks.in", "--verify"])).unwrap();
+        assert_eq!(options.view, View::Verification);
+    }
+
+    #[test]
+    fn components_view() {
+        let options = parse_args(&args(&["example.tasks.in", "--components"])).unwrap();
+        assert_eq!(options.view, View::Components);
+    }
+
+    #[test]
+    fn oneline_view() {
+        let options = parse_args(&args(&["example.tasks.in", "--oneline"])).unwrap();
+        assert_eq!(options.view, View::OneLine);
+    }
+
+    #[test]
+    fn waves_view() {
+        let options = parse_args(&args(&["--waves", "example.tasks.in"])).unwrap();
+        assert_eq!(options.view, View::Waves);
+    }
+
+    #[test]
+    fn slack_histogram_view() {
+        let options = parse_args(&args(&["--slack-histogram", "example.tasks.in"])).unwrap();
+        assert_eq!(options.view, View::SlackHistogram);
+        let options = parse_args(&args(&["--value-progress", "example.tasks.in"])).unwrap();
+        assert_eq!(options.view, View::ValueProgress);
+    }
+
+    #[test]
+    fn output_format() {
+        let options = parse_args(&args(&["--format", "jsonl", "example.tasks.in"])).unwrap();
+        assert_eq!(options.format, Format::JsonLines);
+        assert!(parse_args(&args(&["--format", "xml", "example.tasks.in"])).is_err());
+    }
+
+    #[test]
+    fn strict_labels() {
+        let options = parse_args(&args(&["--strict-labels", "example.tasks.in"])).unwrap();
+        assert!(options.process_options.strict_labels);
+    }
+
+    #[test]
+    fn infer_leaf_duration() {
+        let options =
+            parse_args(&args(&["--infer-leaf-duration", "3", "example.tasks.in"])).unwrap();
+        assert_eq!(options.process_options.infer_leaf_duration, Some(3));
+        let options = parse_args(&args(&["example.tasks.in"])).unwrap();
+        assert_eq!(options.process_options.infer_leaf_duration, None);
+        let err = parse_args(&args(&["--infer-leaf-duration", "x", "example.tasks.in"]));
+        assert_eq!(
+            err.unwrap_err(),
+            "Expected a duration for --infer-leaf-duration, found: x"
+        );
+    }
+
+    #[test]
+    fn strict() {
+        let options = parse_args(&args(&["--strict", "example.tasks.in"])).unwrap();
+        assert!(options.process_options.strict);
+        let options = parse_args(&args(&["example.tasks.in"])).unwrap();
+        assert!(!options.process_options.strict);
+    }
+
+    #[test]
+    fn record_separator() {
+        let options = parse_args(&args(&["example.tasks.in"])).unwrap();
+        assert_eq!(
+            options.process_options.record_separator,
+            RecordSeparator::Newline
+        );
+        let options = parse_args(&args(&[
+            "--record-separator",
+            "semicolon",
+            "example.tasks.in",
+        ]))
+        .unwrap();
+        assert_eq!(
+            options.process_options.record_separator,
+            RecordSeparator::Semicolon
+        );
+        let err = parse_args(&args(&["--record-separator", "tab", "example.tasks.in"]));
+        assert_eq!(err.unwrap_err(), "Unknown record separator: tab");
+    }
+
+    #[test]
+    fn strict_numbers() {
+        let options = parse_args(&args(&["--strict-numbers", "example.tasks.in"])).unwrap();
+        assert!(options.process_options.strict_numbers);
+    }
+
+    #[test]
+    fn audit() {
+        let options = parse_args(&args(&["--audit", "example.tasks.in"])).unwrap();
+        assert!(options.process_options.audit);
+        let options = parse_args(&args(&["example.tasks.in"])).unwrap();
+        assert!(!options.process_options.audit);
+    }
+
+    #[test]
+    fn lenient() {
+        let options = parse_args(&args(&["--lenient", "example.tasks.in"])).unwrap();
+        assert!(options.process_options.lenient);
+        let options = parse_args(&args(&["example.tasks.in"])).unwrap();
+        assert!(!options.process_options.lenient);
+    }
+
+    #[test]
+    fn duration_scales() {
+        let options = parse_args(&args(&[
+            "--scale",
+            "build.*=1.5",
+            "--scale",
+            "test=2",
+            "example.tasks.in",
+        ]))
+        .unwrap();
+        assert_eq!(
+            options.process_options.duration_scales,
+            vec![(String::from("build.*"), 1.5), (String::from("test"), 2.0)]
+        );
+        for invalid_scale in &["build.*", "=1.5", "build.*=x", "build.*=-1", "build.*=inf"] {
+            let err = parse_args(&args(&["--scale", invalid_scale, "example.tasks.in"]));
+            assert_eq!(
+                err.unwrap_err(),
+                format!("Expected pattern=factor, found: {}", invalid_scale)
+            );
+        }
+    }
+
+    #[test]
+    fn repeated_duration_overrides() {
+        let options = parse_args(&args(&[
+            "--set",
+            "A=10",
+            "example.tasks.in",
+            "--set",
+            "B=0",
+        ]))
+        .unwrap();
+        assert_eq!(options.file_path, "example.tasks.in");
+        assert_eq!(
+            options.process_options.duration_overrides,
+            vec![(String::from("A"), 10), (String::from("B"), 0)]
+        );
+    }
+
+    #[test]
+    fn invalid_args() {
+        assert!(parse_args(&args(&[])).is_err());
+        assert!(parse_args(&args(&["a.tasks.in", "b.tasks.in"])).is_err());
+        assert!(parse_args(&args(&["--unknown", "a.tasks.in"])).is_err());
+        assert!(parse_args(&args(&["a.tasks.in", "--set"])).is_err());
+        assert!(parse_args(&args(&["--set", "A", "a.tasks.in"])).is_err());
+        assert!(parse_args(&args(&["--set", "=1", "a.tasks.in"])).is_err());
+        assert!(parse_args(&args(&["--set", "A=-1", "a.tasks.in"])).is_err());
+        assert!(parse_args(&args(&["--set", "A=70000", "a.tasks.in"])).is_err());
+    }
+}
@@ -0,0 +1,205 @@
+use crate::analyzer::ScheduleAnalysis;
+use crate::task::{Duration, TaskLabel, TotalDuration};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fmt::Formatter;
+
+/// Changes that turn the analysis of one schedule into the analysis of another one, such as a
+/// revised version of the same schedule
+#[derive(Debug, PartialEq, Eq)]
+pub struct ScheduleDiff<'a> {
+    pub added_tasks: Vec<TaskLabel<'a>>,
+    pub removed_tasks: Vec<TaskLabel<'a>>,
+    /// (task, duration before, duration after) for the tasks found in both schedules
+    pub changed_durations: Vec<(TaskLabel<'a>, Duration, Duration)>,
+    /// (before, after)
+    pub minimum_completion_time: (TotalDuration, TotalDuration),
+    /// (before, after)
+    pub max_parallelism: (usize, usize),
+    pub added_critical_paths: Vec<Vec<TaskLabel<'a>>>,
+    pub removed_critical_paths: Vec<Vec<TaskLabel<'a>>>,
+}
+
+impl<'a> ScheduleDiff<'a> {
+    /// Tasks and critical paths are compared by their labels. Tasks are reported in label order,
+    /// while critical paths keep the order they have in their own analysis.
+    /// Time: O(V * logV + P * M), where P is the number of critical paths and M is the number of
+    ///       tasks on a critical path
+    pub fn new(before: &ScheduleAnalysis<'a>, after: &ScheduleAnalysis<'a>) -> Self {
+        let tasks_before = before.topological_order().iter().collect::<BTreeSet<_>>();
+        let tasks_after = after.topological_order().iter().collect::<BTreeSet<_>>();
+        let changed_durations = tasks_before
+            .intersection(&tasks_after)
+            .filter_map(|&&task| {
+                let duration_before = before.task_duration(task)?;
+                let duration_after = after.task_duration(task)?;
+                if duration_before != duration_after {
+                    Some((task, duration_before, duration_after))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        ScheduleDiff {
+            added_tasks: tasks_after
+                .difference(&tasks_before)
+                .map(|&&task| task)
+                .collect(),
+            removed_tasks: tasks_before
+                .difference(&tasks_after)
+                .map(|&&task| task)
+                .collect(),
+            changed_durations,
+            minimum_completion_time: (
+                before.minimum_completion_time(),
+                after.minimum_completion_time(),
+            ),
+            max_parallelism: (before.max_parallelism(), after.max_parallelism()),
+            added_critical_paths: missing_paths(after.critical_paths(), before.critical_paths()),
+            removed_critical_paths: missing_paths(before.critical_paths(), after.critical_paths()),
+        }
+    }
+
+    /// Change in the minimum completion time, negative if the schedule got shorter
+    pub fn minimum_completion_time_delta(&self) -> i64 {
+        let (before, after) = self.minimum_completion_time;
+        i64::from(after) - i64::from(before)
+    }
+
+    /// Change in the maximum parallelism, negative if fewer tasks can run at the same time
+    pub fn max_parallelism_delta(&self) -> i64 {
+        let (before, after) = self.max_parallelism;
+        after as i64 - before as i64
+    }
+}
+
+// Paths found in `paths` but not in `other_paths`
+fn missing_paths<'a>(
+    paths: &[Vec<TaskLabel<'a>>],
+    other_paths: &[Vec<TaskLabel<'a>>],
+) -> Vec<Vec<TaskLabel<'a>>> {
+    paths
+        .iter()
+        .filter(|path| !other_paths.contains(path))
+        .cloned()
+        .collect()
+}
+
+impl<'a> fmt::Display for ScheduleDiff<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "minimum_completion_time: {} -> {} ({:+})",
+            self.minimum_completion_time.0,
+            self.minimum_completion_time.1,
+            self.minimum_completion_time_delta()
+        )?;
+        writeln!(
+            f,
+            "max_parallelism: {} -> {} ({:+})",
+            self.max_parallelism.0,
+            self.max_parallelism.1,
+            self.max_parallelism_delta()
+        )?;
+        writeln!(f, "added_tasks:")?;
+        for task in &self.added_tasks {
+            writeln!(f, "{}", task.as_ref())?;
+        }
+        writeln!(f, "removed_tasks:")?;
+        for task in &self.removed_tasks {
+            writeln!(f, "{}", task.as_ref())?;
+        }
+        writeln!(f, "changed_durations:")?;
+        for (task, duration_before, duration_after) in &self.changed_durations {
+            writeln!(
+                f,
+                "{}: {} -> {}",
+                task.as_ref(),
+                duration_before,
+                duration_after
+            )?;
+        }
+        writeln!(f, "added_critical_paths:")?;
+        for path in &self.added_critical_paths {
+            writeln!(f, "{}", join_path(path))?;
+        }
+        write!(f, "removed_critical_paths:")?;
+        for path in &self.removed_critical_paths {
+            write!(f, "\n{}", join_path(path))?;
+        }
+        Ok(())
+    }
+}
+
+fn join_path(path: &[TaskLabel]) -> String {
+    path.iter()
+        .map(|task| task.as_ref())
+        .collect::<Vec<_>>()
+        .join("->")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::tests::paths;
+    use crate::processor::process;
+    use std::fs;
+
+    fn read_example(file_name: &str) -> String {
+        fs::read_to_string(format!(
+            "{}/resources/test/{}",
+            env!("CARGO_MANIFEST_DIR"),
+            file_name
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn single_duration_change() {
+        let content_before = read_example("example.tasks.in");
+        let content_after = read_example("example22.tasks.in");
+        let before = process(&content_before).unwrap();
+        let after = process(&content_after).unwrap();
+        let diff = ScheduleDiff::new(&before, &after);
+        assert_eq!(diff.minimum_completion_time, (4, 5));
+        assert_eq!(diff.minimum_completion_time_delta(), 1);
+        assert_eq!(diff.max_parallelism_delta(), 0);
+        assert!(diff.added_tasks.is_empty());
+        assert!(diff.removed_tasks.is_empty());
+        assert_eq!(diff.changed_durations, vec![(TaskLabel::new("H"), 1, 2)]);
+        assert!(diff.added_critical_paths.is_empty());
+        assert_eq!(
+            diff.removed_critical_paths,
+            paths(&["Q->J->N->I", "Q->J->P->I", "Q->T->N->I"])
+        );
+
+        let diff = ScheduleDiff::new(&after, &before);
+        assert_eq!(diff.minimum_completion_time_delta(), -1);
+        assert_eq!(diff.changed_durations, vec![(TaskLabel::new("H"), 2, 1)]);
+        assert_eq!(diff.added_critical_paths.len(), 3);
+    }
+
+    #[test]
+    fn added_and_removed_tasks() {
+        let before = process("A(1)\nB(2) after [A]").unwrap();
+        let after = process("A(1)\nC(1) after [A]").unwrap();
+        let diff = ScheduleDiff::new(&before, &after);
+        assert_eq!(diff.added_tasks, vec![TaskLabel::new("C")]);
+        assert_eq!(diff.removed_tasks, vec![TaskLabel::new("B")]);
+        assert!(diff.changed_durations.is_empty());
+        assert_eq!(
+            diff.to_string(),
+            "minimum_completion_time: 3 -> 2 (-1)\n\
+             max_parallelism: 1 -> 1 (+0)\n\
+             added_tasks:\n\
+             C\n\
+             removed_tasks:\n\
+             B\n\
+             changed_durations:\n\
+             added_critical_paths:\n\
+             A->C\n\
+             removed_critical_paths:\n\
+             A->B"
+        );
+    }
+}
@@ -0,0 +1,459 @@
+use crate::parser::ParsedData;
+use crate::task::TaskLabel;
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::TryFrom;
+use std::fmt;
+
+// labels shorter than this are not compared for near-duplicates
+const MIN_NEAR_DUPLICATE_LEN: usize = 3;
+
+/// Looks for labels that are likely to be typos, which are
+/// - labels that appear only once in the input without being related to any other task, e.g.,
+///   a task referenced in a dependency list that is never declared or a task that is declared
+///   but neither depends on nor is depended upon by other tasks
+/// - labels that differ from another label by a single character. Short labels such as "A" and
+///   "B" are ignored since they naturally differ by a single character.
+///
+/// Time: O(L^2 * M), where L is the number of labels and M is the length of the longest label
+pub fn lint_labels(data: &ParsedData) -> Vec<String> {
+    #[derive(Default)]
+    struct LabelUsage {
+        occurrences: usize,
+        has_prerequisites: bool,
+    }
+
+    let mut usages: BTreeMap<TaskLabel, LabelUsage> = BTreeMap::new();
+    for &(task, _) in data.task_durations() {
+        usages.entry(task).or_default().occurrences += 1;
+    }
+    for &(first, second) in data.task_orders() {
+        if let Some(second) = second {
+            usages.entry(first).or_default().occurrences += 1;
+            usages.entry(second).or_default().has_prerequisites = true;
+        }
+    }
+
+    let mut warnings = usages
+        .iter()
+        .filter(|(_, usage)| usage.occurrences == 1 && !usage.has_prerequisites)
+        .map(|(task, _)| {
+            format!(
+                "Label appears only once and is not related to other tasks: {}",
+                task.as_ref()
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let labels = usages
+        .keys()
+        .filter(|task| task.chars().count() >= MIN_NEAR_DUPLICATE_LEN)
+        .collect::<BTreeSet<_>>();
+    for (idx, label) in labels.iter().enumerate() {
+        for other_label in labels.iter().skip(idx + 1) {
+            if differ_by_single_char(label, other_label) {
+                warnings.push(format!(
+                    "Labels differ by a single character: {}, {}",
+                    label.as_ref(),
+                    other_label.as_ref()
+                ));
+            }
+        }
+    }
+    warnings
+}
+
+/// A label that keeps the schedule from being analyzed, along with where it starts. Empty labels
+/// start where the label is expected, e.g., at the comma following it in a dependency list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabelIssue {
+    line: usize,
+    column: usize,
+    label: String,
+    reason: String,
+}
+
+// for linters
+#[allow(dead_code)]
+impl LabelIssue {
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+impl fmt::Display for LabelIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.reason
+        )
+    }
+}
+
+/// Looks for all the labels that are empty, have whitespace characters, have too many characters,
+/// or that a task depends on itself through, rather than stopping at the first one as parsing
+/// does. The content is only scanned for labels, so that the rest of it does not need to be
+/// well-formed, e.g., both the whitespace of `Build Test` and the empty label between the commas
+/// are reported for `Build Test(1) after [A, , B]`. Issues are ordered by where they are found.
+/// Time: O(N), where N is the number of characters of the content
+#[allow(dead_code)]
+pub fn validate_labels(content: &str) -> Vec<LabelIssue> {
+    let mut scanner = LabelScanner::default();
+    let (mut line, mut column) = (1, 1);
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        let position = (line, column);
+        if c == '\n' {
+            line += 1;
+            column = 1;
+        } else {
+            column += 1;
+        }
+        if scanner.quoted {
+            scanner.quoted = c != '"';
+            continue;
+        }
+        if scanner.in_parens {
+            scanner.in_parens = c != ')';
+            continue;
+        }
+        let escaped = matches!(scanner.run.last(), Some(&('\\', _)));
+        let delimiter = match c {
+            '-' if chars.peek() == Some(&'>') && !escaped => {
+                chars.next();
+                column += 1;
+                Delimiter::Arrow
+            }
+            '(' => Delimiter::Paren,
+            '"' => Delimiter::Quote,
+            '[' => Delimiter::ListStart,
+            ',' => Delimiter::ListSeparator,
+            ']' => Delimiter::ListEnd,
+            ':' => Delimiter::Weight,
+            ';' => Delimiter::RecordEnd,
+            // an arrow still expecting its target reaches over to the next line
+            '\n' if !scanner.in_list && !scanner.expects_arrow_target() => Delimiter::RecordEnd,
+            _ => {
+                scanner.run.push((c, position));
+                continue;
+            }
+        };
+        scanner.end_run(delimiter, position);
+    }
+    scanner.end_run(Delimiter::RecordEnd, (line, column));
+    scanner.issues
+}
+
+// A character along with its line and column
+type PositionedChar = (char, (usize, usize));
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Delimiter {
+    Paren,
+    Quote,
+    ListStart,
+    ListSeparator,
+    ListEnd,
+    Arrow,
+    Weight,
+    RecordEnd,
+}
+
+// Splits the content into runs of characters between delimiters, which are either labels,
+// keywords, or both, e.g., "milestone M after"
+#[derive(Default)]
+struct LabelScanner {
+    run: Vec<PositionedChar>,
+    quoted: bool,
+    in_parens: bool,
+    in_list: bool,
+    // an empty list right after an arrow, e.g., "A -> []", declares the task on its own
+    in_arrow_list: bool,
+    // the run following a colon is the weight of a relation
+    in_weight: bool,
+    // the task declared by the record, or the task on the left side of its arrow
+    record_task: Option<String>,
+    // the task on the left side of the arrow while the right side is expected
+    arrow_task: Option<String>,
+    issues: Vec<LabelIssue>,
+}
+
+impl LabelScanner {
+    fn end_run(&mut self, delimiter: Delimiter, position: (usize, usize)) {
+        let run = std::mem::take(&mut self.run);
+        let (label, ends_with_after) = strip_keywords(&run);
+        if self.in_weight {
+            self.in_weight = false;
+        } else {
+            let required = match delimiter {
+                Delimiter::Paren => true,
+                Delimiter::ListSeparator => self.in_list,
+                Delimiter::ListEnd => self.in_list && !self.in_arrow_list,
+                Delimiter::Arrow => self.record_task.is_none(),
+                _ => false,
+            };
+            if !label.is_empty() || required {
+                let label_position = label.first().map_or(position, |&(_, position)| position);
+                self.check_label(label.iter().map(|&(c, _)| c).collect(), label_position);
+            }
+        }
+        match delimiter {
+            Delimiter::Quote => self.quoted = true,
+            Delimiter::Paren => self.in_parens = true,
+            Delimiter::ListStart => {
+                self.in_list = true;
+                self.in_arrow_list = self.arrow_task.is_some();
+            }
+            Delimiter::ListEnd => {
+                self.in_list = false;
+                if self.in_arrow_list {
+                    self.in_arrow_list = false;
+                    self.arrow_task = None;
+                }
+            }
+            Delimiter::Weight => self.in_weight = true,
+            Delimiter::Arrow => self.arrow_task = self.record_task.clone(),
+            // a record declaring a task "after" a list can go on with the list on the next line
+            Delimiter::RecordEnd if !ends_with_after => {
+                self.in_list = false;
+                self.in_arrow_list = false;
+                self.record_task = None;
+                self.arrow_task = None;
+            }
+            _ => (),
+        }
+    }
+
+    fn expects_arrow_target(&self) -> bool {
+        self.arrow_task.is_some() && self.run.iter().all(|(c, _)| c.is_whitespace())
+    }
+
+    fn check_label(&mut self, label: String, (line, column): (usize, usize)) {
+        let reason = match TaskLabel::try_from(label.as_str()) {
+            Err(reason) => Some(reason),
+            Ok(_) => {
+                let depended_upon_task = if self.in_list {
+                    self.record_task.as_ref()
+                } else {
+                    self.arrow_task.as_ref()
+                };
+                if depended_upon_task == Some(&label) {
+                    Some(format!(
+                        "Labels cannot have a dependency on themselves: {}",
+                        label
+                    ))
+                } else {
+                    None
+                }
+            }
+        };
+        if let Some(reason) = reason {
+            self.issues.push(LabelIssue {
+                line,
+                column,
+                label: label.clone(),
+                reason,
+            });
+        }
+        if self.arrow_task.is_some() {
+            self.arrow_task = None; // right side of the arrow
+        } else if !self.in_list {
+            self.record_task = Some(label);
+        }
+    }
+}
+
+// Trims the run and drops the keywords around the label it holds, if any. Tells whether the run
+// ends with the "after" keyword.
+fn strip_keywords(run: &[PositionedChar]) -> (&[PositionedChar], bool) {
+    fn trim(run: &[PositionedChar]) -> &[PositionedChar] {
+        let start = run
+            .iter()
+            .position(|(c, _)| !c.is_whitespace())
+            .unwrap_or(run.len());
+        let end = run
+            .iter()
+            .rposition(|(c, _)| !c.is_whitespace())
+            .map_or(start, |idx| idx + 1);
+        &run[start..end]
+    }
+    fn is_keyword(chars: &[PositionedChar], keyword: &str) -> bool {
+        chars.iter().map(|&(c, _)| c).eq(keyword.chars())
+    }
+
+    let mut label = trim(run);
+    let keyword_len = "milestone".len();
+    if label.len() > keyword_len
+        && is_keyword(&label[..keyword_len], "milestone")
+        && label[keyword_len].0.is_whitespace()
+    {
+        label = trim(&label[keyword_len..]);
+    }
+    if is_keyword(label, "include") {
+        return (&[], false);
+    }
+    let keyword_len = "after".len();
+    let ends_with_after = label.len() >= keyword_len
+        && is_keyword(&label[label.len() - keyword_len..], "after")
+        && (label.len() == keyword_len || label[label.len() - keyword_len - 1].0.is_whitespace());
+    if ends_with_after {
+        label = trim(&label[..label.len() - keyword_len]);
+    }
+    (label, ends_with_after)
+}
+
+// Whether a single insertion, deletion or substitution turns one label into the other
+fn differ_by_single_char(label: &str, other_label: &str) -> bool {
+    let chars = label.chars().collect::<Vec<_>>();
+    let other_chars = other_label.chars().collect::<Vec<_>>();
+    let (shorter, longer) = if chars.len() <= other_chars.len() {
+        (chars, other_chars)
+    } else {
+        (other_chars, chars)
+    };
+    if longer.len() - shorter.len() > 1 {
+        return false;
+    }
+    let common_prefix_len = shorter
+        .iter()
+        .zip(longer.iter())
+        .take_while(|(c1, c2)| c1 == c2)
+        .count();
+    if shorter.len() == longer.len() {
+        // substitution
+        common_prefix_len < shorter.len()
+            && shorter[common_prefix_len + 1..] == longer[common_prefix_len + 1..]
+    } else {
+        // insertion
+        shorter[common_prefix_len..] == longer[common_prefix_len + 1..]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ScheduleParser;
+    use std::fs;
+
+    #[test]
+    fn single_char_differences() {
+        assert!(differ_by_single_char("build", "buld"));
+        assert!(differ_by_single_char("buld", "build"));
+        assert!(differ_by_single_char("build", "builds"));
+        assert!(differ_by_single_char("build", "guild"));
+        assert!(differ_by_single_char("方言", "方"));
+        assert!(!differ_by_single_char("build", "build"));
+        assert!(!differ_by_single_char("build", "biuld"));
+        assert!(!differ_by_single_char("build", "bui"));
+        assert!(!differ_by_single_char("A", "BCD"));
+    }
+
+    #[test]
+    fn typo_in_dependency_list() {
+        let unparsed_content = fs::read_to_string(format!(
+            "{}/resources/test/example17.tasks.in",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap();
+        let data = ScheduleParser::parse_content(&unparsed_content).unwrap();
+        assert_eq!(
+            lint_labels(&data),
+            vec![
+                "Label appears only once and is not related to other tasks: compil",
+                "Labels differ by a single character: compil, compile",
+            ]
+        );
+    }
+
+    #[test]
+    fn label_issues() {
+        let unparsed_content = fs::read_to_string(format!(
+            "{}/resources/test/example37.tasks.in",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap();
+        let long_label = "ThisLabelIsFarTooLongToBeAcceptedByTheParserSinceItExceedsSeventyCharsX";
+        assert_eq!(
+            validate_labels(&unparsed_content)
+                .iter()
+                .map(|issue| issue.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                String::from(
+                    "line 2, column 1: Labels cannot have whitespace characters: Build Test"
+                ),
+                String::from("line 3, column 26: Empty strings cannot be labels"),
+                String::from(
+                    "line 5, column 4: Labels cannot have a dependency on themselves: Deploy"
+                ),
+                String::from(
+                    "line 6, column 11: Labels cannot have a dependency on themselves: Notify"
+                ),
+                format!(
+                    "line 7, column 1: Labels cannot have more than 70 characters: {}",
+                    long_label
+                ),
+                String::from("line 8, column 1: Empty strings cannot be labels"),
+                String::from(
+                    "line 9, column 36: Labels cannot have a dependency on themselves: Release"
+                ),
+            ]
+        );
+        let issues = validate_labels(&unparsed_content);
+        assert_eq!(issues[0].label(), "Build Test");
+        assert_eq!((issues[1].line(), issues[1].column()), (3, 26));
+        assert_eq!(issues[1].label(), "");
+        assert_eq!(
+            issues[4].reason(),
+            format!("Labels cannot have more than 70 characters: {}", long_label)
+        );
+        assert_eq!(
+            validate_labels("Ship ->\n  Ship\nStore -> []\n")
+                .iter()
+                .map(|issue| issue.to_string())
+                .collect::<Vec<_>>(),
+            vec![String::from(
+                "line 2, column 3: Labels cannot have a dependency on themselves: Ship"
+            )]
+        );
+    }
+
+    #[test]
+    fn no_label_issues_for_valid_schedules() {
+        for file_name in &["example.tasks.in", "example34.tasks.in"] {
+            let unparsed_content = fs::read_to_string(format!(
+                "{}/resources/test/{}",
+                env!("CARGO_MANIFEST_DIR"),
+                file_name
+            ))
+            .unwrap();
+            assert_eq!(validate_labels(&unparsed_content), vec![], "{}", file_name);
+        }
+        let content =
+            "extract\\->load(2) after [Y]\nY(1) -> []\nP -> H : 3\nH ->\n X(2)\nmilestone(1)\n";
+        assert_eq!(validate_labels(content), vec![]);
+    }
+
+    #[test]
+    fn no_warnings_for_consistent_labels() {
+        let unparsed_content = fs::read_to_string(format!(
+            "{}/resources/test/example.tasks.in",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap();
+        let data = ScheduleParser::parse_content(&unparsed_content).unwrap();
+        assert!(lint_labels(&data).is_empty());
+    }
+}
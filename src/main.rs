@@ -9,82 +9,820 @@ extern crate pest_derive;
 #[cfg(test)]
 #[macro_use]
 extern crate lazy_static;
+mod aggregate;
 mod analyzer;
+mod audit;
+mod calendar;
+mod cli;
+mod diff;
+mod lint;
+mod owned;
 mod parser;
 mod processor;
+mod remote;
+mod render;
+mod schedule;
 mod task;
 
+use aggregate::{AggregateReport, FileSummary};
+use analyzer::ScheduleAnalysis;
+use diff::ScheduleDiff;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE};
+use flate2::read::GzDecoder;
 use log::{error, trace};
-use std::error::Error as StdError;
+use notify::{EventKind, RecursiveMode, Watcher};
+use parser::{IncludedContents, Source};
+use processor::{ProcessError, ProcessOptions};
+use render::{Format, View, ViewFormat};
 use std::ffi::OsStr;
-use std::io::{Error as IoError, ErrorKind};
+use std::io::{Error as IoError, ErrorKind, Read};
 use std::path::Path;
+use std::sync::mpsc;
 use std::{env, fs, process};
 
 fn main() {
     env_logger::init();
     let args = env::args().collect::<Vec<_>>();
-    validate_arg_count(args.len());
-    let file_path = &args[1];
+    let mut options = parse_options(&args);
+    let program_name = get_executable_name(&args[0]).unwrap_or(&args[0]);
+    if let Some(allowed_labels_path) = &options.allowed_labels_path {
+        trace!("reading allowed labels...");
+        let allowed_labels = match fs::read_to_string(allowed_labels_path) {
+            Ok(content) => processor::parse_allowed_labels(&content),
+            Err(err) => handle_io_error(err, program_name, allowed_labels_path, options.format),
+        };
+        options.process_options.allowed_labels = Some(allowed_labels);
+    }
+    for overlay_path in &options.duration_overlay_paths {
+        trace!("reading duration overlay...");
+        let overlay = match fs::read_to_string(overlay_path) {
+            Ok(content) => processor::parse_duration_overlay(&content).unwrap_or_else(|reason| {
+                handle_processing_error(ProcessError::Invalid(reason), overlay_path, options.format)
+            }),
+            Err(err) => handle_io_error(err, program_name, overlay_path, options.format),
+        };
+        options.process_options.duration_overlays.push(overlay);
+    }
+    if options.watch {
+        watch_and_analyze(&options, program_name);
+    }
+    if options.input_glob {
+        analyze_files_and_report(&options, program_name);
+    }
+    let included_contents = IncludedContents::new();
+    let unparsed_file_content = read_file(&options.file_path, program_name, options.format);
+    if options.list_tasks || options.duration_histogram {
+        list_tasks(
+            &unparsed_file_content,
+            &included_contents,
+            &options,
+            program_name,
+        );
+    }
+    let analysis = analyze_content(
+        &unparsed_file_content,
+        &options.file_path,
+        &included_contents,
+        &options,
+    );
+    match &options.diff_path {
+        Some(diff_path) => {
+            let other_unparsed_file_content = read_file(diff_path, program_name, options.format);
+            let other_analysis = analyze_content(
+                &other_unparsed_file_content,
+                diff_path,
+                &included_contents,
+                &options,
+            );
+            trace!("rendering diff...");
+            let diff = ScheduleDiff::new(&analysis, &other_analysis);
+            print_output(
+                &render::render_diff(&diff, options.format.into()),
+                &options,
+                program_name,
+            );
+        }
+        None => {
+            trace!("rendering analysis...");
+            match render::render_view(&analysis, options.view, options.format, options.path_style) {
+                Ok(rendered) => {
+                    print_output(&rendered, &options, program_name);
+                    if options.github {
+                        println!(
+                            "{}",
+                            github_command("notice", &options.file_path, None, &analysis.oneline())
+                        );
+                    }
+                }
+                Err(reason) => handle_processing_error(
+                    ProcessError::Invalid(reason),
+                    &options.file_path,
+                    options.format,
+                ),
+            }
+        }
+    }
+}
+
+fn print_output(output: &str, options: &cli::Options, program_name: &str) {
+    if let Err(err) = write_output(output, options.output_path.as_deref()) {
+        let output_path = options.output_path.as_deref().unwrap_or_default();
+        handle_io_error(err, program_name, output_path, options.format);
+    }
+}
+
+/// Writes the output to the file, creating it or replacing its content, or to stdout without a
+/// file
+fn write_output(output: &str, output_path: Option<&str>) -> Result<(), IoError> {
+    match output_path {
+        Some(output_path) => fs::write(output_path, format!("{}\n", output)),
+        None => {
+            println!("{}", output);
+            Ok(())
+        }
+    }
+}
+
+// The first two bytes of every gzip stream
+const GZIP_MAGIC_HEADER: [u8; 2] = [0x1f, 0x8b];
+
+fn read_file(file_path: &str, program_name: &str, format: Format) -> String {
     trace!("reading file from path...");
-    match fs::read_to_string(file_path) {
-        Ok(unparsed_file_content) => match processor::process(&unparsed_file_content) {
-            Ok(analysis) => {
-                trace!("rendering analysis...");
-                println!("{}", analysis);
+    match read_schedule_file(file_path) {
+        Ok(unparsed_file_content) => unparsed_file_content,
+        Err(err) => {
+            trace!("ending with an I/O error...");
+            handle_io_error(err, program_name, file_path, format);
+        }
+    }
+}
+
+/// Reads the file as text, decompressing it first if it's gzipped. HTTP(S) URLs are fetched rather
+/// than read from the filesystem, which requires the http feature. Gzipped files are recognized
+/// either by their `.gz` extension or by their header. The text is expected to be UTF-8, unless
+/// it starts with a UTF-16 byte order mark.
+fn read_schedule_file(file_path: &str) -> Result<String, IoError> {
+    let mut bytes = if remote::is_url(file_path) {
+        trace!("fetching file from URL...");
+        remote::fetch(file_path)?
+    } else {
+        fs::read(file_path)?
+    };
+    let is_gzipped = Path::new(file_path).extension() == Some(OsStr::new("gz"))
+        || bytes.starts_with(&GZIP_MAGIC_HEADER);
+    if is_gzipped {
+        trace!("decompressing gzipped file...");
+        let mut decompressed_bytes = Vec::new();
+        GzDecoder::new(bytes.as_slice()).read_to_end(&mut decompressed_bytes)?;
+        bytes = decompressed_bytes;
+    }
+    decode_text(bytes)
+}
+
+fn decode_text(bytes: Vec<u8>) -> Result<String, IoError> {
+    match Encoding::for_bom(&bytes) {
+        Some((encoding, bom_len)) if encoding == UTF_16LE || encoding == UTF_16BE => {
+            trace!("transcoding {} file...", encoding.name());
+            encoding
+                .decode_without_bom_handling_and_without_replacement(&bytes[bom_len..])
+                .map(|content| content.into_owned())
+                .ok_or_else(|| {
+                    IoError::new(
+                        ErrorKind::InvalidData,
+                        format!("stream did not contain valid {}", encoding.name()),
+                    )
+                })
+        }
+        _ => String::from_utf8(bytes).map_err(|err| IoError::new(ErrorKind::InvalidData, err)),
+    }
+}
+
+// URLs are read by `read_schedule_file` too, yet their content has no directory to include
+// files from
+fn source_of(file_path: &str) -> Source<'_> {
+    if remote::is_url(file_path) {
+        Source::Url
+    } else {
+        Source::File(Path::new(file_path))
+    }
+}
+
+fn analyze_content<'a>(
+    unparsed_file_content: &'a str,
+    file_path: &str,
+    included_contents: &'a IncludedContents,
+    options: &cli::Options,
+) -> ScheduleAnalysis<'a> {
+    match processor::process_with_includes(
+        unparsed_file_content,
+        source_of(file_path),
+        included_contents,
+        &options.process_options,
+    ) {
+        Ok(analysis) => analysis,
+        Err(err) => {
+            trace!("ending with a processing error...");
+            if options.github {
+                print_github_annotations(&err, file_path);
             }
-            Err(err) => {
-                trace!("ending with a processing error...");
-                handle_processing_error(err);
+            if options.view == View::Verification {
+                // the processing error is reported regardless, so failing to write is not
+                // reported on top of it
+                let _ = write_output(
+                    &render::render_failed_verification(&err, options.format.into()),
+                    options.output_path.as_deref(),
+                );
             }
-        },
+            handle_processing_error(err, file_path, options.format);
+        }
+    }
+}
+
+// Exits with an error if any of the files can't be analyzed, once all of them are reported
+fn analyze_files_and_report(options: &cli::Options, program_name: &str) -> ! {
+    match analyze_files(&options.file_path, &options.process_options) {
+        Ok(report) => {
+            print_output(
+                &render::render_aggregate(&report, options.format.into()),
+                options,
+                program_name,
+            );
+            process::exit(if report.failed_count() == 0 { 0 } else { 1 });
+        }
         Err(err) => {
-            trace!("ending with an I/O error...");
-            let program_name = get_executable_name(&args[0]).unwrap_or(&args[0]);
-            handle_io_error(err, program_name, file_path);
+            eprintln!(
+                "{}: {}: Invalid pattern: {}",
+                program_name, options.file_path, err
+            );
+            process::exit(1);
         }
     }
 }
 
-fn get_executable_name(exec_path: &str) -> Option<&str> {
-    Path::new(exec_path).file_name().and_then(OsStr::to_str)
+/// Analyzes each file matching the pattern on its own, in the order of their paths. Files that
+/// can't be read or analyzed are reported in their row rather than ending the analysis.
+fn analyze_files(
+    pattern: &str,
+    options: &ProcessOptions,
+) -> Result<AggregateReport, glob::PatternError> {
+    let files = glob::glob(pattern)?
+        .map(|entry| match entry {
+            Ok(file_path) => summarize_file(&file_path.display().to_string(), options),
+            Err(err) => FileSummary::new(
+                &err.path().display().to_string(),
+                Err(err.error().to_string()),
+            ),
+        })
+        .collect();
+    Ok(AggregateReport { files })
+}
+
+fn summarize_file(file_path: &str, options: &ProcessOptions) -> FileSummary {
+    trace!("analyzing {}...", file_path);
+    let unparsed_file_content = match read_schedule_file(file_path) {
+        Ok(unparsed_file_content) => unparsed_file_content,
+        Err(err) => return FileSummary::new(file_path, Err(err.to_string())),
+    };
+    let included_contents = IncludedContents::new();
+    let analysis = processor::process_with_includes(
+        &unparsed_file_content,
+        Source::File(Path::new(file_path)),
+        &included_contents,
+        options,
+    );
+    FileSummary::new(file_path, analysis.as_ref().map_err(|err| err.to_string()))
 }
 
-fn validate_arg_count(arg_count: usize) {
-    let expected_arg_count = 2usize;
-    if arg_count != expected_arg_count {
-        let usage_message = "usage: ./analyze-task-schedule file";
-        eprintln!("{}", usage_message);
-        process::exit(1);
+fn list_tasks(
+    unparsed_file_content: &str,
+    included_contents: &IncludedContents,
+    options: &cli::Options,
+    program_name: &str,
+) -> ! {
+    match processor::list_tasks(
+        unparsed_file_content,
+        source_of(&options.file_path),
+        included_contents,
+        &options.process_options,
+    ) {
+        Ok(tasks) => {
+            let rendered = if options.duration_histogram {
+                render::render_duration_histogram(
+                    &processor::duration_histogram(&tasks),
+                    options.format.into(),
+                )
+            } else {
+                render::render_task_list(&tasks, options.format.into())
+            };
+            print_output(&rendered, options, program_name);
+            process::exit(0);
+        }
+        Err(err) => {
+            if options.github {
+                print_github_annotations(&err, &options.file_path);
+            }
+            handle_processing_error(err, &options.file_path, options.format)
+        }
     }
 }
 
-fn handle_processing_error<'a>(err: Box<dyn StdError + 'a>) {
-    error!("Error: {}", err);
-    eprintln!("Error: {}", err);
+// Re-analyzes the file whenever it changes, until the process is stopped. Errors are printed
+// without stopping the watch.
+fn watch_and_analyze(options: &cli::Options, program_name: &str) -> ! {
+    let watch_result = watch_file(&options.file_path, || {
+        // clear the screen and move the cursor to the top left corner
+        print!("\x1B[2J\x1B[1;1H");
+        if let Some(rendered_analysis) = analyze_file(options, program_name) {
+            println!("{}", rendered_analysis);
+        }
+        true
+    });
+    if let Err(err) = watch_result {
+        eprintln!(
+            "{}: {}: Cannot watch the file: {}",
+            program_name, options.file_path, err
+        );
+    }
     process::exit(1);
 }
 
-fn handle_io_error(err: IoError, program_name: &str, file_path: &str) {
-    let mut err_str = String::new();
-    match err.kind() {
-        ErrorKind::NotFound => {
-            err_str.push_str(&format!("{}: {}: No such file", program_name, file_path));
+/// Calls `on_change` once right away and again each time the file is modified, until it returns
+/// false. The directory of the file is watched rather than the file itself, since editors often
+/// replace the file instead of writing to it.
+fn watch_file(file_path: &str, mut on_change: impl FnMut() -> bool) -> notify::Result<()> {
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    let path = Path::new(file_path);
+    let watched_dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    watcher.watch(watched_dir, RecursiveMode::NonRecursive)?;
+    if !on_change() {
+        return Ok(());
+    }
+    for event in receiver {
+        let event = event?;
+        let is_file_changed = matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+            && event
+                .paths
+                .iter()
+                .any(|changed_path| changed_path.file_name() == path.file_name());
+        if is_file_changed {
+            trace!("file changed, analyzing again...");
+            if !on_change() {
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+// Renders the analysis of the file, or reports why it can't
+fn analyze_file(options: &cli::Options, program_name: &str) -> Option<String> {
+    let unparsed_file_content = match read_schedule_file(&options.file_path) {
+        Ok(unparsed_file_content) => unparsed_file_content,
+        Err(err) => {
+            report_io_error(err, program_name, &options.file_path, options.format);
+            return None;
+        }
+    };
+    let included_contents = IncludedContents::new();
+    match processor::process_with_includes(
+        &unparsed_file_content,
+        source_of(&options.file_path),
+        &included_contents,
+        &options.process_options,
+    ) {
+        Ok(analysis) => {
+            match render::render_view(&analysis, options.view, options.format, options.path_style) {
+                Ok(rendered) => Some(rendered),
+                Err(reason) => {
+                    report_processing_error(
+                        ProcessError::Invalid(reason),
+                        &options.file_path,
+                        options.format,
+                    );
+                    None
+                }
+            }
         }
-        ErrorKind::PermissionDenied => {
-            err_str.push_str(&format!(
-                "{}: {}: Access to file is denied",
-                program_name, file_path
-            ));
+        Err(err) => {
+            report_processing_error(err, &options.file_path, options.format);
+            None
         }
-        _ => {
-            err_str.push_str(&format!(
-                "{}: {}: Encountered an error while opening the file: {}",
-                program_name, file_path, err
-            ));
+    }
+}
+
+// Both separators are recognized, so that Windows-style paths are handled the same way on every
+// platform, unlike Path::file_name, which only knows about the separators of the host
+fn get_executable_name(exec_path: &str) -> Option<&str> {
+    exec_path
+        .rsplit(['/', '\\'])
+        .next()
+        .filter(|name| !name.is_empty())
+}
+
+fn parse_options(args: &[String]) -> cli::Options {
+    match cli::parse_args(&args[1..]) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{}", err);
+            eprintln!("{}", cli::USAGE);
+            process::exit(1);
         }
     }
+}
+
+fn handle_processing_error(err: ProcessError, file_path: &str, format: Format) -> ! {
+    report_processing_error(err, file_path, format);
+    process::exit(1);
+}
+
+fn report_processing_error(err: ProcessError, file_path: &str, format: Format) {
+    let err_str = match &err {
+        // errors found in included files already name the file
+        ProcessError::Parse(parser_err) if parser_err.file().is_none() => {
+            format!("Error: {}: {}", file_path, parser_err)
+        }
+        ProcessError::Parse(parser_err) => format!("Error: {}", parser_err),
+        ProcessError::Analysis(analysis_err) => format!("Error: {}", analysis_err),
+        ProcessError::Invalid(reason) => format!("Error: {}", reason),
+        ProcessError::Validation(problems) => problems
+            .iter()
+            .map(|problem| format!("Error: {}", problem))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    };
     error!("{}", err_str);
-    eprintln!("{}", err_str);
+    match ViewFormat::from(format) {
+        ViewFormat::Text => eprintln!("{}", err_str),
+        ViewFormat::Json | ViewFormat::JsonLines => eprintln!("{}", render::error_to_json(&err)),
+    }
+}
+
+// workflow commands are only picked up from stdout
+fn print_github_annotations(err: &ProcessError, file_path: &str) {
+    for annotation in github_annotations(err, file_path) {
+        println!("{}", annotation);
+    }
+}
+
+/// GitHub Actions workflow commands that annotate the schedule with the processing error. Parse
+/// errors point to the line and column they are found at, in the included file they are found
+/// in, if any. Each problem found while validating the schedule is an annotation of its own.
+fn github_annotations(err: &ProcessError, file_path: &str) -> Vec<String> {
+    match err {
+        ProcessError::Parse(parser_err) => {
+            let file_path = parser_err
+                .file()
+                .map_or_else(|| file_path.to_string(), |file| file.display().to_string());
+            vec![github_command(
+                "error",
+                &file_path,
+                Some((parser_err.line(), parser_err.column())),
+                parser_err.reason().unwrap_or("Invalid syntax"),
+            )]
+        }
+        ProcessError::Analysis(analysis_err) => {
+            vec![github_command(
+                "error",
+                file_path,
+                None,
+                &analysis_err.to_string(),
+            )]
+        }
+        ProcessError::Invalid(reason) => vec![github_command("error", file_path, None, reason)],
+        ProcessError::Validation(problems) => problems
+            .iter()
+            .map(|problem| github_command("error", file_path, None, &problem.to_string()))
+            .collect(),
+    }
+}
+
+// E.g., "::error file=schedule.tasks.in,line=2,col=6::Invalid syntax". Messages and properties
+// are escaped the way the GitHub Actions toolkit escapes them, so that they stay on one line and
+// don't end the properties early.
+fn github_command(
+    command: &str,
+    file_path: &str,
+    position: Option<(usize, usize)>,
+    message: &str,
+) -> String {
+    fn escape_data(data: &str) -> String {
+        data.replace('%', "%25")
+            .replace('\r', "%0D")
+            .replace('\n', "%0A")
+    }
+    fn escape_property(property: &str) -> String {
+        escape_data(property)
+            .replace(':', "%3A")
+            .replace(',', "%2C")
+    }
+
+    let position = position.map_or_else(String::new, |(line, column)| {
+        format!(",line={},col={}", line, column)
+    });
+    format!(
+        "::{} file={}{}::{}",
+        command,
+        escape_property(file_path),
+        position,
+        escape_data(message)
+    )
+}
+
+fn handle_io_error(err: IoError, program_name: &str, file_path: &str, format: Format) -> ! {
+    report_io_error(err, program_name, file_path, format);
     process::exit(1);
 }
+
+fn report_io_error(err: IoError, program_name: &str, file_path: &str, format: Format) {
+    let file_path = Path::new(file_path);
+    let err_str = io_error_message(&err, program_name, file_path);
+    error!("{}", err_str);
+    match ViewFormat::from(format) {
+        ViewFormat::Text => eprintln!("{}", err_str),
+        ViewFormat::Json | ViewFormat::JsonLines => eprintln!(
+            "{}",
+            render::message_to_json(
+                "io",
+                serde_json::Value::from(file_path.display().to_string()),
+                &err_str
+            )
+        ),
+    }
+}
+
+fn io_error_message(err: &IoError, program_name: &str, file_path: &Path) -> String {
+    match err.kind() {
+        ErrorKind::NotFound => format!("{}: {}: No such file", program_name, file_path.display()),
+        ErrorKind::PermissionDenied => format!(
+            "{}: {}: Access to file is denied",
+            program_name,
+            file_path.display()
+        ),
+        _ => format!(
+            "{}: {}: Encountered an error while opening the file: {}",
+            program_name,
+            file_path.display(),
+            err
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    #[test]
+    fn reading_utf16_file() {
+        let test_folder = format!("{}/resources/test", env!("CARGO_MANIFEST_DIR"));
+        let content = read_schedule_file(&format!("{}/example.tasks.in", test_folder)).unwrap();
+        let utf16_content =
+            read_schedule_file(&format!("{}/example.utf16.tasks.in", test_folder)).unwrap();
+        assert_eq!(utf16_content, content);
+        assert_eq!(
+            processor::process(&utf16_content).unwrap().to_string(),
+            processor::process(&content).unwrap().to_string()
+        );
+
+        let mut big_endian_bytes = vec![0xfe, 0xff];
+        big_endian_bytes.extend(content.encode_utf16().flat_map(|unit| unit.to_be_bytes()));
+        assert_eq!(decode_text(big_endian_bytes).unwrap(), content);
+        // unpaired surrogate
+        let err = decode_text(vec![0xff, 0xfe, 0x00, 0xd8]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn github_annotations_for_errors() {
+        let err = processor::process("A(1)\nB(1) after A").unwrap_err();
+        assert_eq!(
+            github_annotations(&err, "schedule.tasks.in"),
+            vec!["::error file=schedule.tasks.in,line=2,col=6::Invalid syntax"]
+        );
+        let err = processor::process("A(1_)").unwrap_err();
+        assert_eq!(
+            github_annotations(&err, "schedule.tasks.in"),
+            vec!["::error file=schedule.tasks.in,line=1,col=3::Underscores can only separate digits: 1_"]
+        );
+
+        let test_folder = format!("{}/resources/test", env!("CARGO_MANIFEST_DIR"));
+        let file_path = format!("{}/example10.tasks.in", test_folder);
+        let content = read_schedule_file(&file_path).unwrap();
+        let err = processor::process(&content).unwrap_err();
+        assert_eq!(
+            github_annotations(&err, "resources/test/example10.tasks.in"),
+            vec!["::error file=resources/test/example10.tasks.in::There's a cycle in the schedule"]
+        );
+
+        // each problem is annotated on its own
+        let options = ProcessOptions {
+            lenient: true,
+            ..ProcessOptions::default()
+        };
+        let err =
+            processor::process_with_options("A(1)\nA(2)\nB(1) after [C]", &options).unwrap_err();
+        assert_eq!(
+            github_annotations(&err, "schedule.tasks.in"),
+            vec![
+                "::error file=schedule.tasks.in::Conflicting durations for task: A",
+                "::error file=schedule.tasks.in::Schedule is missing durations for: [\"C\"]",
+            ]
+        );
+    }
+
+    #[test]
+    fn github_command_escaping() {
+        assert_eq!(
+            github_command("notice", "a,b:c.tasks.in", None, "100%\ndone"),
+            "::notice file=a%2Cb%3Ac.tasks.in::100%25%0Adone"
+        );
+        assert_eq!(
+            github_command("error", "schedule.tasks.in", Some((2, 6)), "Invalid syntax"),
+            "::error file=schedule.tasks.in,line=2,col=6::Invalid syntax"
+        );
+    }
+
+    #[test]
+    fn executable_names() {
+        assert_eq!(
+            get_executable_name("/usr/local/bin/analyze-task-schedule"),
+            Some("analyze-task-schedule")
+        );
+        assert_eq!(
+            get_executable_name("C:\\tools\\analyze-task-schedule.exe"),
+            Some("analyze-task-schedule.exe")
+        );
+        assert_eq!(
+            get_executable_name(".\\target/debug\\analyze-task-schedule"),
+            Some("analyze-task-schedule")
+        );
+        assert_eq!(
+            get_executable_name("analyze-task-schedule"),
+            Some("analyze-task-schedule")
+        );
+        assert_eq!(get_executable_name("C:\\tools\\"), None);
+    }
+
+    #[test]
+    fn io_error_messages() {
+        let not_found = IoError::from(ErrorKind::NotFound);
+        assert_eq!(
+            io_error_message(
+                &not_found,
+                "analyze-task-schedule.exe",
+                Path::new("C:\\schedules\\release.tasks.in")
+            ),
+            "analyze-task-schedule.exe: C:\\schedules\\release.tasks.in: No such file"
+        );
+        assert_eq!(
+            io_error_message(
+                &not_found,
+                "analyze-task-schedule",
+                Path::new("schedules/release.tasks.in")
+            ),
+            "analyze-task-schedule: schedules/release.tasks.in: No such file"
+        );
+        let denied = IoError::from(ErrorKind::PermissionDenied);
+        assert_eq!(
+            io_error_message(
+                &denied,
+                "analyze-task-schedule",
+                Path::new("..\\a.tasks.in")
+            ),
+            "analyze-task-schedule: ..\\a.tasks.in: Access to file is denied"
+        );
+    }
+
+    #[test]
+    fn writing_output_to_file() {
+        let test_folder = format!("{}/resources/test", env!("CARGO_MANIFEST_DIR"));
+        let content = read_schedule_file(&format!("{}/example.tasks.in", test_folder)).unwrap();
+        let analysis = processor::process(&content).unwrap();
+        let rendered =
+            render::render(&analysis, Format::Json, render::PathStyle::default()).unwrap();
+
+        let output_path = env::temp_dir().join(format!("analysis-{}.json", process::id()));
+        fs::write(
+            &output_path,
+            "previous output that is longer than the analysis".repeat(50),
+        )
+        .unwrap();
+        let written = write_output(&rendered, output_path.to_str());
+        let written_content = fs::read_to_string(&output_path);
+        fs::remove_file(&output_path).unwrap();
+
+        written.unwrap();
+        let value: serde_json::Value = serde_json::from_str(&written_content.unwrap()).unwrap();
+        assert_eq!(value["minimum_completion_time"], 4);
+        assert_eq!(value["critical_path_count"], 6);
+
+        let missing_dir = env::temp_dir()
+            .join(format!("missing-{}", process::id()))
+            .join("analysis.json");
+        let err = write_output(&rendered, missing_dir.to_str()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn reading_gzipped_file() {
+        let file_path = format!(
+            "{}/resources/test/example2.tasks.in",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let content = read_schedule_file(&file_path).unwrap();
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(content.as_bytes()).unwrap();
+        let compressed_content = encoder.finish().unwrap();
+
+        let temp_dir = env::temp_dir();
+        let gzipped_file_path = temp_dir.join(format!("example2-{}.tasks.in.gz", process::id()));
+        // no extension, recognized by the header alone
+        let headed_file_path = temp_dir.join(format!("example2-{}.tasks.in", process::id()));
+        fs::write(&gzipped_file_path, &compressed_content).unwrap();
+        fs::write(&headed_file_path, &compressed_content).unwrap();
+        let gzipped_content = read_schedule_file(gzipped_file_path.to_str().unwrap());
+        let headed_content = read_schedule_file(headed_file_path.to_str().unwrap());
+        fs::remove_file(&gzipped_file_path).unwrap();
+        fs::remove_file(&headed_file_path).unwrap();
+
+        let gzipped_content = gzipped_content.unwrap();
+        assert_eq!(gzipped_content, content);
+        assert_eq!(headed_content.unwrap(), content);
+        assert_eq!(
+            processor::process(&gzipped_content).unwrap().to_string(),
+            processor::process(&content).unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn watching_file() {
+        let file_path = env::temp_dir().join(format!("watched-{}.tasks.in", process::id()));
+        fs::write(&file_path, "A(1)\nB(2) after [A]").unwrap();
+        let file_path = file_path.to_str().unwrap().to_string();
+        let options = cli::parse_args(&[file_path.clone(), String::from("--watch")]).unwrap();
+        let (sender, receiver) = mpsc::channel();
+        let watch_thread = std::thread::spawn(move || {
+            watch_file(&options.file_path, || {
+                let analysis = analyze_file(&options, "test").unwrap_or_default();
+                let is_reanalyzed = analysis.contains("minimum_completion_time: 6");
+                sender.send(analysis).unwrap();
+                !is_reanalyzed
+            })
+        });
+        let timeout = std::time::Duration::from_secs(10);
+        let first_analysis = receiver.recv_timeout(timeout).unwrap();
+        assert!(first_analysis.contains("minimum_completion_time: 3"));
+
+        fs::write(&file_path, "A(1)\nB(5) after [A]").unwrap();
+        // a single write can be reported as several changes, e.g., truncating and then writing
+        let is_reanalyzed = std::iter::from_fn(|| receiver.recv_timeout(timeout).ok())
+            .any(|analysis| analysis.contains("minimum_completion_time: 6"));
+        fs::remove_file(&file_path).unwrap();
+        assert!(is_reanalyzed);
+        watch_thread.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn analyzing_files() {
+        // example10 to example14 and example20 to example24
+        let pattern = format!(
+            "{}/resources/test/example[1-2][0-4].tasks.in",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let report = analyze_files(&pattern, &ProcessOptions::default()).unwrap();
+        assert_eq!(report.files.len(), 10);
+        assert_eq!(report.failed_count(), 7);
+        let file_names = report
+            .files
+            .iter()
+            .map(|file| Path::new(&file.file_path).file_name().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(file_names[0], "example10.tasks.in");
+        assert_eq!(file_names[9], "example24.tasks.in");
+        assert_eq!(
+            report.files[0].outcome,
+            Err(String::from("There's a cycle in the schedule"))
+        );
+        // example21
+        assert_eq!(report.files[6].outcome, Ok((6, 2)));
+
+        let report = analyze_files("no-such-dir/*.tasks.in", &ProcessOptions::default()).unwrap();
+        assert!(report.files.is_empty());
+        assert!(analyze_files("[", &ProcessOptions::default()).is_err());
+    }
+
+    #[test]
+    fn reading_corrupt_gzipped_file() {
+        let file_path = env::temp_dir().join(format!("corrupt-{}.tasks.in.gz", process::id()));
+        fs::write(&file_path, "A(1)").unwrap();
+        let result = read_schedule_file(file_path.to_str().unwrap());
+        fs::remove_file(&file_path).unwrap();
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,220 @@
+use crate::analyzer::ScheduleAnalysis;
+#[cfg(feature = "tokio")]
+use crate::processor::{self, ProcessOptions};
+use crate::task::{Duration, TaskLabel, TotalDuration};
+#[cfg(feature = "bincode")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Analysis whose labels are owned rather than borrowed from the analyzed content, so that it can
+/// outlive the content, e.g., to be cached between runs. Maps are ordered by label so that equal
+/// analyses serialize to equal bytes.
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "bincode", derive(Serialize, Deserialize))]
+pub struct OwnedScheduleAnalysis {
+    pub max_parallelism: usize,
+    pub task_count: usize,
+    pub minimum_completion_time: TotalDuration,
+    pub critical_path_count: usize,
+    pub critical_paths: Vec<Vec<String>>,
+    pub topological_order: Vec<String>,
+    pub task_durations: BTreeMap<String, Duration>,
+    /// task -> tasks depending on it, ordered by label
+    pub task_graph: BTreeMap<String, Vec<String>>,
+    pub edge_weights: BTreeMap<(String, String), Duration>,
+}
+
+impl<'a> From<&ScheduleAnalysis<'a>> for OwnedScheduleAnalysis {
+    fn from(analysis: &ScheduleAnalysis<'a>) -> Self {
+        let task_durations = analysis
+            .topological_order()
+            .iter()
+            .filter_map(|&task| Some((String::from(task.as_ref()), analysis.task_duration(task)?)))
+            .collect();
+        OwnedScheduleAnalysis {
+            max_parallelism: analysis.max_parallelism(),
+            task_count: analysis.task_count(),
+            minimum_completion_time: analysis.minimum_completion_time(),
+            critical_path_count: analysis.critical_path_count(),
+            critical_paths: analysis
+                .critical_paths()
+                .iter()
+                .map(|path| to_strings(path))
+                .collect(),
+            topological_order: to_strings(analysis.topological_order()),
+            task_durations,
+            task_graph: analysis
+                .task_graph()
+                .iter()
+                .map(|(task, next_tasks)| {
+                    let mut next_tasks = to_strings(next_tasks);
+                    next_tasks.sort();
+                    (String::from(task.as_ref()), next_tasks)
+                })
+                .collect(),
+            edge_weights: analysis
+                .edge_weights()
+                .iter()
+                .map(|((from_task, to_task), &weight)| {
+                    (
+                        (
+                            String::from(from_task.as_ref()),
+                            String::from(to_task.as_ref()),
+                        ),
+                        weight,
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl<'a> ScheduleAnalysis<'a> {
+    /// Copies the labels out of the analyzed content, so that the analysis can outlive it
+    pub fn into_owned(self) -> OwnedScheduleAnalysis {
+        OwnedScheduleAnalysis::from(&self)
+    }
+}
+
+#[cfg(feature = "bincode")]
+#[allow(dead_code)]
+impl OwnedScheduleAnalysis {
+    /// Compact binary representation of the analysis
+    pub fn to_bytes(&self) -> Result<Vec<u8>, bincode::Error> {
+        bincode::serialize(self)
+    }
+
+    /// Reverses `to_bytes`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, bincode::Error> {
+        bincode::deserialize(bytes)
+    }
+}
+
+#[cfg(feature = "bincode")]
+#[allow(dead_code)]
+impl<'a> ScheduleAnalysis<'a> {
+    /// Compact binary representation of the analysis, which can be read back as an
+    /// `OwnedScheduleAnalysis` with `OwnedScheduleAnalysis::from_bytes`
+    pub fn to_bytes(&self) -> Result<Vec<u8>, bincode::Error> {
+        OwnedScheduleAnalysis::from(self).to_bytes()
+    }
+}
+
+/// Processes the content on the blocking thread pool of the runtime, so that analyzing large
+/// schedules doesn't hold up other tasks. The content is moved to the pool, so the analysis comes
+/// back owned, and the error comes back as its message.
+#[cfg(feature = "tokio")]
+#[allow(dead_code)]
+pub async fn analyze_schedule_async(
+    unparsed_content: String,
+    options: ProcessOptions,
+) -> Result<OwnedScheduleAnalysis, String> {
+    let analysis = tokio::task::spawn_blocking(move || {
+        processor::process_with_options(&unparsed_content, &options)
+            .map(|analysis| analysis.into_owned())
+            .map_err(|err| err.to_string())
+    })
+    .await;
+    match analysis {
+        Ok(analysis) => analysis,
+        Err(err) => std::panic::resume_unwind(err.into_panic()),
+    }
+}
+
+fn to_strings(tasks: &[TaskLabel]) -> Vec<String> {
+    tasks
+        .iter()
+        .map(|task| String::from(task.as_ref()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processor::process;
+
+    #[test]
+    fn owned_analysis() {
+        let analysis = process("A(1)\nB(2) after [A]\nC(3) after [A]\nB -> D(1): 2").unwrap();
+        let owned_analysis = OwnedScheduleAnalysis::from(&analysis);
+        assert_eq!(owned_analysis.task_count, 4);
+        assert_eq!(owned_analysis.minimum_completion_time, 6);
+        assert_eq!(owned_analysis.critical_paths, vec![vec!["A", "B", "D"]]);
+        assert_eq!(owned_analysis.task_durations["C"], 3);
+        assert_eq!(owned_analysis.task_graph["A"], vec!["B", "C"]);
+        assert_eq!(
+            owned_analysis.edge_weights[&(String::from("B"), String::from("D"))],
+            2
+        );
+    }
+
+    // the content is dropped before the analysis is returned
+    fn analyze_file(file_name: &str) -> OwnedScheduleAnalysis {
+        let content = std::fs::read_to_string(format!(
+            "{}/resources/test/{}",
+            env!("CARGO_MANIFEST_DIR"),
+            file_name
+        ))
+        .unwrap();
+        process(&content).unwrap().into_owned()
+    }
+
+    #[test]
+    fn analysis_outliving_content() {
+        let owned_analysis = analyze_file("example.tasks.in");
+        assert_eq!(owned_analysis.task_count, 8);
+        assert_eq!(owned_analysis.minimum_completion_time, 4);
+        assert_eq!(owned_analysis.critical_path_count, 6);
+        assert_eq!(owned_analysis.critical_paths[0], vec!["Q", "J", "N", "H"]);
+        assert_eq!(owned_analysis.topological_order[0], "Q");
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn analyzing_asynchronously() {
+        let content = std::fs::read_to_string(format!(
+            "{}/resources/test/example.tasks.in",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let owned_analysis = runtime
+            .block_on(analyze_schedule_async(
+                content.clone(),
+                ProcessOptions::default(),
+            ))
+            .unwrap();
+        assert_eq!(owned_analysis, process(&content).unwrap().into_owned());
+
+        let err = runtime
+            .block_on(analyze_schedule_async(
+                String::from("A(1)\nA(2)"),
+                ProcessOptions::default(),
+            ))
+            .unwrap_err();
+        assert_eq!(err, "Conflicting durations for task: A");
+    }
+
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn bincode_round_trip() {
+        let content = std::fs::read_to_string(format!(
+            "{}/resources/test/example.tasks.in",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap();
+        let analysis = process(&content).unwrap();
+        let owned_analysis = OwnedScheduleAnalysis::from(&analysis);
+        let bytes = analysis.to_bytes().unwrap();
+        assert_eq!(bytes, owned_analysis.to_bytes().unwrap());
+        assert_eq!(
+            OwnedScheduleAnalysis::from_bytes(&bytes).unwrap(),
+            owned_analysis
+        );
+        assert!(OwnedScheduleAnalysis::from_bytes(&bytes[..bytes.len() / 2]).is_err());
+    }
+}
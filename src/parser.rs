@@ -4,13 +4,31 @@ use pest::error::Error as PestError;
 use pest::error::LineColLocation;
 use pest::iterators::{Pair, Pairs};
 use pest::Parser;
+use std::env;
 use std::error::Error as StdError;
 use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use typed_arena::Arena;
 
-#[derive(Debug)]
+/// Keeps the contents of included files around for as long as the labels borrowed from them
+pub type IncludedContents = Arena<String>;
+
+/// (line, column) of a task label in the content it is found in, both starting from 1
+pub type TaskSpan = (usize, usize);
+
+#[derive(Debug, Default)]
 pub struct ParsedData<'a> {
     task_orders: Vec<(TaskLabel<'a>, Option<TaskLabel<'a>>)>,
     task_durations: Vec<(TaskLabel<'a>, Duration)>,
+    edge_weights: Vec<((TaskLabel<'a>, TaskLabel<'a>), Duration)>,
+    task_descriptions: Vec<(TaskLabel<'a>, &'a str)>,
+    task_values: Vec<(TaskLabel<'a>, f64)>,
+    milestones: Vec<TaskLabel<'a>>,
+    // spans of the tasks declared with a duration, and of the tasks referred to without one
+    declaration_spans: Vec<(TaskLabel<'a>, TaskSpan)>,
+    dependency_spans: Vec<(TaskLabel<'a>, TaskSpan)>,
 }
 
 impl<'a> ParsedData<'a> {
@@ -21,17 +39,173 @@ impl<'a> ParsedData<'a> {
     pub fn task_orders(&self) -> &[(TaskLabel<'a>, Option<TaskLabel<'a>>)] {
         &self.task_orders
     }
+
+    pub fn edge_weights(&self) -> &[((TaskLabel<'a>, TaskLabel<'a>), Duration)] {
+        &self.edge_weights
+    }
+
+    /// Descriptions following the durations of tasks, without the quotes
+    pub fn task_descriptions(&self) -> &[(TaskLabel<'a>, &'a str)] {
+        &self.task_descriptions
+    }
+
+    /// Values in brackets following the durations of tasks, e.g., 0.2 for A(5)[0.2]
+    pub fn task_values(&self) -> &[(TaskLabel<'a>, f64)] {
+        &self.task_values
+    }
+
+    /// Tasks declared as milestones, which take no time
+    pub fn milestones(&self) -> &[TaskLabel<'a>] {
+        &self.milestones
+    }
+
+    fn merge(&mut self, other: ParsedData<'a>) {
+        self.task_orders.extend(other.task_orders);
+        self.task_durations.extend(other.task_durations);
+        self.edge_weights.extend(other.edge_weights);
+        self.task_descriptions.extend(other.task_descriptions);
+        self.task_values.extend(other.task_values);
+        self.milestones.extend(other.milestones);
+        self.declaration_spans.extend(other.declaration_spans);
+        self.dependency_spans.extend(other.dependency_spans);
+    }
+}
+
+// for editor integrations
+#[allow(dead_code)]
+impl<'a> ParsedData<'a> {
+    /// Spans of the tasks declared along with their durations, in the order they are found.
+    /// Spans of the tasks found in included files are relative to those files.
+    pub fn declaration_spans(&self) -> &[(TaskLabel<'a>, TaskSpan)] {
+        &self.declaration_spans
+    }
+
+    /// Spans of the tasks referred to without a duration, such as dependencies, in the order
+    /// they are found
+    pub fn dependency_spans(&self) -> &[(TaskLabel<'a>, TaskSpan)] {
+        &self.dependency_spans
+    }
+
+    /// Where the task is declared, or where it is first referred to if it is never declared
+    pub fn span_of(&self, task: TaskLabel<'a>) -> Option<TaskSpan> {
+        self.declaration_spans
+            .iter()
+            .chain(self.dependency_spans.iter())
+            .find(|(spanned_task, _)| *spanned_task == task)
+            .map(|&(_, span)| span)
+    }
+}
+
+/// Refinements of the grammar that reject input it accepts otherwise
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// reject numbers with leading zeros, e.g., `022`, which might be meant as octal or be typos.
+    /// A bare `0` is still accepted.
+    pub strict_numbers: bool,
+    /// what separates records, which included files are expected to use too
+    pub record_separator: RecordSeparator,
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum RecordSeparator {
+    /// each record on lines of its own
+    #[default]
+    Newline,
+    /// records separated by `;`, any number of them on a line
+    Semicolon,
+}
+
+impl FromStr for RecordSeparator {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "newline" => Ok(RecordSeparator::Newline),
+            "semicolon" | ";" => Ok(RecordSeparator::Semicolon),
+            unknown => Err(format!("Unknown record separator: {}", unknown)),
+        }
+    }
+}
+
+/// Where the content to parse is read from, which decides whether it can include files
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Source<'p> {
+    /// file whose includes are resolved relative to its directory
+    File(&'p Path),
+    /// URL the content is fetched from, which cannot include files
+    Url,
+}
+
+// Where to find included files and which files are being included at the moment
+struct IncludeContext<'a, 's> {
+    base_dir: &'s Path,
+    // content fetched from a URL has no directory to resolve includes relative to
+    remote: bool,
+    included_contents: &'a IncludedContents,
+    include_stack: &'s mut Vec<PathBuf>,
 }
 
 #[derive(Parser, Debug)]
 #[grammar = "schedule.pest"]
 pub struct ScheduleParser;
 impl ScheduleParser {
-    pub fn parse_content(content: &str) -> Result<ParsedData, ParserError> {
-        // get and unwrap the `file` rule; never fails
-        let file = ScheduleParser::parse(Rule::file, content)?.next().unwrap();
-        let mut task_orders = Vec::new();
-        let mut task_durations = Vec::new();
+    /// Parses content that does not include other files
+    pub fn parse_content(content: &str) -> Result<ParsedData<'_>, ParserError> {
+        ScheduleParser::parse_content_with_options(content, ParseOptions::default())
+    }
+
+    pub fn parse_content_with_options(
+        content: &str,
+        options: ParseOptions,
+    ) -> Result<ParsedData<'_>, ParserError> {
+        ScheduleParser::parse_records(content, None, options)
+    }
+
+    /// Parses the content of `source`. The include directives of a file are resolved relative to
+    /// the directory of the file, and included files are resolved relative to their own directory
+    /// in turn. Circular includes, including a file including itself, are rejected, as are the
+    /// include directives of content fetched from a URL.
+    pub fn parse_content_with_includes<'a>(
+        content: &'a str,
+        source: Source,
+        included_contents: &'a IncludedContents,
+        options: ParseOptions,
+    ) -> Result<ParsedData<'a>, ParserError> {
+        let (base_dir, remote, mut include_stack) = match source {
+            // the file itself is being included at the moment, as far as its includes are
+            // concerned
+            Source::File(file_path) => (
+                file_path.parent().unwrap_or_else(|| Path::new("")),
+                false,
+                fs::canonicalize(file_path).into_iter().collect::<Vec<_>>(),
+            ),
+            Source::Url => (Path::new(""), true, Vec::new()),
+        };
+        ScheduleParser::parse_records(
+            content,
+            Some(IncludeContext {
+                base_dir,
+                remote,
+                included_contents,
+                include_stack: &mut include_stack,
+            }),
+            options,
+        )
+    }
+
+    fn parse_records<'a>(
+        content: &'a str,
+        mut include_context: Option<IncludeContext<'a, '_>>,
+        options: ParseOptions,
+    ) -> Result<ParsedData<'a>, ParserError> {
+        let file_rule = match options.record_separator {
+            RecordSeparator::Newline => Rule::file,
+            RecordSeparator::Semicolon => Rule::semicolon_file,
+        };
+        // get and unwrap the file rule; never fails
+        let file = ScheduleParser::parse(file_rule, content)?.next().unwrap();
+        let mut data = ParsedData::default();
+        let mut includes = Vec::new();
 
         let mut record_count: usize = 0;
         for record in file.into_inner() {
@@ -39,11 +213,11 @@ impl ScheduleParser {
                 Rule::record => {
                     record_count += 1;
                     for field in record.into_inner() {
-                        ScheduleParser::process_record(
-                            field,
-                            &mut task_orders,
-                            &mut task_durations,
-                        );
+                        if field.as_rule() == Rule::include {
+                            includes.push(field);
+                            continue;
+                        }
+                        ScheduleParser::process_record(field, &mut data, options)?;
                     }
                 }
                 Rule::EOI => (),
@@ -52,67 +226,358 @@ impl ScheduleParser {
         }
 
         debug!("parsed record_count: {}", record_count);
-        debug!("parsed task_durations: {:?}", task_durations);
-        debug!("parsed task_orders: {:?}", task_orders);
-        Ok(ParsedData {
-            task_orders,
-            task_durations,
-        })
+        debug!("parsed task_durations: {:?}", data.task_durations);
+        debug!("parsed task_orders: {:?}", data.task_orders);
+        debug!("parsed edge_weights: {:?}", data.edge_weights);
+        for include in includes {
+            let included_data = match include_context.as_mut() {
+                Some(context) => ScheduleParser::parse_included_file(include, context, options)?,
+                None => {
+                    return Err(ParserError::at(
+                        &include,
+                        "Includes cannot be resolved without a base directory",
+                    ))
+                }
+            };
+            data.merge(included_data);
+        }
+        Ok(data)
+    }
+
+    fn parse_included_file<'a>(
+        include: Pair<Rule>,
+        context: &mut IncludeContext<'a, '_>,
+        options: ParseOptions,
+    ) -> Result<ParsedData<'a>, ParserError> {
+        let include_path = include.clone().into_inner().next().unwrap().as_str();
+        if context.remote {
+            return Err(ParserError::at(
+                &include,
+                format!(
+                    "Cannot include {}: schedules read from a URL cannot include files",
+                    include_path
+                ),
+            ));
+        }
+        let path = context.base_dir.join(include_path);
+        let canonical_path = fs::canonicalize(&path).map_err(|err| {
+            ParserError::at(
+                &include,
+                format!("Cannot include {}: {}", include_path, err),
+            )
+        })?;
+        if context.include_stack.contains(&canonical_path) {
+            return Err(ParserError::at(
+                &include,
+                format!("Circular include of {}", include_path),
+            ));
+        }
+        let content = fs::read_to_string(&canonical_path).map_err(|err| {
+            ParserError::at(
+                &include,
+                format!("Cannot include {}: {}", include_path, err),
+            )
+        })?;
+        let content = context.included_contents.alloc(content);
+
+        context.include_stack.push(canonical_path);
+        let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+        let data = ScheduleParser::parse_records(
+            content,
+            Some(IncludeContext {
+                base_dir,
+                remote: false,
+                included_contents: context.included_contents,
+                include_stack: context.include_stack,
+            }),
+            options,
+        )
+        .map_err(|err| err.in_file(&path));
+        context.include_stack.pop();
+        data
     }
 
     // `unwraps` here are completely safe as file's adherence to grammar is already
     // verified earlier
     fn process_record<'a>(
         pair: Pair<'a, Rule>,
-        task_orders: &mut Vec<(TaskLabel<'a>, Option<TaskLabel<'a>>)>,
-        task_durations: &mut Vec<(TaskLabel<'a>, Duration)>,
-    ) {
+        data: &mut ParsedData<'a>,
+        options: ParseOptions,
+    ) -> Result<(), ParserError> {
         match pair.as_rule() {
             Rule::task_name_and_duration => {
+                let span = span_of(&pair);
                 let mut pairs = pair.into_inner();
-                let (task_name, duration) = parse_task_name_and_duration(&mut pairs);
-                task_durations.push((task_name, duration));
-                task_orders.push((task_name, None));
+                let (task_name, duration) = parse_task_name_and_duration(&mut pairs, options)?;
+                data.task_durations.push((task_name, duration));
+                data.task_orders.push((task_name, None));
+                if let Some(value) = parse_task_value(&mut pairs) {
+                    data.task_values.push((task_name, value));
+                }
+                if let Some(description) = parse_task_description(&mut pairs) {
+                    data.task_descriptions.push((task_name, description));
+                }
+                data.declaration_spans.push((task_name, span));
             }
             Rule::task_dependencies => {
                 let mut pairs = pair.into_inner();
                 let task_and_duration_pair = pairs.next().unwrap();
+                let span = span_of(&task_and_duration_pair);
+                let mut task_and_duration_pairs = task_and_duration_pair.into_inner();
                 let (dependent_task_name, duration) =
-                    parse_task_name_and_duration(&mut task_and_duration_pair.into_inner());
-                task_durations.push((dependent_task_name, duration));
+                    parse_task_name_and_duration(&mut task_and_duration_pairs, options)?;
+                data.task_durations.push((dependent_task_name, duration));
+                if let Some(value) = parse_task_value(&mut task_and_duration_pairs) {
+                    data.task_values.push((dependent_task_name, value));
+                }
+                if let Some(description) = parse_task_description(&mut task_and_duration_pairs) {
+                    data.task_descriptions
+                        .push((dependent_task_name, description));
+                }
+                data.declaration_spans.push((dependent_task_name, span));
                 let task_dependency_list_pair = pairs.next().unwrap();
                 for task_name_pair in task_dependency_list_pair.into_inner() {
-                    task_orders.push((
-                        TaskLabel::new(task_name_pair.as_str()),
-                        dependent_task_name.into(),
-                    ));
+                    let task_name = TaskLabel::new(task_name_pair.as_str());
+                    data.task_orders
+                        .push((task_name, dependent_task_name.into()));
+                    data.dependency_spans
+                        .push((task_name, span_of(&task_name_pair)));
+                }
+            }
+            Rule::edge => {
+                let mut pairs = pair.into_inner();
+                let first = parse_edge_task(
+                    pairs.next().unwrap(),
+                    &mut data.task_durations,
+                    &mut data.task_values,
+                    &mut data.declaration_spans,
+                    &mut data.dependency_spans,
+                    options,
+                )?;
+                let second = parse_edge_task(
+                    pairs.next().unwrap(),
+                    &mut data.task_durations,
+                    &mut data.task_values,
+                    &mut data.declaration_spans,
+                    &mut data.dependency_spans,
+                    options,
+                )?;
+                data.task_orders.push((first, second.into()));
+                if let Some(edge_weight) = pairs.next() {
+                    let weight = parse_number(&edge_weight, options)?;
+                    data.edge_weights.push(((first, second), weight));
+                }
+            }
+            Rule::empty_edge => {
+                let task = parse_edge_task(
+                    pair.into_inner().next().unwrap(),
+                    &mut data.task_durations,
+                    &mut data.task_values,
+                    &mut data.declaration_spans,
+                    &mut data.dependency_spans,
+                    options,
+                )?;
+                data.task_orders.push((task, None));
+            }
+            Rule::milestone => {
+                let mut pairs = pair.into_inner().skip(1).peekable(); // skipping the keyword
+                let task_name_pair = pairs.next().unwrap();
+                let milestone = TaskLabel::new(task_name_pair.as_str());
+                data.milestones.push(milestone);
+                data.task_durations.push((milestone, 0));
+                data.task_orders.push((milestone, None));
+                data.declaration_spans
+                    .push((milestone, span_of(&task_name_pair)));
+                if let Some(description) =
+                    pairs.next_if(|pair| pair.as_rule() == Rule::task_description)
+                {
+                    data.task_descriptions
+                        .push((milestone, description.into_inner().next().unwrap().as_str()));
+                }
+                if let Some(task_dependency_list_pair) = pairs.next() {
+                    for task_name_pair in task_dependency_list_pair.into_inner() {
+                        let task_name = TaskLabel::new(task_name_pair.as_str());
+                        data.task_orders.push((task_name, milestone.into()));
+                        data.dependency_spans
+                            .push((task_name, span_of(&task_name_pair)));
+                    }
                 }
             }
             unknown_term => panic!("Unexpected term: {:?}", unknown_term),
         }
+        Ok(())
     }
 }
 
-fn parse_task_name_and_duration<'a>(pairs: &mut Pairs<'a, Rule>) -> (TaskLabel<'a>, Duration) {
+fn parse_task_name_and_duration<'a>(
+    pairs: &mut Pairs<'a, Rule>,
+    options: ParseOptions,
+) -> Result<(TaskLabel<'a>, Duration), ParserError> {
     let name = pairs.next().unwrap();
     let duration = pairs.next().unwrap();
-    (
+    Ok((
         TaskLabel::new(name.as_str()),
-        duration.as_str().parse::<Duration>().unwrap(),
-    )
+        parse_duration(duration, options)?,
+    ))
+}
+
+// The value optionally follows the duration of a task, ahead of its description
+fn parse_task_value(pairs: &mut Pairs<Rule>) -> Option<f64> {
+    match pairs.peek() {
+        Some(value) if value.as_rule() == Rule::task_value => {
+            pairs.next();
+            // the grammar only lets decimal numbers through
+            Some(value.into_inner().as_str().parse().unwrap())
+        }
+        _ => None,
+    }
+}
+
+// The description optionally follows the duration of a task
+fn parse_task_description<'a>(pairs: &mut Pairs<'a, Rule>) -> Option<&'a str> {
+    pairs
+        .next()
+        .map(|description| description.into_inner().next().unwrap().as_str())
+}
+
+// The duration of a task on either side of an edge is optional, and so is the value following
+// it. Tasks with a duration are recorded as declarations, the others as dependencies.
+fn parse_edge_task<'a>(
+    pair: Pair<'a, Rule>,
+    task_durations: &mut Vec<(TaskLabel<'a>, Duration)>,
+    task_values: &mut Vec<(TaskLabel<'a>, f64)>,
+    declaration_spans: &mut Vec<(TaskLabel<'a>, TaskSpan)>,
+    dependency_spans: &mut Vec<(TaskLabel<'a>, TaskSpan)>,
+    options: ParseOptions,
+) -> Result<TaskLabel<'a>, ParserError> {
+    let span = span_of(&pair);
+    let mut pairs = pair.into_inner();
+    let task = TaskLabel::new(pairs.next().unwrap().as_str());
+    match pairs.next() {
+        Some(duration) => {
+            task_durations.push((task, parse_duration(duration, options)?));
+            if let Some(value) = parse_task_value(&mut pairs) {
+                task_values.push((task, value));
+            }
+            declaration_spans.push((task, span));
+        }
+        None => dependency_spans.push((task, span)),
+    }
+    Ok(task)
+}
+
+fn span_of(pair: &Pair<Rule>) -> TaskSpan {
+    pair.as_span().start_pos().line_col()
+}
+
+// Durations are either literals or references to environment variables
+fn parse_duration(duration: Pair<Rule>, options: ParseOptions) -> Result<Duration, ParserError> {
+    match duration.clone().into_inner().next() {
+        Some(env_var) => {
+            let name = env_var.into_inner().next().unwrap().as_str();
+            let value = env::var(name).map_err(|_| {
+                ParserError::at(
+                    &duration,
+                    format!("Environment variable is not set: {}", name),
+                )
+            })?;
+            value.trim().parse::<Duration>().map_err(|_| {
+                ParserError::at(
+                    &duration,
+                    format!(
+                        "Environment variable {} is not a valid duration: {}",
+                        name, value
+                    ),
+                )
+            })
+        }
+        None => parse_number(&duration, options),
+    }
+}
+
+// "1_000" -> 1000. As in Rust's numeric literals, underscores can only separate digits.
+fn parse_number(number: &Pair<Rule>, options: ParseOptions) -> Result<Duration, ParserError> {
+    let digits = number.as_str();
+    if digits.starts_with('_') || digits.ends_with('_') || digits.contains("__") {
+        return Err(ParserError::at(
+            number,
+            format!("Underscores can only separate digits: {}", digits),
+        ));
+    }
+    let digits_only = digits.replace('_', "");
+    if options.strict_numbers && digits_only.len() > 1 && digits_only.starts_with('0') {
+        return Err(ParserError::at(
+            number,
+            format!("Numbers cannot have leading zeros: {}", digits),
+        ));
+    }
+    digits_only.parse::<Duration>().map_err(|_| {
+        ParserError::at(
+            number,
+            format!(
+                "Numbers cannot be larger than {}: {}",
+                Duration::MAX,
+                digits
+            ),
+        )
+    })
 }
 
 #[derive(Debug)]
 pub struct ParserError {
     line: usize,
     column: usize,
+    file: Option<PathBuf>,  // included file the error is found in
+    reason: Option<String>, // grammar violations have no reason
+}
+
+impl ParserError {
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Included file the error is found in, if it is not found in the file being parsed
+    pub fn file(&self) -> Option<&Path> {
+        self.file.as_deref()
+    }
+
+    pub fn reason(&self) -> Option<&str> {
+        self.reason.as_deref()
+    }
+
+    fn at(pair: &Pair<Rule>, reason: impl Into<String>) -> Self {
+        let (line, column) = pair.as_span().start_pos().line_col();
+        ParserError {
+            line,
+            column,
+            file: None,
+            reason: Some(reason.into()),
+        }
+    }
+
+    // errors in nested includes keep the innermost file
+    fn in_file(mut self, path: &Path) -> Self {
+        self.file.get_or_insert_with(|| path.to_path_buf());
+        self
+    }
 }
 
 impl StdError for ParserError {}
 
 impl fmt::Display for ParserError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "line {}, column {}", self.line, self.column)
+        if let Some(file) = &self.file {
+            write!(f, "{}: ", file.display())?;
+        }
+        write!(f, "line {}, column {}", self.line, self.column)?;
+        if let Some(reason) = &self.reason {
+            write!(f, ": {}", reason)?;
+        }
+        Ok(())
     }
 }
 
@@ -125,6 +590,8 @@ impl<R> From<PestError<R>> for ParserError {
         ParserError {
             line: line_no,
             column: col_no,
+            file: None,
+            reason: None,
         }
     }
 }
@@ -177,6 +644,25 @@ mod tests {
         assert_eq!(res.as_str(), "A--", "only consume until no match");
     }
 
+    #[test]
+    fn task_name_with_escaped_arrow() {
+        let res = ScheduleParser::parse(Rule::task_name, "A\\->B-transform->C").unwrap();
+        assert_eq!(
+            res.as_str(),
+            "A\\->B-transform",
+            "consumption stops at the arrow"
+        );
+
+        let data = ScheduleParser::parse_content("A\\->B(1)\nC(2) after [A\\->B]").unwrap();
+        assert_eq!(
+            data.task_orders(),
+            &[
+                (TaskLabel::new("A\\->B"), None),
+                (TaskLabel::new("A\\->B"), Some(TaskLabel::new("C")))
+            ]
+        );
+    }
+
     #[test]
     fn task_name_fail() {
         let res = ScheduleParser::parse(Rule::task_name, "(AAA");
@@ -198,12 +684,232 @@ mod tests {
             let mut pairs = ScheduleParser::parse(Rule::task_name_and_duration, "A(022)").unwrap();
             let pair = pairs.next().unwrap();
             let mut pairs = pair.into_inner();
-            let (task_name, duration) = parse_task_name_and_duration(&mut pairs);
+            let (task_name, duration) =
+                parse_task_name_and_duration(&mut pairs, ParseOptions::default()).unwrap();
             assert_eq!(task_name.as_ref(), "A");
             assert_eq!(duration, 22);
         }
     }
 
+    #[test]
+    fn semicolon_separated_records() {
+        let options = ParseOptions {
+            record_separator: RecordSeparator::Semicolon,
+            ..ParseOptions::default()
+        };
+        let data = ScheduleParser::parse_content_with_options(
+            "A(1) \"first; and only\"; B(2) after [A]; A -> C(3) : 1;\nD(1) -> []; milestone M after [B, C]",
+            options,
+        )
+        .unwrap();
+        assert_eq!(data.task_durations().len(), 5);
+        assert_eq!(
+            data.task_descriptions(),
+            &[(TaskLabel::new("A"), "first; and only")]
+        );
+        assert_eq!(
+            data.edge_weights(),
+            &[((TaskLabel::new("A"), TaskLabel::new("C")), 1)]
+        );
+        assert!(data.task_orders().contains(&(TaskLabel::new("D"), None)));
+        assert_eq!(data.milestones(), &[TaskLabel::new("M")]);
+        assert!(ScheduleParser::parse_content_with_options("A(1);", options).is_ok());
+        assert!(ScheduleParser::parse_content_with_options("", options).is_ok());
+
+        // records have to be separated, and errors point at where the separator is missing
+        let err =
+            ScheduleParser::parse_content_with_options("A(1);\nB(2) C(3)", options).unwrap_err();
+        assert_eq!((err.line(), err.column()), (2, 6));
+        assert!(ScheduleParser::parse_content_with_options("A(1);;B(2)", options).is_err());
+        // semicolons are only accepted when asked for
+        assert!(ScheduleParser::parse_content("A(1); B(2)").is_err());
+
+        assert_eq!("semicolon".parse(), Ok(RecordSeparator::Semicolon));
+        assert_eq!(";".parse(), Ok(RecordSeparator::Semicolon));
+        assert_eq!("newline".parse(), Ok(RecordSeparator::Newline));
+        assert_eq!(
+            "comma".parse::<RecordSeparator>(),
+            Err(String::from("Unknown record separator: comma"))
+        );
+    }
+
+    #[test]
+    fn strict_numbers() {
+        let options = ParseOptions {
+            strict_numbers: true,
+            ..ParseOptions::default()
+        };
+        let err = ScheduleParser::parse_content_with_options("B(1)\nA(022)", options).unwrap_err();
+        assert_eq!(err.line(), 2);
+        assert_eq!(err.column(), 3);
+        assert_eq!(err.reason(), Some("Numbers cannot have leading zeros: 022"));
+        let err = ScheduleParser::parse_content_with_options("A -> B(0_1)", options).unwrap_err();
+        assert_eq!(err.reason(), Some("Numbers cannot have leading zeros: 0_1"));
+        let err = ScheduleParser::parse_content_with_options("A -> B : 05", options).unwrap_err();
+        assert_eq!(err.reason(), Some("Numbers cannot have leading zeros: 05"));
+
+        let data = ScheduleParser::parse_content_with_options(
+            "A(0)\nB(22) after [A]\nB -> C(10) : 0",
+            options,
+        )
+        .unwrap();
+        assert_eq!(
+            data.task_durations(),
+            &[
+                (TaskLabel::new("A"), 0),
+                (TaskLabel::new("B"), 22),
+                (TaskLabel::new("C"), 10)
+            ]
+        );
+        assert_eq!(data.edge_weights()[0].1, 0);
+    }
+
+    #[test]
+    fn task_descriptions() {
+        let data = ScheduleParser::parse_content(
+            "A(5) \"compile the kernel\"\nB(1)\"\" after [A]\nC(2) after [B]\nB -> D(1)",
+        )
+        .unwrap();
+        assert_eq!(
+            data.task_descriptions(),
+            &[
+                (TaskLabel::new("A"), "compile the kernel"),
+                (TaskLabel::new("B"), "")
+            ]
+        );
+        assert_eq!(data.task_durations().len(), 4);
+        // descriptions end on the line they start
+        assert!(ScheduleParser::parse_content("A(5) \"compile\nthe kernel\"").is_err());
+    }
+
+    #[test]
+    fn task_values() {
+        let data = ScheduleParser::parse_content(
+            "A(5)[0.2] \"compile the kernel\"\nB(1)[3] after [A]\nC(2) after [B]",
+        )
+        .unwrap();
+        assert_eq!(
+            data.task_values(),
+            &[(TaskLabel::new("A"), 0.2), (TaskLabel::new("B"), 3.0)]
+        );
+        assert_eq!(
+            data.task_descriptions(),
+            &[(TaskLabel::new("A"), "compile the kernel")]
+        );
+        assert_eq!(data.task_orders().len(), 3);
+
+        // either side of an edge can declare a value along with the duration
+        let data =
+            ScheduleParser::parse_content("A(5)[0.2] -> B(1)[0.5]\nB -> C(2)\nD(1)[1] -> []")
+                .unwrap();
+        assert_eq!(
+            data.task_values(),
+            &[
+                (TaskLabel::new("A"), 0.2),
+                (TaskLabel::new("B"), 0.5),
+                (TaskLabel::new("D"), 1.0)
+            ]
+        );
+        assert!(ScheduleParser::parse_content("A[0.2] -> B").is_err());
+        assert!(ScheduleParser::parse_content("A(5)[.2]").is_err());
+        assert!(ScheduleParser::parse_content("A(5)[-1]").is_err());
+        assert!(ScheduleParser::parse_content("A(5)[ 1 ]").is_err());
+    }
+
+    #[test]
+    fn milestones() {
+        let data = ScheduleParser::parse_content(
+            "A(1)\nB(2)\nmilestone M \"ready\" after [A, B]\nmilestone N\nC(1) after [M]",
+        )
+        .unwrap();
+        assert_eq!(
+            data.milestones(),
+            &[TaskLabel::new("M"), TaskLabel::new("N")]
+        );
+        assert!(data.task_durations().contains(&(TaskLabel::new("M"), 0)));
+        assert!(data
+            .task_orders()
+            .contains(&(TaskLabel::new("B"), Some(TaskLabel::new("M")))));
+        assert_eq!(data.task_descriptions(), &[(TaskLabel::new("M"), "ready")]);
+        assert_eq!(data.span_of(TaskLabel::new("M")), Some((3, 11)));
+
+        // tasks can still be named after the keyword
+        let data = ScheduleParser::parse_content("milestone(1)\nmilestone -> B(1)").unwrap();
+        assert!(data.milestones().is_empty());
+        assert_eq!(data.task_durations().len(), 2);
+        assert!(ScheduleParser::parse_content("milestoneM").is_err());
+        assert!(ScheduleParser::parse_content("milestone M(1)").is_err());
+    }
+
+    #[test]
+    fn task_duration_with_digit_separators() {
+        let data = ScheduleParser::parse_content("A(1_000)\nB(1_2_3) after [A]").unwrap();
+        assert_eq!(
+            data.task_durations(),
+            &[(TaskLabel::new("A"), 1000), (TaskLabel::new("B"), 123)]
+        );
+        let data = ScheduleParser::parse_content("A(1) -> B(1): 1_0").unwrap();
+        assert_eq!(
+            data.edge_weights(),
+            &[((TaskLabel::new("A"), TaskLabel::new("B")), 10)]
+        );
+
+        let err = ScheduleParser::parse_content("A(1__0)").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 3: Underscores can only separate digits: 1__0"
+        );
+        for content in ["A(_10)", "A(10_)", "A(_)", "A(1) -> B(1): _1"] {
+            let err = ScheduleParser::parse_content(content).unwrap_err();
+            assert!(
+                err.reason().unwrap().starts_with("Underscores"),
+                "{}",
+                content
+            );
+        }
+    }
+
+    #[test]
+    fn numbers_too_large() {
+        let err = ScheduleParser::parse_content("A(70000)").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 3: Numbers cannot be larger than 65535: 70000"
+        );
+        let err = ScheduleParser::parse_content("A -> B : 99_999").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 10: Numbers cannot be larger than 65535: 99_999"
+        );
+        assert!(ScheduleParser::parse_content("A(65535) -> B : 65535").is_ok());
+    }
+
+    #[test]
+    fn task_duration_from_environment() {
+        env::set_var("PARSER_TEST_DURATION", "17");
+        env::set_var("PARSER_TEST_INVALID_DURATION", "soon");
+        let data = ScheduleParser::parse_content("A(${PARSER_TEST_DURATION})").unwrap();
+        assert_eq!(data.task_durations(), &[(TaskLabel::new("A"), 17)]);
+
+        let err = ScheduleParser::parse_content("A(1)\nB(${PARSER_TEST_UNSET_DURATION})")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            err,
+            "line 2, column 3: Environment variable is not set: PARSER_TEST_UNSET_DURATION"
+        );
+        let err = ScheduleParser::parse_content("A(${PARSER_TEST_INVALID_DURATION})")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            err,
+            "line 1, column 3: Environment variable PARSER_TEST_INVALID_DURATION is not a valid \
+             duration: soon"
+        );
+        assert!(ScheduleParser::parse(Rule::task_name_and_duration, "A(${})").is_err());
+        assert!(ScheduleParser::parse(Rule::task_name_and_duration, "A($X)").is_err());
+    }
+
     #[test]
     fn task_name_and_duration_fail() {
         assert!(ScheduleParser::parse(Rule::task_name_and_duration, "A(2.0)").is_err());
@@ -216,6 +922,242 @@ mod tests {
         assert!(ScheduleParser::parse(Rule::task_name_and_duration, "A->(2.0)").is_err());
     }
 
+    #[test]
+    fn edge_succeed() {
+        assert!(ScheduleParser::parse(Rule::edge, "A -> B").is_ok());
+        assert!(ScheduleParser::parse(Rule::edge, "A->B").is_ok());
+        assert!(ScheduleParser::parse(Rule::edge, "A- -> B-").is_ok());
+        assert!(ScheduleParser::parse(Rule::edge, "A -> B : 3").is_ok());
+        assert!(ScheduleParser::parse(Rule::edge, "A->B:3").is_ok());
+
+        let data = ScheduleParser::parse_content("A(1)\nB(2)\nA -> B : 03\nB -> C").unwrap();
+        assert_eq!(
+            data.edge_weights(),
+            &[((TaskLabel::new("A"), TaskLabel::new("B")), 3)]
+        );
+        assert!(data
+            .task_orders()
+            .contains(&(TaskLabel::new("B"), Some(TaskLabel::new("C")))));
+    }
+
+    #[test]
+    fn edge_with_durations() {
+        assert!(ScheduleParser::parse(Rule::edge, "A -> B(5)").is_ok());
+        assert!(ScheduleParser::parse(Rule::edge, "B(5) -> C").is_ok());
+        assert!(ScheduleParser::parse(Rule::edge, "A(1)->B(2):3").is_ok());
+
+        let data = ScheduleParser::parse_content("A(1)\nA -> B(5)\nB(5) -> C : 2\nC(1)").unwrap();
+        assert_eq!(
+            data.task_durations(),
+            &[
+                (TaskLabel::new("A"), 1),
+                (TaskLabel::new("B"), 5),
+                (TaskLabel::new("B"), 5),
+                (TaskLabel::new("C"), 1),
+            ]
+        );
+        assert_eq!(
+            data.task_orders(),
+            &[
+                (TaskLabel::new("A"), None),
+                (TaskLabel::new("A"), Some(TaskLabel::new("B"))),
+                (TaskLabel::new("B"), Some(TaskLabel::new("C"))),
+                (TaskLabel::new("C"), None),
+            ]
+        );
+        assert_eq!(
+            data.edge_weights(),
+            &[((TaskLabel::new("B"), TaskLabel::new("C")), 2)]
+        );
+    }
+
+    #[test]
+    fn empty_edge() {
+        assert!(ScheduleParser::parse(Rule::empty_edge, "A -> []").is_ok());
+        assert!(ScheduleParser::parse(Rule::empty_edge, "A(2)->[ ]").is_ok());
+        assert!(ScheduleParser::parse(Rule::empty_edge, "A -> [B]").is_err());
+
+        let data = ScheduleParser::parse_content("A(2) -> []\nB -> []  \nB(1)").unwrap();
+        assert_eq!(
+            data.task_orders(),
+            &[
+                (TaskLabel::new("A"), None),
+                (TaskLabel::new("B"), None),
+                (TaskLabel::new("B"), None),
+            ]
+        );
+        assert_eq!(
+            data.task_durations(),
+            &[(TaskLabel::new("A"), 2), (TaskLabel::new("B"), 1)]
+        );
+        assert!(ScheduleParser::parse_content("A -> [] : 3").is_err());
+    }
+
+    #[test]
+    fn edge_across_lines() {
+        let data = ScheduleParser::parse_content("A ->\n B").unwrap();
+        assert_eq!(
+            data.task_orders(),
+            &[(TaskLabel::new("A"), Some(TaskLabel::new("B")))]
+        );
+        let data = ScheduleParser::parse_content("A(1)\nB(2)\nA ->\n  B\n").unwrap();
+        assert_eq!(
+            data.task_orders(),
+            &[
+                (TaskLabel::new("A"), None),
+                (TaskLabel::new("B"), None),
+                (TaskLabel::new("A"), Some(TaskLabel::new("B"))),
+            ]
+        );
+    }
+
+    #[test]
+    fn edge_fail() {
+        assert!(ScheduleParser::parse(Rule::edge, "A -> ").is_err());
+        assert!(ScheduleParser::parse(Rule::edge, "-> B").is_err());
+        assert!(ScheduleParser::parse_content("A -> B : -3").is_err());
+        assert!(ScheduleParser::parse(Rule::edge, "A => B").is_err());
+        assert!(ScheduleParser::parse_content("A -> B : ").is_err());
+        assert!(ScheduleParser::parse_content("A -> B()").is_err());
+        assert!(ScheduleParser::parse_content("A -> B(-1)").is_err());
+    }
+
+    #[test]
+    fn include_succeed() {
+        assert!(ScheduleParser::parse(Rule::include, "include \"common.tasks.in\"").is_ok());
+        assert!(ScheduleParser::parse(Rule::include, "include \"../a b/c.tasks.in\"").is_ok());
+        assert!(ScheduleParser::parse(Rule::include, "include common.tasks.in").is_err());
+        assert!(ScheduleParser::parse(Rule::include, "include \"\"").is_err());
+    }
+
+    #[test]
+    fn task_spans() {
+        let unparsed_file_content = fs::read_to_string(format!(
+            "{}/resources/test/example.tasks.in",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap();
+        let data = ScheduleParser::parse_content(&unparsed_file_content).unwrap();
+        assert_eq!(data.span_of(TaskLabel::new("Q")), Some((1, 1)));
+        assert_eq!(data.span_of(TaskLabel::new("K")), Some((5, 1)));
+        assert_eq!(data.span_of(TaskLabel::new("X")), None);
+        assert_eq!(data.declaration_spans().len(), 8);
+        // "N(1) after\n  [T,\n     J]"
+        let n_dependency_spans = data
+            .dependency_spans()
+            .iter()
+            .filter(|(_, (line, _))| (7..=8).contains(line))
+            .copied()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            n_dependency_spans,
+            vec![(TaskLabel::new("T"), (7, 4)), (TaskLabel::new("J"), (8, 6))]
+        );
+
+        let data = ScheduleParser::parse_content("A -> B(2)\n  C(1) -> A").unwrap();
+        assert_eq!(data.span_of(TaskLabel::new("A")), Some((1, 1)));
+        assert_eq!(data.span_of(TaskLabel::new("B")), Some((1, 6)));
+        assert_eq!(data.span_of(TaskLabel::new("C")), Some((2, 3)));
+        assert_eq!(
+            data.declaration_spans(),
+            &[(TaskLabel::new("B"), (1, 6)), (TaskLabel::new("C"), (2, 3))]
+        );
+    }
+
+    #[test]
+    fn file_parsing_with_includes() {
+        let file_path =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("resources/test/example19.tasks.in");
+        let unparsed_file_content =
+            fs::read_to_string(&file_path).expect("Unable to read file to parse");
+        let included_contents = IncludedContents::new();
+        let data = ScheduleParser::parse_content_with_includes(
+            &unparsed_file_content,
+            Source::File(&file_path),
+            &included_contents,
+            ParseOptions::default(),
+        )
+        .unwrap();
+        let tasks = data
+            .task_durations()
+            .iter()
+            .map(|(task, _)| task.as_ref())
+            .collect::<HashSet<_>>();
+        assert_eq!(
+            tasks,
+            vec!["A", "B", "C", "D"].into_iter().collect::<HashSet<_>>()
+        );
+    }
+
+    #[test]
+    fn circular_includes() {
+        let base_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("resources/test");
+        let file_path = base_dir.join("example20.tasks.in");
+        let unparsed_file_content =
+            fs::read_to_string(&file_path).expect("Unable to read file to parse");
+        let included_contents = IncludedContents::new();
+        let err = ScheduleParser::parse_content_with_includes(
+            &unparsed_file_content,
+            Source::File(&file_path),
+            &included_contents,
+            ParseOptions::default(),
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Circular include of example20.tasks.in"));
+
+        // a file including itself
+        let file_path = base_dir.join("example20.self.tasks.in");
+        let unparsed_file_content =
+            fs::read_to_string(&file_path).expect("Unable to read file to parse");
+        let err = ScheduleParser::parse_content_with_includes(
+            &unparsed_file_content,
+            Source::File(&file_path),
+            &included_contents,
+            ParseOptions::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 1: Circular include of example20.self.tasks.in"
+        );
+    }
+
+    #[test]
+    fn unresolvable_includes() {
+        let err = ScheduleParser::parse_content("A(1)\ninclude \"common.tasks.in\"").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 1: Includes cannot be resolved without a base directory"
+        );
+
+        let included_contents = IncludedContents::new();
+        let err = ScheduleParser::parse_content_with_includes(
+            "include \"missing.tasks.in\"",
+            Source::File(&Path::new(env!("CARGO_MANIFEST_DIR")).join("schedule.tasks.in")),
+            &included_contents,
+            ParseOptions::default(),
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("line 1, column 1: Cannot include missing.tasks.in"));
+
+        let err = ScheduleParser::parse_content_with_includes(
+            "A(1)\ninclude \"common.tasks.in\"",
+            Source::Url,
+            &included_contents,
+            ParseOptions::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 1: Cannot include common.tasks.in: schedules read from a URL cannot \
+             include files"
+        );
+    }
+
     #[test]
     fn file_parsing() {
         let unparsed_file_content = fs::read_to_string(format!(
@@ -1,31 +1,451 @@
 use crate::analyzer;
-use crate::analyzer::ScheduleAnalysis;
-use crate::parser::ScheduleParser;
-use crate::task::{Duration, TaskLabel, TaskOrder, TaskRelation};
-use log::trace;
+use crate::analyzer::{AnalysisError, PathOrder, ScheduleAnalysis};
+use crate::audit::StructuralAudit;
+use crate::lint;
+use crate::parser::{
+    IncludedContents, ParseOptions, ParsedData, ParserError, RecordSeparator, ScheduleParser,
+    Source,
+};
+use crate::schedule::Schedule;
+use crate::task::{Duration, TaskLabel, TaskOrder, TaskRelation, TotalDuration};
+use log::{info, trace, warn};
 use std::collections::{HashMap, HashSet};
 use std::error::Error as StdError;
+use std::fmt;
+use std::time::{Duration as StdDuration, Instant};
+use unicode_normalization::UnicodeNormalization;
 
-pub fn process<'a>(
+/// Adjustments applied to the parsed schedule before it gets analyzed
+#[derive(Debug, Default, Clone)]
+pub struct ProcessOptions {
+    /// (task, duration) pairs replacing the durations found in the input
+    pub duration_overrides: Vec<(String, Duration)>,
+    /// sets of (task, duration) pairs replacing the durations found in the input, applied in
+    /// order before `duration_overrides`
+    pub duration_overlays: Vec<Vec<(String, Duration)>>,
+    /// (pattern, factor) pairs multiplying the durations of the matching tasks, where a pattern
+    /// ending with `*` matches the labels starting with what precedes it
+    pub duration_scales: Vec<(String, f64)>,
+    /// warn about labels that are likely to be typos
+    pub strict_labels: bool,
+    /// warn about tasks declared more than once, even with the same duration
+    pub strict: bool,
+    /// warn about relations declared more than once and relations implied by other relations
+    pub audit: bool,
+    /// report all the problems keeping the schedule from being analyzed at once, rather than
+    /// stopping at the first one
+    pub lenient: bool,
+    /// reject schedules that take no time to complete, e.g., all durations are zero
+    pub require_positive_makespan: bool,
+    /// warn about critical tasks that take no time, which might be missing an estimate
+    pub warn_zero_critical: bool,
+    /// report the time spent in each processing stage
+    pub profile: bool,
+    /// order critical paths made up of a single task by the order the tasks are declared in
+    pub preserve_order: bool,
+    /// reject tasks taking longer than this, which are likely to be typos
+    pub max_duration: Option<Duration>,
+    /// duration of the tasks that are only referenced as dependencies, which are rejected for
+    /// missing a duration otherwise
+    pub infer_leaf_duration: Option<Duration>,
+    /// reject schedules where a task depends on more than one task
+    pub require_tree: bool,
+    /// report whether the schedule can complete by this time
+    pub deadline: Option<TotalDuration>,
+    /// reject schedules that cannot complete by the deadline
+    pub strict_deadline: bool,
+    /// reject labels that are not in this set
+    pub allowed_labels: Option<HashSet<String>>,
+    /// only count the critical paths when there are more than this many of them
+    pub max_paths: Option<usize>,
+    /// how the critical paths are ordered
+    pub path_order: PathOrder,
+    /// reject numbers with leading zeros, such as `022`, while parsing
+    pub strict_numbers: bool,
+    /// what separates the records of the input
+    pub record_separator: RecordSeparator,
+}
+
+impl ProcessOptions {
+    fn parse_options(&self) -> ParseOptions {
+        ParseOptions {
+            strict_numbers: self.strict_numbers,
+            record_separator: self.record_separator,
+        }
+    }
+}
+
+/// Reasons a schedule cannot be processed
+#[derive(Debug)]
+pub enum ProcessError<'a> {
+    Parse(ParserError),
+    Analysis(AnalysisError<'a>),
+    /// the schedule is well-formed, but inconsistent or rejected by one of the options
+    Invalid(String),
+    /// all the problems found in lenient mode, in the order they were found
+    Validation(Vec<AnalysisError<'a>>),
+}
+
+#[allow(dead_code)]
+impl<'a> ProcessError<'a> {
+    pub fn as_parse_error(&self) -> Option<&ParserError> {
+        match self {
+            ProcessError::Parse(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    pub fn as_analysis_error(&self) -> Option<&AnalysisError<'a>> {
+        match self {
+            ProcessError::Analysis(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> StdError for ProcessError<'a> {}
+
+impl<'a> fmt::Display for ProcessError<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProcessError::Parse(err) => write!(f, "{}", err),
+            ProcessError::Analysis(err) => write!(f, "{}", err),
+            ProcessError::Invalid(reason) => write!(f, "{}", reason),
+            ProcessError::Validation(problems) => {
+                let problems = problems
+                    .iter()
+                    .map(|problem| problem.to_string())
+                    .collect::<Vec<_>>();
+                write!(f, "{}", problems.join("\n"))
+            }
+        }
+    }
+}
+
+impl<'a> From<ParserError> for ProcessError<'a> {
+    fn from(err: ParserError) -> Self {
+        ProcessError::Parse(err)
+    }
+}
+
+impl<'a> From<AnalysisError<'a>> for ProcessError<'a> {
+    fn from(err: AnalysisError<'a>) -> Self {
+        ProcessError::Analysis(err)
+    }
+}
+
+impl<'a> From<String> for ProcessError<'a> {
+    fn from(reason: String) -> Self {
+        ProcessError::Invalid(reason)
+    }
+}
+
+#[allow(dead_code)]
+pub fn process<'a>(unparsed_content: &'a str) -> Result<ScheduleAnalysis<'a>, ProcessError<'a>> {
+    process_with_options(unparsed_content, &ProcessOptions::default())
+}
+
+pub fn process_with_options<'a>(
     unparsed_content: &'a str,
-) -> Result<ScheduleAnalysis<'a>, Box<dyn StdError + 'a>> {
+    options: &ProcessOptions,
+) -> Result<ScheduleAnalysis<'a>, ProcessError<'a>> {
     trace!("parsing content...");
-    let data = ScheduleParser::parse_content(unparsed_content)?;
+    let started = Instant::now();
+    let data =
+        ScheduleParser::parse_content_with_options(unparsed_content, options.parse_options())?;
+    process_parsed_data(data, started.elapsed(), options)
+}
+
+/// Same as `process_with_options`, except that the content is read from `source`, whose included
+/// files are resolved as `ScheduleParser::parse_content_with_includes` does and kept alive by
+/// `included_contents`
+pub fn process_with_includes<'a>(
+    unparsed_content: &'a str,
+    source: Source,
+    included_contents: &'a IncludedContents,
+    options: &ProcessOptions,
+) -> Result<ScheduleAnalysis<'a>, ProcessError<'a>> {
+    trace!("parsing content...");
+    let started = Instant::now();
+    let data = ScheduleParser::parse_content_with_includes(
+        unparsed_content,
+        source,
+        included_contents,
+        options.parse_options(),
+    )?;
+    process_parsed_data(data, started.elapsed(), options)
+}
+
+/// Tasks declared in the content along with their durations, in label order. The schedule is
+/// not analyzed, so tasks of cyclic schedules are listed too, but conflicting durations are still
+/// rejected.
+pub fn list_tasks<'a>(
+    unparsed_content: &'a str,
+    source: Source,
+    included_contents: &'a IncludedContents,
+    options: &ProcessOptions,
+) -> Result<Vec<(TaskLabel<'a>, Duration)>, ProcessError<'a>> {
+    trace!("parsing content...");
+    let data = ScheduleParser::parse_content_with_includes(
+        unparsed_content,
+        source,
+        included_contents,
+        options.parse_options(),
+    )?;
+    let (task_durations, _) = establish_task_durations(data.task_durations())?;
+    let mut tasks = task_durations.into_iter().collect::<Vec<_>>();
+    tasks.sort_unstable();
+    Ok(tasks)
+}
+
+/// Number of tasks per duration bucket, where the buckets double in size: `0`, `1`, `2-3`, `4-7`,
+/// and so on, up to the bucket of the longest task. Buckets in between are kept even when they
+/// are empty, so that the spread of the durations shows.
+pub fn duration_histogram(tasks: &[(TaskLabel, Duration)]) -> Vec<((Duration, Duration), usize)> {
+    // the bucket of a duration is the number of bits it takes
+    let bucket_of = |duration: Duration| (Duration::BITS - duration.leading_zeros()) as usize;
+    let bucket_count = tasks
+        .iter()
+        .map(|&(_, duration)| bucket_of(duration) + 1)
+        .max()
+        .unwrap_or(0);
+    let mut counts = vec![0; bucket_count];
+    for &(_, duration) in tasks {
+        counts[bucket_of(duration)] += 1;
+    }
+    counts
+        .into_iter()
+        .enumerate()
+        .map(|(bucket_idx, count)| {
+            let bounds = match bucket_idx {
+                0 => (0, 0),
+                _ => (
+                    1 << (bucket_idx - 1),
+                    Duration::MAX >> (Duration::BITS as usize - bucket_idx),
+                ),
+            };
+            (bounds, count)
+        })
+        .collect()
+}
+
+fn process_parsed_data<'a>(
+    data: ParsedData<'a>,
+    parsing_time: StdDuration,
+    options: &ProcessOptions,
+) -> Result<ScheduleAnalysis<'a>, ProcessError<'a>> {
+    let preparation_started = Instant::now();
+    if options.strict_labels {
+        trace!("linting labels...");
+        lint::lint_labels(&data)
+            .iter()
+            .for_each(|warning| report_warning(warning));
+    }
+    if let Some(allowed_labels) = &options.allowed_labels {
+        check_allowed_labels(&data, allowed_labels)?;
+    }
     trace!("preparing data for analysis...");
-    let task_durations = establish_task_durations(data.task_durations())?;
-    let task_orders = establish_task_orders(data.task_orders());
+    // in lenient mode, the records causing a problem are left out once the problem is collected,
+    // so that the problems of the remaining records can be found too
+    let mut problems = Vec::new();
+    let declared_durations = if options.lenient {
+        let (declared_durations, conflicting_tasks) =
+            first_declared_durations(data.task_durations());
+        problems.extend(
+            conflicting_tasks
+                .into_iter()
+                .map(AnalysisError::ConflictingDurations),
+        );
+        declared_durations
+    } else {
+        data.task_durations().to_vec()
+    };
+    let (mut task_durations, redeclarations) = establish_task_durations(&declared_durations)?;
+    if options.strict {
+        redeclaration_warnings(&redeclarations)
+            .iter()
+            .for_each(|warning| report_warning(warning));
+    }
+    let declared_orders = if options.lenient {
+        let (declared_orders, self_dependent_tasks) = independent_orders(data.task_orders());
+        problems.extend(
+            self_dependent_tasks
+                .into_iter()
+                .map(AnalysisError::SelfDependency),
+        );
+        declared_orders
+    } else {
+        data.task_orders().to_vec()
+    };
+    let task_orders = establish_task_orders(&declared_orders);
+    if let Some(leaf_duration) = options.infer_leaf_duration {
+        let inferred_tasks =
+            infer_missing_durations(&mut task_durations, &task_orders, leaf_duration);
+        if !inferred_tasks.is_empty() {
+            report_warning(&format!(
+                "Inferred a duration of {} for tasks: {}",
+                leaf_duration,
+                inferred_tasks
+                    .iter()
+                    .map(|task| task.as_ref())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+    }
+    apply_duration_scales(&mut task_durations, &options.duration_scales)?
+        .iter()
+        .for_each(|warning| report_warning(warning));
+    options
+        .duration_overlays
+        .iter()
+        .chain(std::iter::once(&options.duration_overrides))
+        .flat_map(|overrides| apply_duration_overrides(&mut task_durations, overrides))
+        .for_each(|warning| report_warning(&warning));
+    if let Some(max_duration) = options.max_duration {
+        check_max_duration(&task_durations, max_duration)?;
+    }
+    if options.lenient {
+        problems.extend(analyzer::validate_schedule(&task_orders, &task_durations));
+        if !problems.is_empty() {
+            return Err(ProcessError::Validation(problems));
+        }
+    }
+    let edge_weights = establish_edge_weights(data.edge_weights())?;
+    let schedule = Schedule::from_parts(task_orders, task_durations, edge_weights);
+    let preparation_time = preparation_started.elapsed();
     trace!("analyzing schedule...");
-    let analysis = analyzer::analyze_schedule(&task_orders, &task_durations)?;
+    let analysis = if let Some(max_paths) = options.max_paths {
+        let task_positions = if options.preserve_order {
+            Some(establish_task_positions(&data))
+        } else {
+            None
+        };
+        schedule.analyze_with_max_paths(task_positions.as_ref(), max_paths)?
+    } else if options.preserve_order {
+        schedule.analyze_in_order(&establish_task_positions(&data))?
+    } else {
+        schedule.analyze()?
+    };
+    let analysis = analysis
+        .with_task_descriptions(establish_task_descriptions(&data))
+        .with_task_values(establish_task_values(&data))
+        .with_milestones(data.milestones().iter().cloned().collect())
+        .with_path_order(options.path_order);
+    if !analysis.critical_paths_listed() {
+        report_warning(&format!(
+            "Critical paths are not listed, there are {} of them",
+            analysis.critical_path_count()
+        ));
+    }
+    if options.profile {
+        let mut stage_timings = vec![("parsing", parsing_time), ("preparation", preparation_time)];
+        stage_timings.extend_from_slice(analysis.stage_timings());
+        profile_lines(&stage_timings)
+            .iter()
+            .for_each(|line| eprintln!("{}", line));
+    }
+    if options.require_positive_makespan && analysis.minimum_completion_time() == 0 {
+        return Err(ProcessError::Invalid(String::from(
+            "Minimum completion time of the schedule is zero",
+        )));
+    }
+    if options.require_tree && !analysis.is_forest() {
+        return Err(ProcessError::Invalid(String::from(
+            "Schedule is not a tree, some tasks depend on more than one task",
+        )));
+    }
+    if options.audit {
+        StructuralAudit::new(&data, &analysis)
+            .warnings()
+            .iter()
+            .for_each(|warning| report_warning(warning));
+    }
+    if options.warn_zero_critical {
+        zero_duration_critical_task_warnings(&analysis)
+            .iter()
+            .for_each(|warning| report_warning(warning));
+    }
+    if let Some(deadline) = options.deadline {
+        match deadline_feasibility(&analysis, deadline) {
+            Err(report) if options.strict_deadline => return Err(ProcessError::Invalid(report)),
+            Ok(report) => report_info(&report),
+            Err(report) => report_warning(&report),
+        }
+    }
     Ok(analysis)
 }
 
-fn establish_task_durations<'a>(
+// One line per stage along with its share of the total time
+fn profile_lines(stage_timings: &[(&str, StdDuration)]) -> Vec<String> {
+    let total_time = stage_timings
+        .iter()
+        .map(|&(_, time)| time)
+        .sum::<StdDuration>();
+    let mut lines = stage_timings
+        .iter()
+        .map(|&(stage, time)| {
+            let share = if total_time.as_nanos() == 0 {
+                0.0
+            } else {
+                time.as_secs_f64() * 100.0 / total_time.as_secs_f64()
+            };
+            format!("Profile: {}: {:?} ({:.1}%)", stage, time, share)
+        })
+        .collect::<Vec<_>>();
+    lines.push(format!("Profile: total: {:?}", total_time));
+    lines
+}
+
+// Zero-duration tasks on a critical path are usually milestones, but they might also be tasks
+// whose estimate was forgotten
+fn zero_duration_critical_task_warnings(analysis: &ScheduleAnalysis) -> Vec<String> {
+    analysis
+        .critical_tasks()
+        .into_iter()
+        .filter(|&task| analysis.task_duration(task) == Some(0))
+        .map(|task| format!("Critical task takes no time: {}", task.as_ref()))
+        .collect()
+}
+
+// Infeasible deadlines name the critical tasks, as the schedule can only complete sooner if they
+// take less time
+fn deadline_feasibility(
+    analysis: &ScheduleAnalysis,
+    deadline: TotalDuration,
+) -> Result<String, String> {
+    let minimum_completion_time = analysis.minimum_completion_time();
+    if minimum_completion_time <= deadline {
+        return Ok(format!(
+            "Deadline {} is feasible, {} to spare",
+            deadline,
+            deadline - minimum_completion_time
+        ));
+    }
+    let critical_tasks = analysis.critical_tasks();
+    let critical_tasks = critical_tasks
+        .iter()
+        .map(|task| task.as_ref())
+        .collect::<Vec<_>>();
+    Err(format!(
+        "Deadline {} is infeasible by {}, critical tasks to shrink: {}",
+        deadline,
+        minimum_completion_time - deadline,
+        critical_tasks.join(", ")
+    ))
+}
+
+// Tasks declared more than once with the same duration along with their number of declarations,
+// in the order they are first redeclared
+type Redeclarations<'a> = Vec<(TaskLabel<'a>, usize)>;
+
+pub fn establish_task_durations<'a>(
     task_durations: &[(TaskLabel<'a>, Duration)],
-) -> Result<HashMap<TaskLabel<'a>, Duration>, String> {
+) -> Result<(HashMap<TaskLabel<'a>, Duration>, Redeclarations<'a>), String> {
     let mut same_task_with_different_duration_err = String::new();
     let durations_opt = task_durations.iter().cloned().try_fold(
-        HashMap::new(),
-        |mut task_durations, (task, duration)| {
+        (HashMap::new(), Redeclarations::new()),
+        |(mut task_durations, mut redeclarations), (task, duration)| {
             match task_durations.insert(task, duration) {
                 // encountered the same task with a different duration ?
                 Some(previous_duration) if previous_duration != duration => {
@@ -35,7 +455,17 @@ fn establish_task_durations<'a>(
                     ));
                     None
                 }
-                _ => Some(task_durations),
+                Some(_) => {
+                    match redeclarations
+                        .iter_mut()
+                        .find(|(redeclared_task, _)| *redeclared_task == task)
+                    {
+                        Some((_, count)) => *count += 1,
+                        None => redeclarations.push((task, 2)),
+                    }
+                    Some((task_durations, redeclarations))
+                }
+                None => Some((task_durations, redeclarations)),
             }
         },
     );
@@ -45,7 +475,307 @@ fn establish_task_durations<'a>(
     }
 }
 
-fn establish_task_orders<'a>(
+// Keeps the first declaration of the tasks declared with different durations, which are
+// returned in the order they are first found conflicting
+fn first_declared_durations<'a>(
+    task_durations: &[(TaskLabel<'a>, Duration)],
+) -> (Vec<(TaskLabel<'a>, Duration)>, Vec<TaskLabel<'a>>) {
+    let mut first_durations = HashMap::new();
+    let mut conflicting_tasks = Vec::new();
+    let declared_durations = task_durations
+        .iter()
+        .filter(|&&(task, duration)| {
+            let first_duration = *first_durations.entry(task).or_insert(duration);
+            if first_duration != duration && !conflicting_tasks.contains(&task) {
+                conflicting_tasks.push(task);
+            }
+            first_duration == duration
+        })
+        .cloned()
+        .collect();
+    (declared_durations, conflicting_tasks)
+}
+
+// Leaves out the orders of tasks depending on themselves, which are returned in label order
+fn independent_orders<'a>(
+    task_orders: &[(TaskLabel<'a>, Option<TaskLabel<'a>>)],
+) -> (
+    Vec<(TaskLabel<'a>, Option<TaskLabel<'a>>)>,
+    Vec<TaskLabel<'a>>,
+) {
+    let (declared_orders, self_dependencies): (Vec<_>, Vec<_>) = task_orders
+        .iter()
+        .partition(|&&(first, second)| second != Some(first));
+    let mut self_dependent_tasks = self_dependencies
+        .into_iter()
+        .map(|(task, _)| task)
+        .collect::<Vec<_>>();
+    self_dependent_tasks.sort_unstable();
+    self_dependent_tasks.dedup();
+    (declared_orders, self_dependent_tasks)
+}
+
+fn redeclaration_warnings(redeclarations: &[(TaskLabel, usize)]) -> Vec<String> {
+    redeclarations
+        .iter()
+        .map(|(task, count)| {
+            format!(
+                "Task is declared {} times with the same duration: {}",
+                count,
+                task.as_ref()
+            )
+        })
+        .collect()
+}
+
+// Names the task with the smallest label among the ones exceeding the maximum
+fn check_max_duration(
+    task_durations: &HashMap<TaskLabel, Duration>,
+    max_duration: Duration,
+) -> Result<(), String> {
+    match task_durations
+        .iter()
+        .filter(|(_, &duration)| duration > max_duration)
+        .min()
+    {
+        Some((task, duration)) => Err(format!(
+            "Duration of task {} exceeds the maximum of {}: {}",
+            task.as_ref(),
+            max_duration,
+            duration
+        )),
+        None => Ok(()),
+    }
+}
+
+/// One label per line. Surrounding white space and empty lines are ignored.
+pub fn parse_allowed_labels(content: &str) -> HashSet<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|label| !label.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// One `label,duration` pair per line, such as `Build,3`. Surrounding white space, empty lines,
+/// lines starting with `#` and a `label,duration` header are ignored.
+pub fn parse_duration_overlay(content: &str) -> Result<Vec<(String, Duration)>, String> {
+    content
+        .lines()
+        .enumerate()
+        .map(|(line_idx, line)| (line_idx + 1, line.trim()))
+        .filter(|&(line_no, line)| {
+            let is_header = line_no == 1 && line == "label,duration";
+            !(line.is_empty() || line.starts_with('#') || is_header)
+        })
+        .map(|(line_no, line)| {
+            let invalid_line = || {
+                format!(
+                    "Expected label,duration on line {}, found: {}",
+                    line_no, line
+                )
+            };
+            let (task, duration) = line.split_once(',').ok_or_else(invalid_line)?;
+            let task = task.trim();
+            if task.is_empty() {
+                return Err(invalid_line());
+            }
+            let duration = duration
+                .trim()
+                .parse::<Duration>()
+                .map_err(|_| invalid_line())?;
+            Ok((task.to_string(), duration))
+        })
+        .collect()
+}
+
+// Names every label that is not allowed, in label order. Labels are compared in their composed
+// form, so that "é" is the same label whether it is written as one character or as "e" followed
+// by a combining accent.
+fn check_allowed_labels(data: &ParsedData, allowed_labels: &HashSet<String>) -> Result<(), String> {
+    let allowed_labels = allowed_labels
+        .iter()
+        .map(|label| label.nfc().collect::<String>())
+        .collect::<HashSet<_>>();
+    let mut disallowed_labels = data
+        .task_orders()
+        .iter()
+        .flat_map(|&(task, next_task)| std::iter::once(task).chain(next_task))
+        .chain(data.task_durations().iter().map(|&(task, _)| task))
+        .filter(|task| !allowed_labels.contains(&task.nfc().collect::<String>()))
+        .collect::<Vec<_>>();
+    if disallowed_labels.is_empty() {
+        return Ok(());
+    }
+    disallowed_labels.sort_unstable();
+    disallowed_labels.dedup();
+    let disallowed_labels = disallowed_labels
+        .iter()
+        .map(|task| task.as_ref())
+        .collect::<Vec<_>>();
+    Err(format!(
+        "Labels are not allowed: {}",
+        disallowed_labels.join(", ")
+    ))
+}
+
+// The first value of a task wins
+fn establish_task_values<'a>(data: &ParsedData<'a>) -> HashMap<TaskLabel<'a>, f64> {
+    let mut task_values = HashMap::new();
+    for &(task, value) in data.task_values() {
+        task_values.entry(task).or_insert(value);
+    }
+    task_values
+}
+
+// The first description of a task wins
+fn establish_task_descriptions<'a>(data: &ParsedData<'a>) -> HashMap<TaskLabel<'a>, &'a str> {
+    let mut task_descriptions = HashMap::new();
+    for &(task, description) in data.task_descriptions() {
+        task_descriptions.entry(task).or_insert(description);
+    }
+    task_descriptions
+}
+
+// Position of the first record mentioning each task
+fn establish_task_positions<'a>(data: &ParsedData<'a>) -> HashMap<TaskLabel<'a>, usize> {
+    let mut task_positions = HashMap::new();
+    for &(task, _) in data.task_durations() {
+        let position = task_positions.len();
+        task_positions.entry(task).or_insert(position);
+    }
+    for &(first, second) in data.task_orders() {
+        for task in std::iter::once(first).chain(second) {
+            let position = task_positions.len();
+            task_positions.entry(task).or_insert(position);
+        }
+    }
+    task_positions
+}
+
+pub fn establish_edge_weights<'a>(
+    edge_weights: &[((TaskLabel<'a>, TaskLabel<'a>), Duration)],
+) -> Result<HashMap<(TaskLabel<'a>, TaskLabel<'a>), Duration>, String> {
+    let mut weights = HashMap::new();
+    for &(edge, weight) in edge_weights {
+        match weights.insert(edge, weight) {
+            // encountered the same relation with a different weight ?
+            Some(previous_weight) if previous_weight != weight => {
+                return Err(format!(
+                    "Conflicting weights for relation: {} -> {}",
+                    edge.0.as_ref(),
+                    edge.1.as_ref()
+                ));
+            }
+            _ => (),
+        }
+    }
+    Ok(weights)
+}
+
+// Gives the tasks that are only referenced as prerequisites, without a duration of their own,
+// the given duration. Tasks depending on others still need a declared duration. Returns the
+// inferred tasks in label order.
+fn infer_missing_durations<'a>(
+    task_durations: &mut HashMap<TaskLabel<'a>, Duration>,
+    task_orders: &HashSet<TaskOrder<'a>>,
+    duration: Duration,
+) -> Vec<TaskLabel<'a>> {
+    let dependent_tasks = task_orders
+        .iter()
+        .filter_map(|order| order.second())
+        .collect::<HashSet<_>>();
+    let mut inferred_tasks = task_orders
+        .iter()
+        .filter(|order| order.second().is_some())
+        .map(|order| order.first())
+        .filter(|task| !task_durations.contains_key(task) && !dependent_tasks.contains(task))
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    inferred_tasks.sort_unstable();
+    trace!("inferring durations of {:?}", inferred_tasks);
+    for &task in &inferred_tasks {
+        task_durations.insert(task, duration);
+    }
+    inferred_tasks
+}
+
+// Scaled durations are rounded to the nearest duration, halfway cases away from zero. Returns
+// warnings for the scales that do not match any task.
+fn apply_duration_scales(
+    task_durations: &mut HashMap<TaskLabel, Duration>,
+    scales: &[(String, f64)],
+) -> Result<Vec<String>, String> {
+    let mut warnings = Vec::new();
+    for (pattern, factor) in scales {
+        let mut matched = false;
+        for (task, duration) in task_durations
+            .iter_mut()
+            .filter(|(task, _)| matches_pattern(task, pattern))
+        {
+            let scaled_duration = (f64::from(*duration) * factor).round();
+            if scaled_duration > f64::from(Duration::MAX) {
+                return Err(format!(
+                    "Scaled duration of task {} is too large: {}",
+                    task.as_ref(),
+                    scaled_duration
+                ));
+            }
+            *duration = scaled_duration as Duration;
+            matched = true;
+        }
+        if !matched {
+            warnings.push(format!(
+                "Cannot scale durations, no task matches: {}",
+                pattern
+            ));
+        }
+    }
+    Ok(warnings)
+}
+
+// "build.*" matches the labels starting with "build.", any other pattern matches itself
+fn matches_pattern(task: &TaskLabel, pattern: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => task.starts_with(prefix),
+        None => task.as_ref() == pattern,
+    }
+}
+
+// Returns warnings for the overrides that do not match any task
+fn apply_duration_overrides<'a>(
+    task_durations: &mut HashMap<TaskLabel<'a>, Duration>,
+    overrides: &[(String, Duration)],
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+    for (task, duration) in overrides {
+        match task_durations
+            .iter_mut()
+            .find(|(label, _)| label.as_ref() == task)
+        {
+            Some((_, previous_duration)) => *previous_duration = *duration,
+            None => warnings.push(format!(
+                "Cannot override duration of unknown task: {}",
+                task
+            )),
+        }
+    }
+    warnings
+}
+
+fn report_warning(warning: &str) {
+    warn!("{}", warning);
+    eprintln!("Warning: {}", warning);
+}
+
+fn report_info(message: &str) {
+    info!("{}", message);
+    eprintln!("{}", message);
+}
+
+pub fn establish_task_orders<'a>(
     task_orders: &[(TaskLabel<'a>, Option<TaskLabel<'a>>)],
 ) -> HashSet<TaskOrder<'a>> {
     task_orders
@@ -64,6 +794,7 @@ mod tests {
     use super::*;
     use crate::analyzer::tests::paths;
     use std::fs;
+    use std::path::Path;
 
     lazy_static! {
         static ref TEST_FILE_FOLDER: String =
@@ -165,6 +896,18 @@ mod tests {
         assert_eq!(analysis.critical_paths(), &paths(&["A", "B", "C"]))
     }
 
+    #[test]
+    #[should_panic(expected = "Minimum completion time of the schedule is zero")]
+    fn processing_schedule_from_file_7_requiring_positive_makespan() {
+        let unparsed_content =
+            fs::read_to_string(format!("{}/{}", *TEST_FILE_FOLDER, "example7.tasks.in")).unwrap();
+        let options = ProcessOptions {
+            require_positive_makespan: true,
+            ..ProcessOptions::default()
+        };
+        let _ = process_with_options(&unparsed_content, &options).unwrap();
+    }
+
     #[test]
     fn processing_schedule_from_file_8() {
         let unparsed_content =
@@ -174,7 +917,8 @@ mod tests {
         assert_eq!(analysis.task_count(), 2);
         assert_eq!(analysis.minimum_completion_time(), 17);
         assert_eq!(analysis.critical_path_count(), 1);
-        assert_eq!(analysis.critical_paths(), &paths(&["B"]))
+        assert_eq!(analysis.critical_paths(), &paths(&["B"]));
+        assert_eq!(analysis.longest_task(), Some((TaskLabel::new("B"), 17)))
     }
 
     #[test]
@@ -230,7 +974,9 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Labels cannot have a dependency on themselves")]
+    #[should_panic(
+        expected = "Labels cannot have a dependency on themselves: BuoxppGpYOk9kdzEAELC7o9B"
+    )]
     fn processing_schedule_from_file_15() {
         let unparsed_content =
             fs::read_to_string(format!("{}/{}", *TEST_FILE_FOLDER, "example15.tasks.in")).unwrap();
@@ -248,4 +994,859 @@ mod tests {
             &paths(&["v8KK2w5u6a72cQmFVJph88->hV4qcwM0JWUb97yFkKfYcK75DL->t3e49256a01B8W1DG8m37c->BuoxppGpYOk9kdzEAELC7o9B"])
         );
     }
+
+    #[test]
+    #[should_panic(expected = "MissingDurations([TL(compil)]")]
+    fn processing_schedule_from_file_17() {
+        let unparsed_content =
+            fs::read_to_string(format!("{}/{}", *TEST_FILE_FOLDER, "example17.tasks.in")).unwrap();
+        let options = ProcessOptions {
+            strict_labels: true,
+            ..ProcessOptions::default()
+        };
+        let _ = process_with_options(&unparsed_content, &options).unwrap();
+    }
+
+    #[test]
+    fn processing_schedule_from_file_18() {
+        let unparsed_content =
+            fs::read_to_string(format!("{}/{}", *TEST_FILE_FOLDER, "example18.tasks.in")).unwrap();
+        let analysis = process(&unparsed_content).unwrap();
+        assert_eq!(analysis.max_parallelism(), 2);
+        assert_eq!(analysis.task_count(), 4);
+        assert_eq!(analysis.minimum_completion_time(), 9);
+        assert_eq!(analysis.critical_path_count(), 1);
+        assert_eq!(analysis.critical_paths(), &paths(&["A->B->D"]))
+    }
+
+    #[test]
+    #[should_panic(expected = "Conflicting weights for relation: A -> B")]
+    fn processing_schedule_with_conflicting_weights() {
+        let _ = process("A(1)\nB(1)\nA -> B : 1\nA -> B : 2").unwrap();
+    }
+
+    #[test]
+    fn processing_schedule_from_file_19() {
+        let unparsed_content =
+            fs::read_to_string(format!("{}/{}", *TEST_FILE_FOLDER, "example19.tasks.in")).unwrap();
+        let included_contents = IncludedContents::new();
+        let analysis = process_with_includes(
+            &unparsed_content,
+            Source::File(&Path::new(&*TEST_FILE_FOLDER).join("example19.tasks.in")),
+            &included_contents,
+            &ProcessOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(analysis.max_parallelism(), 2);
+        assert_eq!(analysis.task_count(), 4);
+        assert_eq!(analysis.minimum_completion_time(), 7);
+        assert_eq!(analysis.critical_path_count(), 1);
+        assert_eq!(analysis.critical_paths(), &paths(&["A->C->D"]))
+    }
+
+    #[test]
+    #[should_panic(expected = "Circular include of example20.tasks.in")]
+    fn processing_schedule_from_file_20() {
+        let unparsed_content =
+            fs::read_to_string(format!("{}/{}", *TEST_FILE_FOLDER, "example20.tasks.in")).unwrap();
+        let included_contents = IncludedContents::new();
+        let _ = process_with_includes(
+            &unparsed_content,
+            Source::File(&Path::new(&*TEST_FILE_FOLDER).join("example20.tasks.in")),
+            &included_contents,
+            &ProcessOptions::default(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn processing_schedule_from_file_21() {
+        // labels can start with a digit, so numeric identifiers need no special treatment
+        let unparsed_content =
+            fs::read_to_string(format!("{}/{}", *TEST_FILE_FOLDER, "example21.tasks.in")).unwrap();
+        let analysis = process(&unparsed_content).unwrap();
+        assert_eq!(analysis.max_parallelism(), 2);
+        assert_eq!(analysis.task_count(), 4);
+        assert_eq!(analysis.minimum_completion_time(), 6);
+        assert_eq!(analysis.critical_path_count(), 1);
+        assert_eq!(analysis.critical_paths(), &paths(&["1001->1002->1003"]))
+    }
+
+    #[test]
+    fn processing_schedule_from_file_23() {
+        let unparsed_content =
+            fs::read_to_string(format!("{}/{}", *TEST_FILE_FOLDER, "example23.tasks.in")).unwrap();
+        let analysis = process(&unparsed_content).unwrap();
+        assert_eq!(analysis.max_parallelism(), 2);
+        assert_eq!(analysis.task_count(), 4);
+        assert_eq!(analysis.minimum_completion_time(), 8);
+        assert_eq!(analysis.critical_path_count(), 1);
+        assert_eq!(analysis.critical_paths(), &paths(&["A->B->C"]))
+    }
+
+    #[test]
+    #[should_panic(expected = "Conflicting durations for task: B")]
+    fn processing_schedule_from_file_24() {
+        let unparsed_content =
+            fs::read_to_string(format!("{}/{}", *TEST_FILE_FOLDER, "example24.tasks.in")).unwrap();
+        let _ = process(&unparsed_content).unwrap();
+    }
+
+    #[test]
+    fn processing_schedule_from_file_25() {
+        let unparsed_content =
+            fs::read_to_string(format!("{}/{}", *TEST_FILE_FOLDER, "example25.tasks.in")).unwrap();
+        let analysis = process(&unparsed_content).unwrap();
+        assert_eq!(analysis.max_parallelism(), 3);
+        assert_eq!(analysis.task_count(), 4);
+        assert_eq!(analysis.minimum_completion_time(), 4);
+        assert_eq!(analysis.critical_path_count(), 2);
+        assert_eq!(analysis.critical_paths(), &paths(&["B->C", "D"]))
+    }
+
+    #[test]
+    fn processing_schedule_with_environment_durations() {
+        std::env::set_var("PROCESSOR_TEST_BUILD_SECS", "5");
+        let analysis = process("A(${PROCESSOR_TEST_BUILD_SECS})\nB(1) after [A]").unwrap();
+        assert_eq!(analysis.minimum_completion_time(), 6);
+        assert_eq!(analysis.critical_paths(), &paths(&["A->B"]));
+    }
+
+    #[test]
+    fn error_variants() {
+        let err = process("A(1)\nB(x)").unwrap_err();
+        assert!(matches!(err, ProcessError::Parse(_)));
+        assert_eq!(err.as_parse_error().unwrap().line(), 2);
+        assert!(err.as_analysis_error().is_none());
+
+        let err = process("A(1)\nB(1) after [A, C]").unwrap_err();
+        assert_eq!(
+            err.as_analysis_error(),
+            Some(&AnalysisError::MissingDurations(vec![TaskLabel::new("C")]))
+        );
+        assert!(err.as_parse_error().is_none());
+
+        let err = process("A(1) after [B]\nB(1) after [A]").unwrap_err();
+        assert_eq!(err.as_analysis_error(), Some(&AnalysisError::Cycle));
+
+        let err = process("A(1)\nA(2)").unwrap_err();
+        assert!(matches!(err, ProcessError::Invalid(_)));
+        assert!(err.as_parse_error().is_none());
+        assert!(err.as_analysis_error().is_none());
+    }
+
+    #[test]
+    fn processing_schedule_requiring_tree() {
+        let options = ProcessOptions {
+            require_tree: true,
+            ..ProcessOptions::default()
+        };
+        let analysis =
+            process_with_options("A(1)\nB(1)\nC(1) after [A]\nD(1) after [B]", &options).unwrap();
+        assert_eq!(analysis.task_count(), 4);
+        let err = process_with_options("A(1)\nB(1)\nC(1) after [A, B]", &options).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Schedule is not a tree, some tasks depend on more than one task"
+        );
+    }
+
+    #[test]
+    fn processing_schedule_with_max_duration() {
+        let unparsed_content = "A(10)\nB(5000) after [A]\nC(600) after [A]";
+        let options = ProcessOptions {
+            max_duration: Some(500),
+            ..ProcessOptions::default()
+        };
+        let err = process_with_options(unparsed_content, &options).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Duration of task B exceeds the maximum of 500: 5000"
+        );
+        let options = ProcessOptions {
+            max_duration: Some(5000),
+            ..ProcessOptions::default()
+        };
+        let analysis = process_with_options(unparsed_content, &options).unwrap();
+        assert_eq!(analysis.minimum_completion_time(), 5010);
+    }
+
+    #[test]
+    fn processing_schedule_with_deadline() {
+        let unparsed_content = "K(1)\nL(12) after [K]\nT(5) after [K]\nF(20) after [T]";
+        let analysis = process(unparsed_content).unwrap();
+        assert_eq!(
+            deadline_feasibility(&analysis, 30),
+            Ok(String::from("Deadline 30 is feasible, 4 to spare"))
+        );
+        assert_eq!(
+            deadline_feasibility(&analysis, 26),
+            Ok(String::from("Deadline 26 is feasible, 0 to spare"))
+        );
+        assert_eq!(
+            deadline_feasibility(&analysis, 20),
+            Err(String::from(
+                "Deadline 20 is infeasible by 6, critical tasks to shrink: F, K, T"
+            ))
+        );
+
+        let options = ProcessOptions {
+            deadline: Some(20),
+            ..ProcessOptions::default()
+        };
+        assert!(process_with_options(unparsed_content, &options).is_ok());
+        let options = ProcessOptions {
+            deadline: Some(20),
+            strict_deadline: true,
+            ..ProcessOptions::default()
+        };
+        let err = process_with_options(unparsed_content, &options).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Deadline 20 is infeasible by 6, critical tasks to shrink: F, K, T"
+        );
+        let options = ProcessOptions {
+            deadline: Some(26),
+            strict_deadline: true,
+            ..ProcessOptions::default()
+        };
+        assert!(process_with_options(unparsed_content, &options).is_ok());
+    }
+
+    #[test]
+    fn processing_schedule_from_file_26() {
+        let unparsed_content =
+            fs::read_to_string(format!("{}/{}", *TEST_FILE_FOLDER, "example26.tasks.in")).unwrap();
+        let analysis = process(&unparsed_content).unwrap();
+        assert_eq!(analysis.max_parallelism(), 2);
+        assert_eq!(analysis.task_count(), 4);
+        assert_eq!(analysis.minimum_completion_time(), 9);
+        assert_eq!(analysis.critical_path_count(), 1);
+        assert_eq!(analysis.critical_paths(), &paths(&["Café->Ürün->検品"]))
+    }
+
+    #[test]
+    fn processing_schedule_from_file_27() {
+        let unparsed_content =
+            fs::read_to_string(format!("{}/{}", *TEST_FILE_FOLDER, "example27.tasks.in")).unwrap();
+        let analysis = process(&unparsed_content).unwrap();
+        assert_eq!(analysis.max_parallelism(), 2);
+        assert_eq!(analysis.task_count(), 5);
+        assert_eq!(analysis.minimum_completion_time(), 46);
+        assert_eq!(analysis.critical_path_count(), 1);
+        assert_eq!(
+            analysis.critical_paths(),
+            &paths(&["checkout->configure->compile->package"])
+        );
+        assert_eq!(
+            analysis.task_description(TaskLabel::new("compile")),
+            Some("compile the kernel")
+        );
+        assert_eq!(analysis.task_description(TaskLabel::new("docs")), None);
+    }
+
+    #[test]
+    fn processing_schedule_from_file_28() {
+        let unparsed_content =
+            fs::read_to_string(format!("{}/{}", *TEST_FILE_FOLDER, "example28.tasks.in")).unwrap();
+        let analysis = process(&unparsed_content).unwrap();
+        assert_eq!(analysis.max_parallelism(), 2);
+        assert_eq!(analysis.task_count(), 5);
+        assert_eq!(analysis.minimum_completion_time(), 9);
+        assert_eq!(
+            analysis.critical_paths(),
+            &paths(&["Design->Backend->Integrated->Release"])
+        );
+        assert!(analysis.is_milestone(TaskLabel::new("Integrated")));
+        assert!(!analysis.is_milestone(TaskLabel::new("Backend")));
+        assert_eq!(analysis.milestones(), vec![TaskLabel::new("Integrated")]);
+        assert_eq!(
+            analysis.task_duration(TaskLabel::new("Integrated")),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn processing_schedule_with_allowed_labels() {
+        let unparsed_content =
+            fs::read_to_string(format!("{}/{}", *TEST_FILE_FOLDER, "example26.tasks.in")).unwrap();
+        let allowed_labels = parse_allowed_labels(
+            &fs::read_to_string(format!("{}/{}", *TEST_FILE_FOLDER, "example26.labels.in"))
+                .unwrap(),
+        );
+        // "Café" is allowed in its decomposed form
+        assert!(!allowed_labels.contains("Café"));
+        assert_eq!(allowed_labels.len(), 4);
+        let options = ProcessOptions {
+            allowed_labels: Some(allowed_labels.clone()),
+            ..ProcessOptions::default()
+        };
+        let analysis = process_with_options(&unparsed_content, &options).unwrap();
+        assert_eq!(analysis.minimum_completion_time(), 9);
+
+        let mut allowed_labels = allowed_labels;
+        allowed_labels.remove("発送");
+        allowed_labels.remove("検品");
+        let options = ProcessOptions {
+            allowed_labels: Some(allowed_labels),
+            ..ProcessOptions::default()
+        };
+        let err = process_with_options(&unparsed_content, &options).unwrap_err();
+        assert_eq!(err.to_string(), "Labels are not allowed: 検品, 発送");
+    }
+
+    #[test]
+    fn processing_schedule_preserving_order() {
+        let unparsed_content = "D(0)\nB(0)\nC(0)\nA(0)";
+        let options = ProcessOptions {
+            preserve_order: true,
+            ..ProcessOptions::default()
+        };
+        let analysis = process_with_options(unparsed_content, &options).unwrap();
+        assert_eq!(analysis.critical_paths(), &paths(&["D", "B", "C", "A"]));
+        let analysis = process(unparsed_content).unwrap();
+        assert_eq!(analysis.critical_paths(), &paths(&["A", "B", "C", "D"]));
+    }
+
+    #[test]
+    fn processing_schedule_requiring_positive_makespan() {
+        let unparsed_content =
+            fs::read_to_string(format!("{}/{}", *TEST_FILE_FOLDER, "example.tasks.in")).unwrap();
+        let options = ProcessOptions {
+            require_positive_makespan: true,
+            ..ProcessOptions::default()
+        };
+        let analysis = process_with_options(&unparsed_content, &options).unwrap();
+        assert_eq!(analysis.minimum_completion_time(), 4);
+    }
+
+    #[test]
+    fn processing_schedule_with_duration_overrides() {
+        let unparsed_content =
+            fs::read_to_string(format!("{}/{}", *TEST_FILE_FOLDER, "example.tasks.in")).unwrap();
+        let options = ProcessOptions {
+            duration_overrides: vec![(String::from("H"), 5)],
+            ..ProcessOptions::default()
+        };
+        let analysis = process_with_options(&unparsed_content, &options).unwrap();
+        assert_eq!(analysis.minimum_completion_time(), 8);
+        assert_eq!(
+            analysis.critical_paths(),
+            &paths(&["Q->J->N->H", "Q->T->K->H", "Q->T->N->H"])
+        );
+    }
+
+    #[test]
+    fn profiling_keeps_analysis_intact() {
+        let unparsed_content =
+            fs::read_to_string(format!("{}/{}", *TEST_FILE_FOLDER, "example.tasks.in")).unwrap();
+        let options = ProcessOptions {
+            profile: true,
+            ..ProcessOptions::default()
+        };
+        let profiled_analysis = process_with_options(&unparsed_content, &options).unwrap();
+        let analysis = process(&unparsed_content).unwrap();
+        assert_eq!(profiled_analysis.to_string(), analysis.to_string());
+        let stages = analysis
+            .stage_timings()
+            .iter()
+            .map(|&(stage, _)| stage)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            stages,
+            vec!["graph building", "topological sort", "critical paths"]
+        );
+    }
+
+    #[test]
+    fn profile_breakdown() {
+        let lines = profile_lines(&[
+            ("parsing", StdDuration::from_millis(3)),
+            ("analysis", StdDuration::from_millis(1)),
+        ]);
+        assert_eq!(
+            lines,
+            vec![
+                "Profile: parsing: 3ms (75.0%)",
+                "Profile: analysis: 1ms (25.0%)",
+                "Profile: total: 4ms",
+            ]
+        );
+        assert_eq!(
+            profile_lines(&[("parsing", StdDuration::from_millis(0))])[0],
+            "Profile: parsing: 0ns (0.0%)"
+        );
+    }
+
+    #[test]
+    fn max_paths() {
+        let unparsed_content =
+            fs::read_to_string(format!("{}/{}", *TEST_FILE_FOLDER, "example.tasks.in")).unwrap();
+        let options = ProcessOptions {
+            max_paths: Some(5),
+            ..ProcessOptions::default()
+        };
+        let analysis = process_with_options(&unparsed_content, &options).unwrap();
+        assert_eq!(analysis.critical_path_count(), 6);
+        assert!(analysis.critical_paths().is_empty());
+        assert_eq!(analysis.minimum_completion_time(), 4);
+
+        let options = ProcessOptions {
+            max_paths: Some(6),
+            preserve_order: true,
+            ..ProcessOptions::default()
+        };
+        let analysis = process_with_options(&unparsed_content, &options).unwrap();
+        assert_eq!(analysis.critical_paths().len(), 6);
+    }
+
+    #[test]
+    fn processing_schedule_from_file_29() {
+        let unparsed_content =
+            fs::read_to_string(format!("{}/{}", *TEST_FILE_FOLDER, "example29.tasks.in")).unwrap();
+        let options = ProcessOptions {
+            strict: true,
+            ..ProcessOptions::default()
+        };
+        let analysis = process_with_options(&unparsed_content, &options).unwrap();
+        assert_eq!(analysis.task_count(), 6);
+        assert_eq!(analysis.minimum_completion_time(), 9);
+        assert_eq!(analysis.critical_paths(), &paths(&["Fetch->Build->Test"]));
+
+        let data = ScheduleParser::parse_content(&unparsed_content).unwrap();
+        let (_, redeclarations) = establish_task_durations(data.task_durations()).unwrap();
+        assert_eq!(
+            redeclaration_warnings(&redeclarations),
+            vec![
+                "Task is declared 2 times with the same duration: Build",
+                "Task is declared 3 times with the same duration: Fetch"
+            ]
+        );
+    }
+
+    #[test]
+    fn processing_with_strict_numbers() {
+        let analysis = process("A(022)").unwrap();
+        assert_eq!(analysis.minimum_completion_time(), 22);
+        let options = ProcessOptions {
+            strict_numbers: true,
+            ..ProcessOptions::default()
+        };
+        let err = process_with_options("A(022)", &options).unwrap_err();
+        assert!(matches!(err, ProcessError::Parse(_)));
+        assert!(err
+            .to_string()
+            .ends_with("Numbers cannot have leading zeros: 022"));
+        let analysis = process_with_options("A(0)\nB(22) after [A]", &options).unwrap();
+        assert_eq!(analysis.minimum_completion_time(), 22);
+    }
+
+    #[test]
+    fn no_redeclarations() {
+        let (_, redeclarations) =
+            establish_task_durations(&[(TaskLabel::new("A"), 1), (TaskLabel::new("B"), 1)])
+                .unwrap();
+        assert!(redeclarations.is_empty());
+    }
+
+    #[test]
+    fn processing_schedule_from_file_30() {
+        let unparsed_content =
+            fs::read_to_string(format!("{}/{}", *TEST_FILE_FOLDER, "example30.tasks.in")).unwrap();
+        let err = process(&unparsed_content).unwrap_err();
+        assert_eq!(
+            err.as_analysis_error(),
+            Some(&AnalysisError::MissingDurations(vec![
+                TaskLabel::new("Approve"),
+                TaskLabel::new("Fetch")
+            ]))
+        );
+
+        let options = ProcessOptions {
+            infer_leaf_duration: Some(2),
+            ..ProcessOptions::default()
+        };
+        let analysis = process_with_options(&unparsed_content, &options).unwrap();
+        assert_eq!(analysis.task_count(), 5);
+        assert_eq!(analysis.task_duration(TaskLabel::new("Fetch")), Some(2));
+        assert_eq!(analysis.task_duration(TaskLabel::new("Approve")), Some(2));
+        assert_eq!(analysis.task_duration(TaskLabel::new("Build")), Some(3));
+        assert_eq!(analysis.minimum_completion_time(), 8);
+        assert_eq!(
+            analysis.critical_paths(),
+            &paths(&["Fetch->Build->Test->Deploy"])
+        );
+
+        // inferred durations can be overridden like the declared ones
+        let options = ProcessOptions {
+            infer_leaf_duration: Some(2),
+            duration_overrides: vec![(String::from("Approve"), 10)],
+            ..ProcessOptions::default()
+        };
+        let analysis = process_with_options(&unparsed_content, &options).unwrap();
+        assert_eq!(analysis.critical_paths(), &paths(&["Approve->Deploy"]));
+
+        // only prerequisites get an inferred duration, dependents still need their own
+        let options = ProcessOptions {
+            infer_leaf_duration: Some(2),
+            ..ProcessOptions::default()
+        };
+        let err = process_with_options("Fetch(1) -> Build\n", &options).unwrap_err();
+        assert_eq!(
+            err.as_analysis_error(),
+            Some(&AnalysisError::MissingDurations(vec![TaskLabel::new(
+                "Build"
+            )]))
+        );
+    }
+
+    #[test]
+    fn processing_schedule_from_file_31_leniently() {
+        let unparsed_content =
+            fs::read_to_string(format!("{}/{}", *TEST_FILE_FOLDER, "example31.tasks.in")).unwrap();
+        let err = process(&unparsed_content).unwrap_err();
+        assert_eq!(err.to_string(), "Conflicting durations for task: Build");
+
+        let options = ProcessOptions {
+            lenient: true,
+            ..ProcessOptions::default()
+        };
+        match process_with_options(&unparsed_content, &options).unwrap_err() {
+            ProcessError::Validation(problems) => assert_eq!(
+                problems,
+                vec![
+                    AnalysisError::ConflictingDurations(TaskLabel::new("Build")),
+                    AnalysisError::SelfDependency(TaskLabel::new("Test")),
+                    AnalysisError::MissingDurations(vec![
+                        TaskLabel::new("Configure"),
+                        TaskLabel::new("Sign")
+                    ]),
+                ]
+            ),
+            err => panic!("{:?}", err),
+        }
+
+        // cycles are only found once there are no other problems
+        let err = process_with_options("A(1) after [B]\nB(1) after [A]", &options).unwrap_err();
+        assert_eq!(err.as_analysis_error(), Some(&AnalysisError::Cycle));
+    }
+
+    #[test]
+    fn processing_schedule_from_file_33() {
+        let unparsed_content =
+            fs::read_to_string(format!("{}/{}", *TEST_FILE_FOLDER, "example33.tasks.in")).unwrap();
+        let analysis = process(&unparsed_content).unwrap();
+        assert_eq!(analysis.task_count(), 4);
+        let extract_and_load = TaskLabel::new("extract\\->load");
+        assert_eq!(analysis.task_duration(extract_and_load), Some(2));
+        assert_eq!(extract_and_load.unescaped(), "extract->load");
+        assert_eq!(analysis.minimum_completion_time(), 5);
+        assert_eq!(
+            analysis.critical_paths(),
+            &vec![vec![extract_and_load, TaskLabel::new("transform")]]
+        );
+
+        // paths are written out as relations that can be parsed again
+        let mut serialized_path = String::new();
+        analyzer::serialize_path(
+            &analysis.critical_paths()[0],
+            &mut serialized_path,
+            "->",
+            TaskLabel::MAX_LEN,
+        )
+        .unwrap();
+        assert_eq!(serialized_path, "extract\\->load->transform\n");
+        let data = ScheduleParser::parse_content(&serialized_path).unwrap();
+        assert_eq!(
+            data.task_orders(),
+            &[(extract_and_load, Some(TaskLabel::new("transform")))]
+        );
+    }
+
+    #[test]
+    fn processing_schedule_from_file_34() {
+        let unparsed_content =
+            fs::read_to_string(format!("{}/{}", *TEST_FILE_FOLDER, "example34.tasks.in")).unwrap();
+        let newline_content =
+            fs::read_to_string(format!("{}/{}", *TEST_FILE_FOLDER, "example.tasks.in")).unwrap();
+        let options = ProcessOptions {
+            record_separator: RecordSeparator::Semicolon,
+            ..ProcessOptions::default()
+        };
+        let analysis = process_with_options(&unparsed_content, &options).unwrap();
+        assert_eq!(
+            analysis.to_string(),
+            process(&newline_content).unwrap().to_string()
+        );
+        let err = process(&unparsed_content).unwrap_err();
+        assert_eq!(err.to_string(), "line 1, column 5");
+    }
+
+    #[test]
+    fn schedule_fingerprints() {
+        fn fingerprint_of(unparsed_content: &str) -> u64 {
+            let data = ScheduleParser::parse_content(unparsed_content).unwrap();
+            let (task_durations, _) = establish_task_durations(data.task_durations()).unwrap();
+            analyzer::fingerprint(&establish_task_orders(data.task_orders()), &task_durations)
+        }
+
+        let unparsed_content =
+            fs::read_to_string(format!("{}/{}", *TEST_FILE_FOLDER, "example.tasks.in")).unwrap();
+        let fingerprint = fingerprint_of(&unparsed_content);
+        assert_eq!(fingerprint, fingerprint_of(&unparsed_content));
+
+        let reordered_content = "I(1) after [N, P]
+            H(1) after [K, N]
+            P(1) after [J]
+            N(1) after [J, T]
+            K(1) after [T]
+            J(1) after [Q]
+            T(1) after [Q]
+
+            Q(1)";
+        assert_eq!(fingerprint_of(reordered_content), fingerprint);
+
+        let changed_content = unparsed_content.replace("P(1)", "P(2)");
+        assert_ne!(changed_content, unparsed_content);
+        assert_ne!(fingerprint_of(&changed_content), fingerprint);
+        assert_ne!(
+            fingerprint_of("A(1)\nB(1) after [A]"),
+            fingerprint_of("A(1)\nB(1)")
+        );
+    }
+
+    #[test]
+    fn listing_tasks() {
+        let included_contents = IncludedContents::new();
+        // cyclic
+        let file_path = Path::new(TEST_FILE_FOLDER.as_str()).join("example10.tasks.in");
+        let unparsed_content = fs::read_to_string(&file_path).unwrap();
+        let err = process(&unparsed_content).unwrap_err();
+        assert_eq!(err.as_analysis_error(), Some(&AnalysisError::Cycle));
+        let tasks = list_tasks(
+            &unparsed_content,
+            Source::File(&file_path),
+            &included_contents,
+            &ProcessOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            tasks,
+            vec![(TaskLabel::new("A"), 18), (TaskLabel::new("B"), 19)]
+        );
+
+        // conflicting durations
+        let file_path = Path::new(TEST_FILE_FOLDER.as_str()).join("example13.tasks.in");
+        let unparsed_content = fs::read_to_string(&file_path).unwrap();
+        let err = list_tasks(
+            &unparsed_content,
+            Source::File(&file_path),
+            &included_contents,
+            &ProcessOptions::default(),
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "Conflicting durations for task: A");
+    }
+
+    #[test]
+    fn duration_histogram_of_file_35() {
+        let included_contents = IncludedContents::new();
+        let file_path = Path::new(TEST_FILE_FOLDER.as_str()).join("example35.tasks.in");
+        let unparsed_content = fs::read_to_string(&file_path).unwrap();
+        // cyclic, yet its tasks can be counted
+        let err = process(&unparsed_content).unwrap_err();
+        assert_eq!(err.as_analysis_error(), Some(&AnalysisError::Cycle));
+        let tasks = list_tasks(
+            &unparsed_content,
+            Source::File(&file_path),
+            &included_contents,
+            &ProcessOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            duration_histogram(&tasks),
+            vec![
+                ((0, 0), 1),
+                ((1, 1), 2),
+                ((2, 3), 2),
+                ((4, 7), 1),
+                ((8, 15), 2),
+                ((16, 31), 1)
+            ]
+        );
+
+        assert_eq!(duration_histogram(&[]), vec![]);
+        // empty buckets in between are kept
+        let tasks = [(TaskLabel::new("A"), 1), (TaskLabel::new("B"), 9)];
+        assert_eq!(
+            duration_histogram(&tasks),
+            vec![
+                ((0, 0), 0),
+                ((1, 1), 1),
+                ((2, 3), 0),
+                ((4, 7), 0),
+                ((8, 15), 1)
+            ]
+        );
+        let tasks = [(TaskLabel::new("A"), Duration::MAX)];
+        assert_eq!(
+            duration_histogram(&tasks).last(),
+            Some(&((1 << (Duration::BITS - 1), Duration::MAX), 1))
+        );
+    }
+
+    #[test]
+    fn processing_schedule_from_file_36_with_overlays() {
+        let unparsed_content =
+            fs::read_to_string(format!("{}/{}", *TEST_FILE_FOLDER, "example36.tasks.in")).unwrap();
+        let analysis = process(&unparsed_content).unwrap();
+        assert_eq!(analysis.minimum_completion_time(), 12);
+
+        let overlay_of = |file_name| {
+            let content =
+                fs::read_to_string(format!("{}/{}", *TEST_FILE_FOLDER, file_name)).unwrap();
+            parse_duration_overlay(&content).unwrap()
+        };
+        let staging_overlay = overlay_of("example36.staging.csv");
+        let production_overlay = overlay_of("example36.production.csv");
+        assert_eq!(
+            staging_overlay,
+            vec![(String::from("Build"), 3), (String::from("Test"), 6)]
+        );
+        // Deploy is not part of the schedule, which is warned about
+        assert_eq!(
+            production_overlay,
+            vec![(String::from("Test"), 9), (String::from("Deploy"), 2)]
+        );
+
+        // the production overlay wins for Test, while Build keeps its staging duration
+        let options = ProcessOptions {
+            duration_overlays: vec![staging_overlay.clone(), production_overlay.clone()],
+            ..ProcessOptions::default()
+        };
+        let analysis = process_with_options(&unparsed_content, &options).unwrap();
+        assert_eq!(analysis.task_duration(TaskLabel::new("Build")), Some(3));
+        assert_eq!(analysis.task_duration(TaskLabel::new("Test")), Some(9));
+        assert_eq!(analysis.minimum_completion_time(), 15);
+        let options = ProcessOptions {
+            duration_overlays: vec![production_overlay, staging_overlay],
+            ..ProcessOptions::default()
+        };
+        let analysis = process_with_options(&unparsed_content, &options).unwrap();
+        assert_eq!(analysis.minimum_completion_time(), 12);
+
+        // overrides given one by one win over the overlays
+        let options = ProcessOptions {
+            duration_overlays: vec![overlay_of("example36.staging.csv")],
+            duration_overrides: vec![(String::from("Test"), 1)],
+            ..ProcessOptions::default()
+        };
+        let analysis = process_with_options(&unparsed_content, &options).unwrap();
+        assert_eq!(analysis.minimum_completion_time(), 7);
+
+        let err = parse_duration_overlay("Build,3\nTest;6").unwrap_err();
+        assert_eq!(err, "Expected label,duration on line 2, found: Test;6");
+        let err = parse_duration_overlay("Build,-3").unwrap_err();
+        assert_eq!(err, "Expected label,duration on line 1, found: Build,-3");
+        assert!(parse_duration_overlay(",3").is_err());
+    }
+
+    #[test]
+    fn processing_schedule_from_file_38_with_values() {
+        let unparsed_content =
+            fs::read_to_string(format!("{}/{}", *TEST_FILE_FOLDER, "example38.tasks.in")).unwrap();
+        let analysis = process(&unparsed_content).unwrap();
+        assert_eq!(analysis.minimum_completion_time(), 8);
+        assert_eq!(analysis.task_value(TaskLabel::new("Backend")), 0.4);
+        // Docs has no value of its own
+        assert_eq!(analysis.task_value(TaskLabel::new("Docs")), 1.0);
+        assert_eq!(
+            analysis.task_description(TaskLabel::new("Design")),
+            Some("agree on the interfaces")
+        );
+
+        let curve = analysis.value_progress_curve();
+        assert_eq!(curve.first(), Some(&(0, 0.0)));
+        assert_eq!(curve.last(), Some(&(8, 1.0)));
+        assert_eq!(
+            curve.iter().map(|&(time, _)| time).collect::<Vec<_>>(),
+            vec![0, 2, 3, 5, 7, 8]
+        );
+        // Design delivers 0.1 out of a total value of 2
+        assert!((curve[1].1 - 0.05).abs() < 1e-9);
+    }
+
+    #[test]
+    fn scaling_durations() {
+        let unparsed_content = "build.fetch(3)
+            build.compile(2) after [build.fetch]
+            test(4) after [build.compile]
+            builder(2)";
+        let analysis = process(unparsed_content).unwrap();
+        assert_eq!(analysis.minimum_completion_time(), 9);
+
+        let options = ProcessOptions {
+            duration_scales: vec![(String::from("build.*"), 1.5)],
+            ..ProcessOptions::default()
+        };
+        let analysis = process_with_options(unparsed_content, &options).unwrap();
+        // 3 * 1.5 = 4.5 rounds up to 5, 2 * 1.5 = 3
+        assert_eq!(
+            analysis.task_duration(TaskLabel::new("build.fetch")),
+            Some(5)
+        );
+        assert_eq!(
+            analysis.task_duration(TaskLabel::new("build.compile")),
+            Some(3)
+        );
+        assert_eq!(analysis.task_duration(TaskLabel::new("test")), Some(4));
+        assert_eq!(analysis.task_duration(TaskLabel::new("builder")), Some(2));
+        assert_eq!(analysis.minimum_completion_time(), 12);
+
+        let mut task_durations = vec![(TaskLabel::new("A"), 3), (TaskLabel::new("B"), 65_000)]
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+        // 3 * 0.49 = 1.47 rounds down to 1
+        let warnings =
+            apply_duration_scales(&mut task_durations, &[(String::from("A"), 0.49)]).unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(task_durations[&TaskLabel::new("A")], 1);
+        let warnings =
+            apply_duration_scales(&mut task_durations, &[(String::from("C*"), 2.0)]).unwrap();
+        assert_eq!(
+            warnings,
+            vec!["Cannot scale durations, no task matches: C*"]
+        );
+        // 65_000 * 1.01 = 65_650 does not fit in a duration
+        let err = apply_duration_scales(&mut task_durations, &[(String::from("B"), 1.01)]);
+        assert_eq!(
+            err.unwrap_err(),
+            "Scaled duration of task B is too large: 65650"
+        );
+    }
+
+    #[test]
+    fn zero_duration_critical_tasks() {
+        let analysis = process("A(2)\nK(0) after [A]\nB(1) after [K]\nC(0)").unwrap();
+        assert_eq!(
+            zero_duration_critical_task_warnings(&analysis),
+            vec!["Critical task takes no time: K"]
+        );
+        let analysis = process("A(2)\nB(1) after [A]").unwrap();
+        assert!(zero_duration_critical_task_warnings(&analysis).is_empty());
+    }
+
+    #[test]
+    fn overriding_unknown_task_duration() {
+        let mut task_durations = vec![(TaskLabel::new("A"), 1)].into_iter().collect();
+        let warnings = apply_duration_overrides(
+            &mut task_durations,
+            &[(String::from("A"), 3), (String::from("Z"), 2)],
+        );
+        assert_eq!(task_durations[&TaskLabel::new("A")], 3);
+        assert_eq!(
+            warnings,
+            vec!["Cannot override duration of unknown task: Z"]
+        );
+    }
 }
@@ -0,0 +1,123 @@
+#[cfg(feature = "http")]
+use std::io::Read;
+use std::io::{Error as IoError, ErrorKind};
+
+/// Whether the schedule is to be fetched over HTTP rather than read from the filesystem
+pub fn is_url(file_path: &str) -> bool {
+    file_path.starts_with("http://") || file_path.starts_with("https://")
+}
+
+/// Fetches the content found at the URL. Responses that report a missing or forbidden resource
+/// are reported as their I/O counterparts, so that they read the same as files.
+#[cfg(feature = "http")]
+pub fn fetch(url: &str) -> Result<Vec<u8>, IoError> {
+    let response = ureq::get(url).call().map_err(|err| match err {
+        ureq::Error::Status(404, _) | ureq::Error::Status(410, _) => {
+            IoError::from(ErrorKind::NotFound)
+        }
+        ureq::Error::Status(401, _) | ureq::Error::Status(403, _) => {
+            IoError::from(ErrorKind::PermissionDenied)
+        }
+        ureq::Error::Status(status, response) => IoError::other(format!(
+            "server responded with {} {}",
+            status,
+            response.status_text()
+        )),
+        ureq::Error::Transport(transport) => IoError::other(transport),
+    })?;
+    let mut bytes = Vec::new();
+    response.into_reader().read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+#[cfg(not(feature = "http"))]
+pub fn fetch(_url: &str) -> Result<Vec<u8>, IoError> {
+    Err(IoError::new(
+        ErrorKind::Unsupported,
+        "reading from a URL requires the http feature",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "http")]
+    use std::io::Write;
+    #[cfg(feature = "http")]
+    use std::net::TcpListener;
+    #[cfg(feature = "http")]
+    use std::thread;
+
+    // Serves a single request with the given status line and body, returning the URL to request
+    #[cfg(feature = "http")]
+    fn serve_once(status_line: &'static str, body: String) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!(
+            "http://{}/schedule.tasks.in",
+            listener.local_addr().unwrap()
+        );
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let read_len = stream.read(&mut buf).unwrap();
+                if read_len == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..read_len]);
+            }
+            write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status_line,
+                body.len(),
+                body
+            )
+            .unwrap();
+        });
+        url
+    }
+
+    #[test]
+    fn recognizing_urls() {
+        assert!(is_url("https://example.com/schedule.tasks.in"));
+        assert!(is_url("http://localhost:8080/schedule.tasks.in"));
+        assert!(!is_url("resources/test/example.tasks.in"));
+        assert!(!is_url("http.tasks.in"));
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn fetching_schedule() {
+        use crate::processor::process;
+        use std::fs;
+
+        let content = fs::read_to_string(format!(
+            "{}/resources/test/example.tasks.in",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap();
+        let url = serve_once("200 OK", content.clone());
+        let fetched_content = String::from_utf8(fetch(&url).unwrap()).unwrap();
+        assert_eq!(fetched_content, content);
+        let analysis = process(&fetched_content).unwrap();
+        assert_eq!(analysis.minimum_completion_time(), 4);
+        assert_eq!(analysis.critical_path_count(), 6);
+
+        let url = serve_once("404 Not Found", String::new());
+        assert_eq!(fetch(&url).unwrap_err().kind(), ErrorKind::NotFound);
+        let url = serve_once("503 Service Unavailable", String::new());
+        assert_eq!(
+            fetch(&url).unwrap_err().to_string(),
+            "server responded with 503 Service Unavailable"
+        );
+    }
+
+    #[cfg(not(feature = "http"))]
+    #[test]
+    fn fetching_without_http_feature() {
+        let err = fetch("https://example.com/schedule.tasks.in").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+    }
+}
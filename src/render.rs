@@ -0,0 +1,1888 @@
+use crate::aggregate::{AggregateReport, FileSummary};
+use crate::analyzer::AnalysisError;
+use crate::analyzer::ScheduleAnalysis;
+use crate::analyzer::{elide_path, serialize_path, serialize_path_with_durations};
+use crate::calendar;
+use crate::calendar::CalendarSettings;
+use crate::diff::ScheduleDiff;
+use crate::processor::ProcessError;
+use crate::task::{Duration, TaskLabel, TotalDuration};
+use serde_json::{json, Value};
+use std::fmt::Write;
+use std::str::FromStr;
+
+/// Output formats the analysis can be rendered in
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+    /// One JSON object per critical path, separated by newlines
+    JsonLines,
+    /// iCalendar with an event per task. Only the complete analysis can be rendered this way.
+    Ics(CalendarSettings),
+    /// Each task followed by the tasks depending on it, one task per line. Only the complete
+    /// analysis can be rendered this way.
+    Adjacency,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "jsonl" => Ok(Format::JsonLines),
+            "ics" => Ok(Format::Ics(CalendarSettings::default())),
+            "adjacency" => Ok(Format::Adjacency),
+            unknown => Err(format!("Unknown format: {}", unknown)),
+        }
+    }
+}
+
+/// Formats the views other than the complete analysis are rendered in. Views are rendered as text
+/// in place of the formats that only render the complete analysis, such as the calendar.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ViewFormat {
+    Text,
+    Json,
+    JsonLines,
+}
+
+impl From<Format> for ViewFormat {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Text | Format::Ics(_) | Format::Adjacency => ViewFormat::Text,
+            Format::Json => ViewFormat::Json,
+            Format::JsonLines => ViewFormat::JsonLines,
+        }
+    }
+}
+
+/// Number of decimals floating-point metrics are rendered with, unless told otherwise
+pub const DEFAULT_PRECISION: usize = 2;
+/// Most decimals floating-point metrics can be rendered with, as doubles don't hold more
+pub const MAX_PRECISION: usize = 17;
+
+// inclusive upper bounds of the slack buckets
+const SLACK_HISTOGRAM_BUCKETS: [TotalDuration; 3] = [0, 5, 20];
+
+/// Parts of the analysis that can be rendered
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum View {
+    /// the complete analysis in the chosen format
+    #[default]
+    Analysis,
+    /// tasks in topological order, one per line. Sink tasks come first in reverse.
+    Order { reverse: bool },
+    /// paths taking at most `within` less than the critical paths, along with their durations
+    NearCritical { within: TotalDuration },
+    /// the longest path leading to each sink task, longer paths first
+    SinkPaths,
+    /// statistics about the tasks and the shape of the schedule, with `precision` decimals for
+    /// the floating-point ones
+    Metrics { precision: usize },
+    /// number of tasks per slack bucket
+    SlackHistogram,
+    /// share of the value of the schedule delivered by each time tasks complete
+    ValueProgress,
+    /// short narrative on what drives the minimum completion time
+    Explanation,
+    /// critical paths with their shared prefixes collapsed into a tree
+    CriticalPathTree,
+    /// summary of the analysis on a single line, regardless of the format
+    OneLine,
+    /// tasks assigned to the given number of workers, along with when they start
+    Assignment { workers: usize },
+    /// tasks grouped into waves, where each wave can start once the previous waves complete
+    Waves,
+    /// the critical path with the given number, starting from 1, in the order the critical paths
+    /// are listed
+    CriticalPath { number: usize },
+    /// analysis of each weakly connected component on its own, along with a rollup of the
+    /// whole schedule
+    Components,
+    /// how well the given number of workers are kept busy until the schedule completes
+    Efficiency { workers: usize },
+    /// confirmation that the schedule is acyclic and complete, along with the number of tasks and
+    /// the ordering constraints among them
+    Verification,
+}
+
+/// How paths are written out in text, which the other formats leave as they are
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct PathStyle {
+    /// elide the middle of the paths with more tasks than this, see `elide_path`
+    pub max_depth: Option<usize>,
+    /// write the duration of each task in parentheses after its label, e.g., A(5)->C(9)
+    pub durations: bool,
+}
+
+/// Views selecting a part of the analysis that doesn't exist, such as a critical path past the
+/// last one, are rejected rather than rendered
+pub fn render_view(
+    analysis: &ScheduleAnalysis,
+    view: View,
+    format: Format,
+    path_style: PathStyle,
+) -> Result<String, String> {
+    let view_format = ViewFormat::from(format);
+    Ok(match view {
+        View::Analysis => render(analysis, format, path_style)?,
+        View::Order { reverse } => render_order(analysis, reverse, view_format),
+        View::NearCritical { within } => {
+            render_near_critical_paths(analysis, within, view_format, path_style)
+        }
+        View::SinkPaths => render_sink_paths(analysis, view_format, path_style),
+        View::Metrics { precision } => render_metrics(analysis, precision, view_format),
+        View::SlackHistogram => render_slack_histogram(analysis, view_format),
+        View::ValueProgress => render_value_progress(analysis, view_format),
+        View::CriticalPathTree => render_critical_path_tree(analysis, view_format),
+        View::OneLine => analysis.oneline(),
+        View::Assignment { workers } => render_assignment(analysis, workers, view_format),
+        View::Waves => render_waves(analysis, view_format),
+        View::Efficiency { workers } => render_efficiency(analysis, workers, view_format),
+        View::Components => render_components(analysis, view_format, path_style)?,
+        View::Verification => render_verification(analysis, view_format),
+        View::CriticalPath { number } => {
+            render_critical_path(analysis, number, view_format, path_style)?
+        }
+        View::Explanation => match view_format {
+            ViewFormat::Text => analysis.explain(),
+            ViewFormat::Json | ViewFormat::JsonLines => {
+                json!({ "explanation": analysis.explain() }).to_string()
+            }
+        },
+    })
+}
+
+// The JSON formats render a single array of labels
+fn render_order(analysis: &ScheduleAnalysis, reverse: bool, format: ViewFormat) -> String {
+    let mut order = analysis.topological_order().clone();
+    if reverse {
+        order.reverse();
+    }
+    match format {
+        ViewFormat::Text => order
+            .iter()
+            .map(|task| task.as_ref())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        ViewFormat::Json | ViewFormat::JsonLines => labels_to_json(&order).to_string(),
+    }
+}
+
+fn render_critical_path(
+    analysis: &ScheduleAnalysis,
+    number: usize,
+    format: ViewFormat,
+    path_style: PathStyle,
+) -> Result<String, String> {
+    if !analysis.critical_paths_listed() {
+        return Err(String::from(
+            "Cannot select a critical path, critical paths are not listed",
+        ));
+    }
+    let path = number
+        .checked_sub(1)
+        .and_then(|path_idx| analysis.critical_paths().get(path_idx))
+        .ok_or_else(|| {
+            format!(
+                "Critical path {} does not exist, there are {} critical paths",
+                number,
+                analysis.critical_path_count()
+            )
+        })?;
+    Ok(match format {
+        ViewFormat::Text => {
+            let mut text = String::new();
+            serialize_path_text(analysis, path, path_style, &mut text).unwrap();
+            text.pop(); // trailing newline
+            text
+        }
+        ViewFormat::Json | ViewFormat::JsonLines => path_to_json(analysis, path).to_string(),
+    })
+}
+
+// Task along with the tasks following it on the critical paths going through the task
+struct PathTreeNode<'a> {
+    task: TaskLabel<'a>,
+    children: Vec<PathTreeNode<'a>>,
+}
+
+impl<'a> PathTreeNode<'a> {
+    fn to_json(&self) -> Value {
+        json!({
+            "task": self.task.as_ref(),
+            "next": self.children.iter().map(|child| child.to_json()).collect::<Vec<_>>(),
+        })
+    }
+
+    fn write_text(&self, depth: usize, text: &mut String) {
+        writeln!(text, "{}{}", "  ".repeat(depth), self.task.as_ref()).unwrap();
+        for child in &self.children {
+            child.write_text(depth + 1, text);
+        }
+    }
+}
+
+// Paths sharing a prefix share the nodes of the prefix. Every path from a root to a leaf is one of
+// the given paths, as no path is a prefix of another one. Children keep the order of the paths.
+fn path_tree<'a>(paths: &[Vec<TaskLabel<'a>>]) -> Vec<PathTreeNode<'a>> {
+    let mut roots = Vec::new();
+    for path in paths {
+        let mut nodes: &mut Vec<PathTreeNode<'a>> = &mut roots;
+        for &task in path {
+            let node_idx = match nodes.iter().position(|node| node.task == task) {
+                Some(node_idx) => node_idx,
+                None => {
+                    nodes.push(PathTreeNode {
+                        task,
+                        children: Vec::new(),
+                    });
+                    nodes.len() - 1
+                }
+            };
+            nodes = &mut nodes[node_idx].children;
+        }
+    }
+    roots
+}
+
+fn render_critical_path_tree(analysis: &ScheduleAnalysis, format: ViewFormat) -> String {
+    let roots = path_tree(analysis.critical_paths());
+    match format {
+        ViewFormat::Text => {
+            let mut text = String::new();
+            for root in &roots {
+                root.write_text(0, &mut text);
+            }
+            text.pop(); // trailing newline
+            text
+        }
+        ViewFormat::Json => json!({
+            "critical_path_count": analysis.critical_path_count(),
+            "critical_path_tree": roots.iter().map(|root| root.to_json()).collect::<Vec<_>>(),
+        })
+        .to_string(),
+        ViewFormat::JsonLines => roots
+            .iter()
+            .map(|root| root.to_json().to_string())
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+fn render_slack_histogram(analysis: &ScheduleAnalysis, format: ViewFormat) -> String {
+    let counts = analysis.slack_histogram(&SLACK_HISTOGRAM_BUCKETS);
+    let bucket_names = slack_bucket_names(&SLACK_HISTOGRAM_BUCKETS);
+    match format {
+        ViewFormat::Text => {
+            let name_width = bucket_names
+                .iter()
+                .map(|name| name.chars().count())
+                .max()
+                .unwrap_or(0);
+            bucket_names
+                .iter()
+                .zip(counts.iter())
+                .map(|(name, &count)| {
+                    format!(
+                        "{:>width$} | {} {}",
+                        name,
+                        "#".repeat(count),
+                        count,
+                        width = name_width
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        ViewFormat::Json | ViewFormat::JsonLines => Value::from(
+            bucket_names
+                .iter()
+                .zip(counts.iter())
+                .map(|(name, count)| json!({"slack": name, "task_count": count}))
+                .collect::<Vec<_>>(),
+        )
+        .to_string(),
+    }
+}
+
+fn render_value_progress(analysis: &ScheduleAnalysis, format: ViewFormat) -> String {
+    let curve = analysis.value_progress_curve();
+    match format {
+        ViewFormat::Text => {
+            let time_width = curve
+                .last()
+                .map_or(0, |(time, _)| time.to_string().chars().count());
+            curve
+                .iter()
+                .map(|&(time, share)| {
+                    format!("{:>width$} | {:.1}%", time, share * 100.0, width = time_width)
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        ViewFormat::Json | ViewFormat::JsonLines => Value::from(
+            curve
+                .iter()
+                .map(|&(time, share)| {
+                    json!({"time": time, "value": round(share, DEFAULT_PRECISION + 2)})
+                })
+                .collect::<Vec<_>>(),
+        )
+        .to_string(),
+    }
+}
+
+fn render_assignment(analysis: &ScheduleAnalysis, workers: usize, format: ViewFormat) -> String {
+    let assignments = analysis.assign_to_workers(workers);
+    let makespan = analysis.makespan_with_workers(workers);
+    match format {
+        ViewFormat::Text => {
+            let mut text = String::new();
+            for (worker_idx, tasks) in assignments.iter().enumerate() {
+                let timeline = tasks
+                    .iter()
+                    .map(|(task, start)| format!("{}@{}", task.as_ref(), start))
+                    .collect::<Vec<_>>();
+                writeln!(text, "worker {}: {}", worker_idx + 1, timeline.join(", ")).unwrap();
+            }
+            write!(text, "makespan: {}", makespan).unwrap();
+            text
+        }
+        ViewFormat::Json | ViewFormat::JsonLines => json!({
+            "makespan": makespan,
+            "workers": assignments
+                .iter()
+                .map(|tasks| {
+                    tasks
+                        .iter()
+                        .map(|(task, start)| json!({"task": task.as_ref(), "start": start}))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>(),
+        })
+        .to_string(),
+    }
+}
+
+fn render_efficiency(analysis: &ScheduleAnalysis, workers: usize, format: ViewFormat) -> String {
+    let efficiency = analysis.efficiency(workers);
+    match format {
+        ViewFormat::Text => format!(
+            "workers: {}\ntotal_work: {}\nmakespan: {}\nefficiency: {}",
+            workers,
+            analysis.total_work(),
+            analysis.makespan_with_workers(workers),
+            efficiency.map_or_else(
+                || String::from("n/a"),
+                |efficiency| format!("{:.*}", DEFAULT_PRECISION, efficiency)
+            )
+        ),
+        ViewFormat::Json | ViewFormat::JsonLines => json!({
+            "workers": workers,
+            "total_work": analysis.total_work(),
+            "makespan": analysis.makespan_with_workers(workers),
+            "efficiency": efficiency.map(|efficiency| round(efficiency, DEFAULT_PRECISION)),
+        })
+        .to_string(),
+    }
+}
+
+fn render_waves(analysis: &ScheduleAnalysis, format: ViewFormat) -> String {
+    let waves = analysis.execution_waves();
+    match format {
+        ViewFormat::Text => waves
+            .iter()
+            .enumerate()
+            .map(|(wave_idx, tasks)| {
+                let tasks = tasks.iter().map(|task| task.as_ref()).collect::<Vec<_>>();
+                format!("wave {}: {}", wave_idx + 1, tasks.join(", "))
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        ViewFormat::Json | ViewFormat::JsonLines => json!({
+            "waves": waves.iter().map(|tasks| labels_to_json(tasks)).collect::<Vec<_>>(),
+        })
+        .to_string(),
+    }
+}
+
+// The rollup comes from the analysis of the whole schedule, as the components don't necessarily
+// peak at the same time
+fn render_components(
+    analysis: &ScheduleAnalysis,
+    format: ViewFormat,
+    path_style: PathStyle,
+) -> Result<String, String> {
+    let components = analysis.components().map_err(|err| err.to_string())?;
+    Ok(match format {
+        ViewFormat::Text => {
+            let mut text = format!(
+                "component_count: {}\nmax_parallelism: {}\nminimum_completion_time: {}\n",
+                components.len(),
+                analysis.max_parallelism(),
+                analysis.minimum_completion_time()
+            );
+            for (component_idx, component) in components.iter().enumerate() {
+                write!(
+                    text,
+                    "\ncomponent {}:\n{}",
+                    component_idx + 1,
+                    render_text(component, path_style)
+                )
+                .unwrap();
+            }
+            text.pop(); // trailing newline
+            text
+        }
+        ViewFormat::Json => json!({
+            "component_count": components.len(),
+            "max_parallelism": analysis.max_parallelism(),
+            "minimum_completion_time": analysis.minimum_completion_time(),
+            "components": components.iter().map(to_json).collect::<Vec<_>>(),
+        })
+        .to_string(),
+        ViewFormat::JsonLines => components
+            .iter()
+            .map(|component| to_json(component).to_string())
+            .collect::<Vec<_>>()
+            .join("\n"),
+    })
+}
+
+// [0, 5, 20] -> ["0", "1-5", "6-20", ">20"]
+fn slack_bucket_names(buckets: &[TotalDuration]) -> Vec<String> {
+    let mut names = Vec::with_capacity(buckets.len() + 1);
+    let mut lower_bound = 0;
+    for &upper_bound in buckets {
+        if lower_bound == upper_bound {
+            names.push(upper_bound.to_string());
+        } else {
+            names.push(format!("{}-{}", lower_bound, upper_bound));
+        }
+        lower_bound = upper_bound + 1;
+    }
+    names.push(match buckets.last() {
+        Some(last_bound) => format!(">{}", last_bound),
+        None => String::from(">=0"),
+    });
+    names
+}
+
+fn render_metrics(analysis: &ScheduleAnalysis, precision: usize, format: ViewFormat) -> String {
+    let longest_task = analysis.longest_task();
+    let ideal_speedup = analysis.ideal_speedup();
+    match format {
+        ViewFormat::Text => {
+            let mut text = String::new();
+            writeln!(text, "task_count: {}", analysis.task_count()).unwrap();
+            if let Some((task, duration)) = longest_task {
+                writeln!(text, "longest_task: {}({})", task.as_ref(), duration).unwrap();
+            }
+            writeln!(text, "longest_chain_len: {}", analysis.longest_chain_len()).unwrap();
+            if let Some(speedup) = ideal_speedup {
+                writeln!(text, "ideal_speedup: {:.*}", precision, speedup).unwrap();
+            }
+            write!(
+                text,
+                "critical_task_count: {}",
+                analysis.critical_tasks().len()
+            )
+            .unwrap();
+            text
+        }
+        ViewFormat::Json | ViewFormat::JsonLines => json!({
+            "task_count": analysis.task_count(),
+            "longest_task": longest_task.map(|(task, duration)| json!({
+                "task": task.as_ref(),
+                "duration": duration,
+            })),
+            "longest_chain_len": analysis.longest_chain_len(),
+            "ideal_speedup": ideal_speedup.map(|speedup| round(speedup, precision)),
+            "critical_task_count": analysis.critical_tasks().len(),
+        })
+        .to_string(),
+    }
+}
+
+// JSON numbers drop trailing zeros, so only the rounding is up to the precision
+fn round(value: f64, precision: usize) -> f64 {
+    let scale = 10f64.powi(precision as i32);
+    (value * scale).round() / scale
+}
+
+fn render_sink_paths(
+    analysis: &ScheduleAnalysis,
+    format: ViewFormat,
+    path_style: PathStyle,
+) -> String {
+    let mut paths = analysis
+        .longest_path_per_sink()
+        .into_iter()
+        .collect::<Vec<_>>();
+    paths.sort_unstable_by(|(sink1, (duration1, _)), (sink2, (duration2, _))| {
+        duration2.cmp(duration1).then(sink1.cmp(sink2))
+    });
+    match format {
+        ViewFormat::Text => {
+            let mut text = format!("sink_count: {}\nsink_paths:\n", paths.len());
+            for (path_idx, (_, (duration, path))) in paths.iter().enumerate() {
+                writeln!(text, "{}) duration: {}", path_idx + 1, duration).unwrap();
+                serialize_path_text(analysis, path, path_style, &mut text).unwrap();
+            }
+            text.pop(); // trailing newline
+            text
+        }
+        ViewFormat::Json => json!({
+            "sink_count": paths.len(),
+            "sink_paths": paths
+                .iter()
+                .map(|(_, (_, path))| path_to_json(analysis, path))
+                .collect::<Vec<_>>(),
+        })
+        .to_string(),
+        ViewFormat::JsonLines => paths
+            .iter()
+            .map(|(_, (_, path))| path_to_json(analysis, path).to_string())
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+fn render_near_critical_paths(
+    analysis: &ScheduleAnalysis,
+    within: TotalDuration,
+    format: ViewFormat,
+    path_style: PathStyle,
+) -> String {
+    let paths = analysis.near_critical_paths(within);
+    match format {
+        ViewFormat::Text => {
+            let mut text = format!(
+                "near_critical_path_count: {}\nnear_critical_paths:\n",
+                paths.len()
+            );
+            for (path_idx, path) in paths.iter().enumerate() {
+                writeln!(
+                    text,
+                    "{}) duration: {}",
+                    path_idx + 1,
+                    analysis.path_duration(path)
+                )
+                .unwrap();
+                serialize_path_text(analysis, path, path_style, &mut text).unwrap();
+            }
+            text.pop(); // trailing newline
+            text
+        }
+        ViewFormat::Json => json!({
+            "within": within,
+            "near_critical_path_count": paths.len(),
+            "near_critical_paths": paths
+                .iter()
+                .map(|path| path_to_json(analysis, path))
+                .collect::<Vec<_>>(),
+        })
+        .to_string(),
+        ViewFormat::JsonLines => paths
+            .iter()
+            .map(|path| path_to_json(analysis, path).to_string())
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+fn path_to_json(analysis: &ScheduleAnalysis, path: &[TaskLabel]) -> Value {
+    json!({
+        "length": path.len(),
+        "duration": analysis.path_duration(path),
+        "path": labels_to_json(path),
+    })
+}
+
+/// Writes the path out the way the analysis is rendered in text: elided beyond the maximum depth
+/// of the style, with the duration of each task if the style asks for it, and wrapped like
+/// `serialize_path`
+pub fn serialize_path_text(
+    analysis: &ScheduleAnalysis,
+    path: &[TaskLabel],
+    path_style: PathStyle,
+    buffer: &mut dyn Write,
+) -> std::fmt::Result {
+    let path = elide_path(path, path_style.max_depth);
+    if path_style.durations {
+        serialize_path_with_durations(
+            &path,
+            analysis.task_durations(),
+            buffer,
+            "->",
+            TaskLabel::MAX_LEN,
+        )
+    } else {
+        serialize_path(&path, buffer, "->", TaskLabel::MAX_LEN)
+    }
+}
+
+fn render_text(analysis: &ScheduleAnalysis, path_style: PathStyle) -> String {
+    let mut text = String::new();
+    analysis
+        .write_text(&mut text, &|path, buffer| {
+            serialize_path_text(analysis, path, path_style, buffer)
+        })
+        .unwrap();
+    text
+}
+
+/// Fails when the schedule cannot be placed on a calendar
+pub fn render(
+    analysis: &ScheduleAnalysis,
+    format: Format,
+    path_style: PathStyle,
+) -> Result<String, String> {
+    Ok(match format {
+        Format::Text => render_text(analysis, path_style),
+        Format::Json => to_json(analysis).to_string(),
+        Format::JsonLines => to_json_lines(analysis),
+        Format::Ics(settings) => calendar::render_calendar(analysis, settings)?,
+        Format::Adjacency => render_adjacency(analysis),
+    })
+}
+
+// "A: B C", where B and C depend on A. Tasks no other task depends on end with the colon.
+fn render_adjacency(analysis: &ScheduleAnalysis) -> String {
+    let mut tasks = analysis.topological_order().clone();
+    tasks.sort_unstable();
+    tasks
+        .iter()
+        .map(|&task| {
+            let mut next_tasks = analysis
+                .task_graph()
+                .get(&task)
+                .map_or(&[][..], |next_tasks| &next_tasks[..])
+                .iter()
+                .map(|next_task| next_task.as_ref())
+                .collect::<Vec<_>>();
+            next_tasks.sort_unstable();
+            let mut line = format!("{}:", task.as_ref());
+            for next_task in next_tasks {
+                line.push(' ');
+                line.push_str(next_task);
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn to_json(analysis: &ScheduleAnalysis) -> Value {
+    let mut value = json!({
+        "task_count": analysis.task_count(),
+        "max_parallelism": analysis.max_parallelism(),
+        "minimum_completion_time": analysis.minimum_completion_time(),
+        "critical_path_count": analysis.critical_path_count(),
+        "critical_paths": analysis
+            .critical_paths()
+            .iter()
+            .map(|path| labels_to_json(path))
+            .collect::<Vec<_>>(),
+        "task_degrees": analysis
+            .topological_order()
+            .iter()
+            .map(|&task| {
+                (
+                    String::from(task.as_ref()),
+                    json!({
+                        "in_degree": analysis.in_degree(task),
+                        "out_degree": analysis.out_degree(task),
+                    }),
+                )
+            })
+            .collect::<serde_json::Map<_, _>>(),
+    });
+    // only schedules describing their tasks have descriptions
+    if !analysis.task_descriptions().is_empty() {
+        value["task_descriptions"] = analysis
+            .task_descriptions()
+            .iter()
+            .map(|(task, &description)| (String::from(task.as_ref()), Value::from(description)))
+            .collect();
+    }
+    let milestones = analysis.milestones();
+    if !milestones.is_empty() {
+        value["milestones"] = labels_to_json(&milestones);
+    }
+    value
+}
+
+// Each line is a standalone JSON document, so consumers can process the paths as a stream
+fn to_json_lines(analysis: &ScheduleAnalysis) -> String {
+    analysis
+        .critical_paths()
+        .iter()
+        .enumerate()
+        .map(|(path_idx, path)| {
+            json!({
+                "index": path_idx + 1,
+                "length": path.len(),
+                "duration": analysis.minimum_completion_time(),
+                "path": labels_to_json(path),
+            })
+            .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn render_diff(diff: &ScheduleDiff, format: ViewFormat) -> String {
+    match format {
+        ViewFormat::Text => diff.to_string(),
+        ViewFormat::Json | ViewFormat::JsonLines => json!({
+            "minimum_completion_time": {
+                "before": diff.minimum_completion_time.0,
+                "after": diff.minimum_completion_time.1,
+                "delta": diff.minimum_completion_time_delta(),
+            },
+            "max_parallelism": {
+                "before": diff.max_parallelism.0,
+                "after": diff.max_parallelism.1,
+                "delta": diff.max_parallelism_delta(),
+            },
+            "added_tasks": labels_to_json(&diff.added_tasks),
+            "removed_tasks": labels_to_json(&diff.removed_tasks),
+            "changed_durations": diff
+                .changed_durations
+                .iter()
+                .map(|(task, before, after)| json!({
+                    "task": task.as_ref(),
+                    "before": before,
+                    "after": after,
+                }))
+                .collect::<Vec<_>>(),
+            "added_critical_paths": diff
+                .added_critical_paths
+                .iter()
+                .map(|path| labels_to_json(path))
+                .collect::<Vec<_>>(),
+            "removed_critical_paths": diff
+                .removed_critical_paths
+                .iter()
+                .map(|path| labels_to_json(path))
+                .collect::<Vec<_>>(),
+        })
+        .to_string(),
+    }
+}
+
+/// A bar per range of durations, counting the tasks whose durations fall into it
+pub fn render_duration_histogram(
+    buckets: &[((Duration, Duration), usize)],
+    format: ViewFormat,
+) -> String {
+    let bucket_names = buckets
+        .iter()
+        .map(|&((lower_bound, upper_bound), _)| {
+            if lower_bound == upper_bound {
+                lower_bound.to_string()
+            } else {
+                format!("{}-{}", lower_bound, upper_bound)
+            }
+        })
+        .collect::<Vec<_>>();
+    match format {
+        ViewFormat::Text => {
+            let name_width = bucket_names
+                .iter()
+                .map(|name| name.chars().count())
+                .max()
+                .unwrap_or(0);
+            bucket_names
+                .iter()
+                .zip(buckets.iter())
+                .map(|(name, &(_, count))| {
+                    format!(
+                        "{:>width$} | {} {}",
+                        name,
+                        "#".repeat(count),
+                        count,
+                        width = name_width
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        ViewFormat::Json | ViewFormat::JsonLines => Value::from(
+            bucket_names
+                .iter()
+                .zip(buckets.iter())
+                .map(|(name, (_, count))| json!({"duration": name, "task_count": count}))
+                .collect::<Vec<_>>(),
+        )
+        .to_string(),
+    }
+}
+
+/// "label: duration" lines, or a JSON object mapping labels to durations
+pub fn render_task_list(tasks: &[(TaskLabel, Duration)], format: ViewFormat) -> String {
+    match format {
+        ViewFormat::Text => tasks
+            .iter()
+            .map(|(task, duration)| format!("{}: {}", task.as_ref(), duration))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        ViewFormat::Json | ViewFormat::JsonLines => Value::from(
+            tasks
+                .iter()
+                .map(|(task, duration)| (String::from(task.as_ref()), Value::from(*duration)))
+                .collect::<serde_json::Map<_, _>>(),
+        )
+        .to_string(),
+    }
+}
+
+/// JSON Lines render one object per file
+pub fn render_aggregate(report: &AggregateReport, format: ViewFormat) -> String {
+    match format {
+        ViewFormat::Text => report.to_string(),
+        ViewFormat::Json => json!({
+            "files": report.files.iter().map(file_summary_to_json).collect::<Vec<_>>(),
+            "passed": report.files.len() - report.failed_count(),
+            "failed": report.failed_count(),
+        })
+        .to_string(),
+        ViewFormat::JsonLines => report
+            .files
+            .iter()
+            .map(|file| file_summary_to_json(file).to_string())
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+fn file_summary_to_json(file: &FileSummary) -> Value {
+    match &file.outcome {
+        Ok((makespan, parallelism)) => json!({
+            "file": file.file_path,
+            "passed": true,
+            "minimum_completion_time": makespan,
+            "max_parallelism": parallelism,
+        }),
+        Err(reason) => json!({
+            "file": file.file_path,
+            "passed": false,
+            "error": reason,
+        }),
+    }
+}
+
+/// The checks the analyzed schedule passes, along with how many tasks and constraints they cover
+fn render_verification(analysis: &ScheduleAnalysis, format: ViewFormat) -> String {
+    match format {
+        ViewFormat::Text => format!(
+            "task_count: {}\nordering_constraints: {}\nacyclic: yes\ncomplete: yes\n\
+             analyzed {} tasks with {} ordering constraints, all satisfiable",
+            analysis.task_count(),
+            analysis.relation_count(),
+            analysis.task_count(),
+            analysis.relation_count()
+        ),
+        ViewFormat::Json | ViewFormat::JsonLines => json!({
+            "verified": true,
+            "task_count": analysis.task_count(),
+            "ordering_constraints": analysis.relation_count(),
+            "acyclic": true,
+            "complete": true,
+        })
+        .to_string(),
+    }
+}
+
+/// Counterpart of the verification view for schedules that cannot be analyzed, listing the checks
+/// the error fails. Errors that are not about the checks, such as parse errors, fail none of
+/// them.
+pub fn render_failed_verification(err: &ProcessError, format: ViewFormat) -> String {
+    let problems = match err {
+        ProcessError::Analysis(err) => std::slice::from_ref(err),
+        ProcessError::Validation(problems) => &problems[..],
+        ProcessError::Parse(_) | ProcessError::Invalid(_) => &[],
+    };
+    let mut failed_checks = problems
+        .iter()
+        .filter_map(|problem| match problem {
+            AnalysisError::Cycle | AnalysisError::SelfDependency(_) => Some("acyclic"),
+            AnalysisError::MissingDurations(_) | AnalysisError::MissingOrders(_) => {
+                Some("complete")
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    failed_checks.sort_unstable();
+    failed_checks.dedup();
+    match format {
+        ViewFormat::Text => {
+            let mut text = String::from("verification failed");
+            for check in failed_checks {
+                write!(text, "\n{}: no", check).unwrap();
+            }
+            text
+        }
+        ViewFormat::Json | ViewFormat::JsonLines => json!({
+            "verified": false,
+            "failed_checks": failed_checks,
+        })
+        .to_string(),
+    }
+}
+
+/// `{"error": kind, "detail": payload, "message": text}`, where the detail carries the labels an
+/// analysis error is about, the location of a parsing error, or nothing
+pub fn error_to_json(err: &ProcessError) -> Value {
+    let (kind, detail) = match err {
+        ProcessError::Parse(err) => (
+            "parse",
+            json!({
+                "line": err.line(),
+                "column": err.column(),
+                "file": err.file().map(|file| file.display().to_string()),
+                "reason": err.reason(),
+            }),
+        ),
+        ProcessError::Analysis(err) => analysis_error_to_json(err),
+        ProcessError::Invalid(_) => ("invalid", json!([])),
+        ProcessError::Validation(problems) => (
+            "validation",
+            problems
+                .iter()
+                .map(|problem| {
+                    let (kind, detail) = analysis_error_to_json(problem);
+                    message_to_json(kind, detail, &problem.to_string())
+                })
+                .collect(),
+        ),
+    };
+    message_to_json(kind, detail, &err.to_string())
+}
+
+// (kind, detail) of an analysis error
+fn analysis_error_to_json(err: &AnalysisError) -> (&'static str, Value) {
+    match err {
+        AnalysisError::EmptyInput => ("empty_input", json!([])),
+        AnalysisError::MissingDurations(tasks) => ("missing_durations", labels_to_json(tasks)),
+        AnalysisError::MissingOrders(tasks) => ("missing_orders", labels_to_json(tasks)),
+        AnalysisError::Cycle => ("cycle", json!([])),
+        AnalysisError::DurationOverflow(task) => ("duration_overflow", labels_to_json(&[*task])),
+        AnalysisError::ConflictingDurations(task) => {
+            ("conflicting_durations", labels_to_json(&[*task]))
+        }
+        AnalysisError::SelfDependency(task) => ("self_dependency", labels_to_json(&[*task])),
+    }
+}
+
+/// JSON counterpart of the errors that are not about the schedule itself, such as I/O errors
+pub fn message_to_json(kind: &str, detail: Value, message: &str) -> Value {
+    json!({
+        "error": kind,
+        "detail": detail,
+        "message": message,
+    })
+}
+
+fn labels_to_json(labels: &[TaskLabel]) -> Value {
+    Value::from(
+        labels
+            .iter()
+            .map(|label| label.as_ref())
+            .collect::<Vec<_>>(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processor::{process, process_with_options, ProcessOptions};
+    use std::fs;
+
+    fn example_1_content() -> String {
+        fs::read_to_string(format!(
+            "{}/resources/test/example.tasks.in",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn format_names() {
+        assert_eq!("text".parse::<Format>(), Ok(Format::Text));
+        assert_eq!("json".parse::<Format>(), Ok(Format::Json));
+        assert_eq!("jsonl".parse::<Format>(), Ok(Format::JsonLines));
+        assert_eq!("adjacency".parse::<Format>(), Ok(Format::Adjacency));
+        assert!("xml".parse::<Format>().is_err());
+    }
+
+    #[test]
+    fn view_formats() {
+        assert_eq!(ViewFormat::from(Format::Json), ViewFormat::Json);
+        assert_eq!(ViewFormat::from(Format::JsonLines), ViewFormat::JsonLines);
+        for format in ["ics", "adjacency", "text"] {
+            assert_eq!(
+                ViewFormat::from(format.parse::<Format>().unwrap()),
+                ViewFormat::Text
+            );
+        }
+        let analysis = process("A(1)\nB(2) after [A]").unwrap();
+        assert_eq!(
+            render_view(
+                &analysis,
+                View::Waves,
+                Format::Adjacency,
+                PathStyle::default()
+            )
+            .unwrap(),
+            render_view(&analysis, View::Waves, Format::Text, PathStyle::default()).unwrap()
+        );
+    }
+
+    #[test]
+    fn adjacency_rendering() {
+        let unparsed_file_content = fs::read_to_string(format!(
+            "{}/resources/test/example2.tasks.in",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap();
+        let analysis = process(&unparsed_file_content).unwrap();
+        assert_eq!(
+            render(&analysis, Format::Adjacency, PathStyle::default()).unwrap(),
+            "H:\n\
+             I:\n\
+             J: N P 锈\n\
+             K: H\n\
+             N: H I\n\
+             P: I 锈\n\
+             Q: J T\n\
+             T: K N\n\
+             方言: 锈\n\
+             锈:"
+        );
+    }
+
+    #[test]
+    fn json_rendering() {
+        let content = example_1_content();
+        let analysis = process(&content).unwrap();
+        let value: Value =
+            serde_json::from_str(&render(&analysis, Format::Json, PathStyle::default()).unwrap())
+                .unwrap();
+        assert_eq!(value["task_count"], 8);
+        assert_eq!(value["max_parallelism"], 3);
+        assert_eq!(value["minimum_completion_time"], 4);
+        assert_eq!(value["critical_path_count"], 6);
+        assert_eq!(value["critical_paths"][0], json!(["Q", "J", "N", "H"]));
+        assert_eq!(value["task_degrees"].as_object().unwrap().len(), 8);
+        assert_eq!(
+            value["task_degrees"]["Q"],
+            json!({"in_degree": 0, "out_degree": 2})
+        );
+        assert_eq!(
+            value["task_degrees"]["N"],
+            json!({"in_degree": 2, "out_degree": 2})
+        );
+        assert_eq!(
+            value["task_degrees"]["I"],
+            json!({"in_degree": 2, "out_degree": 0})
+        );
+    }
+
+    #[test]
+    fn json_lines_rendering() {
+        let content = example_1_content();
+        let analysis = process(&content).unwrap();
+        let rendered = render(&analysis, Format::JsonLines, PathStyle::default()).unwrap();
+        let lines = rendered
+            .split('\n')
+            .map(|line| serde_json::from_str::<Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(lines.len(), 6);
+        for (line_idx, line) in lines.iter().enumerate() {
+            assert_eq!(line["index"], line_idx + 1);
+            assert_eq!(line["length"], 4);
+            assert_eq!(line["duration"], 4);
+        }
+        assert_eq!(lines[0]["path"], json!(["Q", "J", "N", "H"]));
+        assert_eq!(lines[5]["path"], json!(["Q", "T", "N", "I"]));
+    }
+
+    #[test]
+    fn topological_order_rendering() {
+        let content = example_1_content();
+        let analysis = process(&content).unwrap();
+        assert_eq!(
+            render_view(
+                &analysis,
+                View::Order { reverse: false },
+                Format::Text,
+                PathStyle::default()
+            )
+            .unwrap(),
+            "Q\nJ\nT\nK\nP\nN\nH\nI"
+        );
+        assert_eq!(
+            render_view(
+                &analysis,
+                View::Order { reverse: true },
+                Format::Text,
+                PathStyle::default()
+            )
+            .unwrap(),
+            "I\nH\nN\nP\nK\nT\nJ\nQ"
+        );
+        assert_eq!(
+            render_view(
+                &analysis,
+                View::Order { reverse: false },
+                Format::Json,
+                PathStyle::default()
+            )
+            .unwrap(),
+            r#"["Q","J","T","K","P","N","H","I"]"#
+        );
+        assert_eq!(
+            render_view(
+                &analysis,
+                View::Order { reverse: true },
+                Format::JsonLines,
+                PathStyle::default()
+            )
+            .unwrap(),
+            r#"["I","H","N","P","K","T","J","Q"]"#
+        );
+    }
+
+    #[test]
+    fn near_critical_paths_rendering() {
+        let analysis = process("A(3)\nB(1) after [A]\nC(2) after [A, B]").unwrap();
+        let view = View::NearCritical { within: 1 };
+        assert_eq!(
+            render_view(&analysis, view, Format::Text, PathStyle::default()).unwrap(),
+            "near_critical_path_count: 2\n\
+             near_critical_paths:\n\
+             1) duration: 6\n\
+             A->B->C\n\
+             2) duration: 5\n\
+             A->C"
+        );
+        let value: Value = serde_json::from_str(
+            &render_view(&analysis, view, Format::Json, PathStyle::default()).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(value["near_critical_path_count"], 2);
+        assert_eq!(value["near_critical_paths"][1]["duration"], 5);
+        assert_eq!(value["near_critical_paths"][1]["path"], json!(["A", "C"]));
+        let view = View::NearCritical { within: 0 };
+        assert_eq!(
+            render_view(&analysis, view, Format::JsonLines, PathStyle::default())
+                .unwrap()
+                .lines()
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn assignment_rendering() {
+        let unparsed_file_content = example_1_content();
+        let analysis = process(&unparsed_file_content).unwrap();
+        let view = View::Assignment { workers: 2 };
+        assert_eq!(
+            render_view(&analysis, view, Format::Text, PathStyle::default()).unwrap(),
+            "worker 1: Q@0, J@1, K@2, P@3, I@4\n\
+             worker 2: T@1, N@2, H@3\n\
+             makespan: 5"
+        );
+        let value: Value = serde_json::from_str(
+            &render_view(&analysis, view, Format::Json, PathStyle::default()).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(value["makespan"], 5);
+        assert_eq!(value["workers"][1][0], json!({"task": "T", "start": 1}));
+    }
+
+    #[test]
+    fn efficiency_rendering() {
+        let unparsed_file_content = example_1_content();
+        let analysis = process(&unparsed_file_content).unwrap();
+        let view = View::Efficiency { workers: 2 };
+        assert_eq!(
+            render_view(&analysis, view, Format::Text, PathStyle::default()).unwrap(),
+            "workers: 2\ntotal_work: 8\nmakespan: 5\nefficiency: 0.80"
+        );
+        let value: Value = serde_json::from_str(
+            &render_view(&analysis, view, Format::Json, PathStyle::default()).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(value["efficiency"], 0.8);
+
+        let analysis = process("A(0)").unwrap();
+        assert_eq!(
+            render_view(&analysis, view, Format::Text, PathStyle::default()).unwrap(),
+            "workers: 2\ntotal_work: 0\nmakespan: 0\nefficiency: n/a"
+        );
+        let value: Value = serde_json::from_str(
+            &render_view(&analysis, view, Format::Json, PathStyle::default()).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(value["efficiency"], Value::Null);
+    }
+
+    #[test]
+    fn waves_rendering() {
+        let unparsed_file_content = example_1_content();
+        let analysis = process(&unparsed_file_content).unwrap();
+        assert_eq!(
+            render_view(&analysis, View::Waves, Format::Text, PathStyle::default()).unwrap(),
+            "wave 1: Q\n\
+             wave 2: J, T\n\
+             wave 3: K, N, P\n\
+             wave 4: H, I"
+        );
+        let value: Value = serde_json::from_str(
+            &render_view(&analysis, View::Waves, Format::Json, PathStyle::default()).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(value["waves"][1], json!(["J", "T"]));
+    }
+
+    #[test]
+    fn critical_path_rendering() {
+        let unparsed_file_content = example_1_content();
+        let analysis = process(&unparsed_file_content).unwrap();
+        let view = View::CriticalPath { number: 3 };
+        assert_eq!(
+            render_view(&analysis, view, Format::Text, PathStyle::default()),
+            Ok(String::from("Q->J->P->I"))
+        );
+        let value: Value = serde_json::from_str(
+            &render_view(&analysis, view, Format::Json, PathStyle::default()).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(value["path"], json!(["Q", "J", "P", "I"]));
+        assert_eq!(value["duration"], 4);
+        let path_style = PathStyle {
+            max_depth: Some(2),
+            ..PathStyle::default()
+        };
+        assert_eq!(
+            render_view(&analysis, view, Format::Text, path_style),
+            Ok(String::from("Q->...->I"))
+        );
+        let value: Value =
+            serde_json::from_str(&render_view(&analysis, view, Format::Json, path_style).unwrap())
+                .unwrap();
+        assert_eq!(value["length"], 4);
+
+        let view = View::CriticalPath { number: 7 };
+        assert_eq!(
+            render_view(&analysis, view, Format::Text, PathStyle::default()),
+            Err(String::from(
+                "Critical path 7 does not exist, there are 6 critical paths"
+            ))
+        );
+
+        let analysis = process("A(1)\nB(2) after [A]\nC(3) after [B]").unwrap();
+        let view = View::CriticalPath { number: 1 };
+        let path_style = PathStyle {
+            durations: true,
+            ..PathStyle::default()
+        };
+        assert_eq!(
+            render_view(&analysis, view, Format::Text, path_style),
+            Ok(String::from("A(1)->B(2)->C(3)"))
+        );
+        assert!(
+            render_view(&analysis, View::Analysis, Format::Text, path_style)
+                .unwrap()
+                .ends_with("critical_paths:\n1)\nA(1)->B(2)->C(3)\n")
+        );
+        // the ellipsis of an elided path has no duration
+        let path_style = PathStyle {
+            max_depth: Some(2),
+            durations: true,
+        };
+        assert_eq!(
+            render_view(&analysis, view, Format::Text, path_style),
+            Ok(String::from("A(1)->...->C(3)"))
+        );
+    }
+
+    #[test]
+    fn components_rendering() {
+        let analysis = process("A(2)\nB(3)\nD(7)\nL(1) after [D]").unwrap();
+        assert_eq!(
+            render_view(
+                &analysis,
+                View::Components,
+                Format::Text,
+                PathStyle::default()
+            )
+            .unwrap(),
+            "component_count: 3
+max_parallelism: 3
+minimum_completion_time: 8
+
+component 1:
+task_count: 1
+max_parallelism: 1
+minimum_completion_time: 2
+critical_path_count: 1
+critical_paths:
+1)
+A
+
+component 2:
+task_count: 1
+max_parallelism: 1
+minimum_completion_time: 3
+critical_path_count: 1
+critical_paths:
+1)
+B
+
+component 3:
+task_count: 2
+max_parallelism: 1
+minimum_completion_time: 8
+critical_path_count: 1
+critical_paths:
+1)
+D->L"
+        );
+        let value: Value = serde_json::from_str(
+            &render_view(
+                &analysis,
+                View::Components,
+                Format::Json,
+                PathStyle::default(),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(value["component_count"], 3);
+        assert_eq!(
+            value["components"][2]["critical_paths"],
+            json!([["D", "L"]])
+        );
+
+        // the components write their paths out in the same style
+        let analysis = process("A(1)\nB(1) after [A]\nC(1) after [B]").unwrap();
+        let path_style = PathStyle {
+            max_depth: Some(2),
+            ..PathStyle::default()
+        };
+        assert!(
+            render_view(&analysis, View::Components, Format::Text, path_style)
+                .unwrap()
+                .ends_with("critical_paths:\n1)\nA->...->C")
+        );
+    }
+
+    #[test]
+    fn verification_rendering() {
+        let unparsed_file_content = example_1_content();
+        let analysis = process(&unparsed_file_content).unwrap();
+        assert_eq!(
+            render_view(
+                &analysis,
+                View::Verification,
+                Format::Text,
+                PathStyle::default()
+            )
+            .unwrap(),
+            "task_count: 8
+ordering_constraints: 10
+acyclic: yes
+complete: yes
+analyzed 8 tasks with 10 ordering constraints, all satisfiable"
+        );
+        let value: Value = serde_json::from_str(
+            &render_view(
+                &analysis,
+                View::Verification,
+                Format::Json,
+                PathStyle::default(),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(value["verified"], true);
+        assert_eq!(value["ordering_constraints"], 10);
+
+        let err = process("A(1) after [B]\nB(1) after [A]").unwrap_err();
+        assert_eq!(
+            render_failed_verification(&err, ViewFormat::Text),
+            "verification failed\nacyclic: no"
+        );
+        let err = process("A(1) after [B]").unwrap_err();
+        assert_eq!(
+            render_failed_verification(&err, ViewFormat::Text),
+            "verification failed\ncomplete: no"
+        );
+        let options = ProcessOptions {
+            lenient: true,
+            ..ProcessOptions::default()
+        };
+        let err = process_with_options("A(1) after [A, B]", &options).unwrap_err();
+        let value: Value =
+            serde_json::from_str(&render_failed_verification(&err, ViewFormat::Json)).unwrap();
+        assert_eq!(value["verified"], false);
+        assert_eq!(value["failed_checks"], json!(["acyclic", "complete"]));
+        let err = process("A(1) after").unwrap_err();
+        assert_eq!(
+            render_failed_verification(&err, ViewFormat::Text),
+            "verification failed"
+        );
+    }
+
+    #[test]
+    fn oneline_rendering() {
+        let unparsed_file_content = example_1_content();
+        let analysis = process(&unparsed_file_content).unwrap();
+        assert_eq!(
+            analysis.oneline(),
+            "tasks=8 parallelism=3 makespan=4 paths=6"
+        );
+        assert_eq!(
+            render_view(&analysis, View::OneLine, Format::Json, PathStyle::default()).unwrap(),
+            analysis.oneline()
+        );
+    }
+
+    #[test]
+    fn critical_path_tree_rendering() {
+        let unparsed_file_content = fs::read_to_string(format!(
+            "{}/resources/test/example.tasks.in",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap();
+        let analysis = process(&unparsed_file_content).unwrap();
+        assert_eq!(
+            render_view(
+                &analysis,
+                View::CriticalPathTree,
+                Format::Text,
+                PathStyle::default()
+            )
+            .unwrap(),
+            "Q\n\
+             \x20 J\n\
+             \x20   N\n\
+             \x20     H\n\
+             \x20     I\n\
+             \x20   P\n\
+             \x20     I\n\
+             \x20 T\n\
+             \x20   K\n\
+             \x20     H\n\
+             \x20   N\n\
+             \x20     H\n\
+             \x20     I"
+        );
+
+        // every path from the root to a leaf is a critical path
+        fn leaf_paths(node: &Value, prefix: &mut Vec<String>, paths: &mut Vec<String>) {
+            prefix.push(node["task"].as_str().unwrap().to_string());
+            let children = node["next"].as_array().unwrap();
+            if children.is_empty() {
+                paths.push(prefix.join("->"));
+            }
+            for child in children {
+                leaf_paths(child, prefix, paths);
+            }
+            prefix.pop();
+        }
+        let value: Value = serde_json::from_str(
+            &render_view(
+                &analysis,
+                View::CriticalPathTree,
+                Format::Json,
+                PathStyle::default(),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let roots = value["critical_path_tree"].as_array().unwrap();
+        assert_eq!(roots.len(), 1);
+        let mut tree_paths = Vec::new();
+        leaf_paths(&roots[0], &mut Vec::new(), &mut tree_paths);
+        let critical_paths = analysis
+            .critical_paths()
+            .iter()
+            .map(|path| {
+                path.iter()
+                    .map(|task| task.as_ref())
+                    .collect::<Vec<_>>()
+                    .join("->")
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(tree_paths, critical_paths);
+    }
+
+    #[test]
+    fn task_descriptions_rendering() {
+        let unparsed_file_content = fs::read_to_string(format!(
+            "{}/resources/test/example27.tasks.in",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap();
+        let analysis = process(&unparsed_file_content).unwrap();
+        let value: Value =
+            serde_json::from_str(&render(&analysis, Format::Json, PathStyle::default()).unwrap())
+                .unwrap();
+        assert_eq!(
+            value["task_descriptions"],
+            json!({
+                "checkout": "fetch the sources",
+                "configure": "pick the target, e.g., x86_64",
+                "compile": "compile the kernel",
+            })
+        );
+        // the terse views leave them out
+        assert!(!render(&analysis, Format::Text, PathStyle::default())
+            .unwrap()
+            .contains("kernel"));
+        assert!(!render(&analysis, Format::JsonLines, PathStyle::default())
+            .unwrap()
+            .contains("kernel"));
+
+        let analysis = process("A(1)\nB(1) after [A]").unwrap();
+        let value: Value =
+            serde_json::from_str(&render(&analysis, Format::Json, PathStyle::default()).unwrap())
+                .unwrap();
+        assert!(value.get("task_descriptions").is_none());
+        assert!(value.get("milestones").is_none());
+    }
+
+    #[test]
+    fn milestones_rendering() {
+        let analysis = process("A(1)\nB(2)\nmilestone M after [A, B]\nC(1) after [M]").unwrap();
+        let value: Value =
+            serde_json::from_str(&render(&analysis, Format::Json, PathStyle::default()).unwrap())
+                .unwrap();
+        assert_eq!(value["milestones"], json!(["M"]));
+    }
+
+    #[test]
+    fn sink_paths_rendering() {
+        let analysis =
+            process("K(1)\nL(12) after [K]\nZ(1) after [L]\nT(5) after [K]\nF(20) after [T]")
+                .unwrap();
+        assert_eq!(
+            render_view(
+                &analysis,
+                View::SinkPaths,
+                Format::Text,
+                PathStyle::default()
+            )
+            .unwrap(),
+            "sink_count: 2\n\
+             sink_paths:\n\
+             1) duration: 26\n\
+             K->T->F\n\
+             2) duration: 14\n\
+             K->L->Z"
+        );
+        let value: Value = serde_json::from_str(
+            &render_view(
+                &analysis,
+                View::SinkPaths,
+                Format::Json,
+                PathStyle::default(),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(value["sink_paths"][1]["duration"], 14);
+        assert_eq!(value["sink_paths"][1]["path"], json!(["K", "L", "Z"]));
+    }
+
+    #[test]
+    fn explanation_rendering() {
+        let analysis = process("A(1)\nB(3) after [A]\nC(3) after [A]").unwrap();
+        let explanation = "The minimum completion time of 4 is driven by the path A->B, \
+                           where B (3) dominates. It is one of 2 critical paths.";
+        assert_eq!(
+            render_view(
+                &analysis,
+                View::Explanation,
+                Format::Text,
+                PathStyle::default()
+            )
+            .unwrap(),
+            explanation
+        );
+        let value: Value = serde_json::from_str(
+            &render_view(
+                &analysis,
+                View::Explanation,
+                Format::Json,
+                PathStyle::default(),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(value["explanation"], explanation);
+    }
+
+    #[test]
+    fn metrics_rendering() {
+        let analysis = process("A(2)\nB(1) after [A]\nC(3) after [B]\nD(7)").unwrap();
+        let view = View::Metrics {
+            precision: DEFAULT_PRECISION,
+        };
+        assert_eq!(
+            render_view(&analysis, view, Format::Text, PathStyle::default()).unwrap(),
+            "task_count: 4\n\
+             longest_task: D(7)\n\
+             longest_chain_len: 3\n\
+             ideal_speedup: 1.86\n\
+             critical_task_count: 1"
+        );
+        let value: Value = serde_json::from_str(
+            &render_view(&analysis, view, Format::Json, PathStyle::default()).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(value["longest_task"], json!({"task": "D", "duration": 7}));
+        assert_eq!(value["longest_chain_len"], 3);
+        assert_eq!(value["ideal_speedup"], 1.86);
+
+        let view = View::Metrics { precision: 4 };
+        assert!(
+            render_view(&analysis, view, Format::Text, PathStyle::default())
+                .unwrap()
+                .contains("ideal_speedup: 1.8571\n")
+        );
+        let value: Value = serde_json::from_str(
+            &render_view(&analysis, view, Format::Json, PathStyle::default()).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(value["ideal_speedup"], 1.8571);
+
+        let analysis = process("A(0)").unwrap();
+        let value: Value = serde_json::from_str(
+            &render_view(&analysis, view, Format::Json, PathStyle::default()).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(value["ideal_speedup"], Value::Null);
+    }
+
+    #[test]
+    fn task_list_rendering() {
+        let tasks = vec![(TaskLabel::new("A"), 18), (TaskLabel::new("B"), 19)];
+        assert_eq!(render_task_list(&tasks, ViewFormat::Text), "A: 18\nB: 19");
+        let value: Value =
+            serde_json::from_str(&render_task_list(&tasks, ViewFormat::Json)).unwrap();
+        assert_eq!(value, json!({"A": 18, "B": 19}));
+    }
+
+    #[test]
+    fn duration_histogram_rendering() {
+        let buckets = vec![((0, 0), 1), ((1, 1), 2), ((2, 3), 0), ((4, 7), 1)];
+        assert_eq!(
+            render_duration_histogram(&buckets, ViewFormat::Text),
+            "  0 | # 1\n  1 | ## 2\n2-3 |  0\n4-7 | # 1"
+        );
+        let value: Value =
+            serde_json::from_str(&render_duration_histogram(&buckets, ViewFormat::Json)).unwrap();
+        assert_eq!(value[3], json!({"duration": "4-7", "task_count": 1}));
+    }
+
+    #[test]
+    fn aggregate_rendering() {
+        let analysis = process("A(1)\nB(2) after [A]").unwrap();
+        let report = AggregateReport {
+            files: vec![
+                FileSummary::new("a.tasks.in", Ok(&analysis)),
+                FileSummary::new("b.tasks.in", Err(String::from("Input is empty"))),
+            ],
+        };
+        let value: Value =
+            serde_json::from_str(&render_aggregate(&report, ViewFormat::Json)).unwrap();
+        assert_eq!(value["passed"], 1);
+        assert_eq!(value["failed"], 1);
+        assert_eq!(
+            value["files"][0],
+            json!({
+                "file": "a.tasks.in",
+                "passed": true,
+                "minimum_completion_time": 3,
+                "max_parallelism": 1,
+            })
+        );
+        assert_eq!(
+            value["files"][1],
+            json!({"file": "b.tasks.in", "passed": false, "error": "Input is empty"})
+        );
+        let lines = render_aggregate(&report, ViewFormat::JsonLines)
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(lines, value["files"].as_array().unwrap().clone());
+    }
+
+    #[test]
+    fn diff_rendering() {
+        let before = process("A(1)\nB(2) after [A]").unwrap();
+        let after = process("A(1)\nB(3) after [A]").unwrap();
+        let diff = ScheduleDiff::new(&before, &after);
+        let value: Value = serde_json::from_str(&render_diff(&diff, ViewFormat::Json)).unwrap();
+        assert_eq!(
+            value["minimum_completion_time"],
+            json!({"before": 3, "after": 4, "delta": 1})
+        );
+        assert_eq!(
+            value["changed_durations"],
+            json!([{"task": "B", "before": 2, "after": 3}])
+        );
+        assert_eq!(value["added_critical_paths"], json!([]));
+    }
+
+    #[test]
+    fn slack_histogram_rendering() {
+        let analysis = process("A(2)\nB(10) after [A]\nC(3) after [A]\nD(30)").unwrap();
+        assert_eq!(
+            render_view(
+                &analysis,
+                View::SlackHistogram,
+                Format::Text,
+                PathStyle::default()
+            )
+            .unwrap(),
+            ["   0 | # 1", " 1-5 |  0", "6-20 | ## 2", " >20 | # 1"].join("\n")
+        );
+        let value: Value = serde_json::from_str(
+            &render_view(
+                &analysis,
+                View::SlackHistogram,
+                Format::Json,
+                PathStyle::default(),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(value[2], json!({"slack": "6-20", "task_count": 2}));
+    }
+
+    #[test]
+    fn value_progress_rendering() {
+        let analysis = process("A(2)[0.5]\nB(10)[1.5] after [A]\nC(3) after [A]").unwrap();
+        assert_eq!(
+            render_view(
+                &analysis,
+                View::ValueProgress,
+                Format::Text,
+                PathStyle::default()
+            )
+            .unwrap(),
+            [" 0 | 0.0%", " 2 | 16.7%", " 5 | 50.0%", "12 | 100.0%"].join("\n")
+        );
+        let value: Value = serde_json::from_str(
+            &render_view(
+                &analysis,
+                View::ValueProgress,
+                Format::Json,
+                PathStyle::default(),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(value[1], json!({"time": 2, "value": 0.1667}));
+        assert_eq!(value[3], json!({"time": 12, "value": 1.0}));
+    }
+
+    #[test]
+    fn slack_bucket_naming() {
+        assert_eq!(
+            slack_bucket_names(&[0, 5, 20]),
+            vec!["0", "1-5", "6-20", ">20"]
+        );
+        assert_eq!(slack_bucket_names(&[2, 3]), vec!["0-2", "3", ">3"]);
+        assert_eq!(slack_bucket_names(&[]), vec![">=0"]);
+    }
+
+    #[test]
+    fn analysis_errors_as_json() {
+        let value = error_to_json(&process("").unwrap_err());
+        assert_eq!(value["error"], "empty_input");
+        assert_eq!(value["detail"], json!([]));
+        assert_eq!(value["message"], "Input is empty");
+
+        let value = error_to_json(&process("A(1)\nB(1) after [A, C, D]").unwrap_err());
+        assert_eq!(value["error"], "missing_durations");
+        assert_eq!(value["detail"], json!(["C", "D"]));
+
+        let value = error_to_json(&process("A(1)\nB(1) after [A]\nA(1) after [B]").unwrap_err());
+        assert_eq!(value["error"], "cycle");
+        assert_eq!(value["detail"], json!([]));
+    }
+
+    #[test]
+    fn missing_orders_as_json() {
+        let err = ProcessError::Analysis(AnalysisError::MissingOrders(vec![TaskLabel::new("A")]));
+        let value = error_to_json(&err);
+        assert_eq!(value["error"], "missing_orders");
+        assert_eq!(value["detail"], json!(["A"]));
+    }
+
+    #[test]
+    fn validation_errors_as_json() {
+        let options = ProcessOptions {
+            lenient: true,
+            ..ProcessOptions::default()
+        };
+        let err = process_with_options("A(1)\nA(2)\nB(1) after [C]", &options).unwrap_err();
+        let value = error_to_json(&err);
+        assert_eq!(value["error"], "validation");
+        assert_eq!(value["detail"][0]["error"], "conflicting_durations");
+        assert_eq!(value["detail"][0]["detail"], json!(["A"]));
+        assert_eq!(value["detail"][1]["error"], "missing_durations");
+        assert_eq!(value["detail"][1]["detail"], json!(["C"]));
+        assert_eq!(
+            value["message"],
+            "Conflicting durations for task: A\nSchedule is missing durations for: [\"C\"]"
+        );
+    }
+
+    #[test]
+    fn parsing_errors_as_json() {
+        let value = error_to_json(&process("A(1)\nB(x)").unwrap_err());
+        assert_eq!(value["error"], "parse");
+        assert_eq!(value["detail"]["line"], 2);
+        assert_eq!(value["detail"]["column"], 3);
+        assert_eq!(value["detail"]["file"], Value::Null);
+        assert_eq!(value["detail"]["reason"], Value::Null);
+
+        let value = error_to_json(&process("include \"common.tasks.in\"").unwrap_err());
+        assert_eq!(value["error"], "parse");
+        assert_eq!(
+            value["detail"]["reason"],
+            "Includes cannot be resolved without a base directory"
+        );
+    }
+
+    #[test]
+    fn invalid_schedule_errors_as_json() {
+        let value = error_to_json(&process("A(1)\nA(2)").unwrap_err());
+        assert_eq!(value["error"], "invalid");
+        assert_eq!(value["detail"], json!([]));
+        assert_eq!(value["message"], "Conflicting durations for task: A");
+    }
+
+    #[test]
+    fn json_lines_unicode_labels() {
+        let analysis = process("方言(20)").unwrap();
+        let rendered = render(&analysis, Format::JsonLines, PathStyle::default()).unwrap();
+        let line: Value = serde_json::from_str(&rendered).unwrap();
+        assert_eq!(line["path"], json!(["方言"]));
+    }
+}
@@ -0,0 +1,221 @@
+use crate::analyzer;
+use crate::analyzer::{AnalysisError, ScheduleAnalysis};
+use crate::parser::{ParsedData, ScheduleParser};
+use crate::processor;
+use crate::processor::ProcessError;
+use crate::task::{Duration, TaskLabel, TaskOrder, TaskRelation};
+use std::collections::{HashMap, HashSet};
+
+/// Tasks of a schedule along with their durations and the weights of their relations, once their
+/// declarations agree with each other. Unlike its analysis, a schedule can still be adjusted,
+/// e.g., to analyze it again with other durations.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Schedule<'a> {
+    task_orders: HashSet<TaskOrder<'a>>,
+    task_durations: HashMap<TaskLabel<'a>, Duration>,
+    edge_weights: HashMap<(TaskLabel<'a>, TaskLabel<'a>), Duration>,
+}
+
+impl<'a> Schedule<'a> {
+    /// Empty schedule to build upon with `with_task`, `with_order` and `with_weight`
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Schedule::default()
+    }
+
+    pub fn from_parts(
+        task_orders: HashSet<TaskOrder<'a>>,
+        task_durations: HashMap<TaskLabel<'a>, Duration>,
+        edge_weights: HashMap<(TaskLabel<'a>, TaskLabel<'a>), Duration>,
+    ) -> Self {
+        Schedule {
+            task_orders,
+            task_durations,
+            edge_weights,
+        }
+    }
+
+    /// Parses the content, rejecting tasks declared with different durations and relations
+    /// declared with different weights
+    #[allow(dead_code)]
+    pub fn parse(unparsed_content: &'a str) -> Result<Self, ProcessError<'a>> {
+        let data = ScheduleParser::parse_content(unparsed_content)?;
+        Ok(Schedule::from_parsed_data(&data)?)
+    }
+
+    #[allow(dead_code)]
+    pub fn from_parsed_data(data: &ParsedData<'a>) -> Result<Self, String> {
+        let (task_durations, _) = processor::establish_task_durations(data.task_durations())?;
+        Ok(Schedule {
+            task_orders: processor::establish_task_orders(data.task_orders()),
+            task_durations,
+            edge_weights: processor::establish_edge_weights(data.edge_weights())?,
+        })
+    }
+
+    /// Declares the task along with its duration, replacing the duration it might already have
+    #[allow(dead_code)]
+    pub fn with_task(mut self, task: TaskLabel<'a>, duration: Duration) -> Self {
+        self.task_orders.insert(task.node());
+        self.task_durations.insert(task, duration);
+        self
+    }
+
+    /// Orders two tasks, which still need to be declared with their durations. Analyzing a
+    /// schedule with cyclic orders results in `AnalysisError::Cycle`, while ordering a task after
+    /// itself panics.
+    #[allow(dead_code)]
+    pub fn with_order(mut self, first: TaskLabel<'a>, second: TaskLabel<'a>) -> Self {
+        self.task_orders.insert(first.arrow(second));
+        self
+    }
+
+    /// Orders two tasks along with the cost of moving from the first to the second
+    #[allow(dead_code)]
+    pub fn with_weight(
+        mut self,
+        first: TaskLabel<'a>,
+        second: TaskLabel<'a>,
+        weight: Duration,
+    ) -> Self {
+        self.task_orders.insert(first.arrow(second));
+        self.edge_weights.insert((first, second), weight);
+        self
+    }
+
+    /// Copy of the schedule where the given tasks take the given durations, e.g., to see how the
+    /// analysis changes if they slip. Tasks that are not part of the schedule are declared
+    /// without an order, so analyzing the copy rejects them.
+    #[allow(dead_code)]
+    pub fn with_duration_overrides(
+        &self,
+        overrides: impl IntoIterator<Item = (TaskLabel<'a>, Duration)>,
+    ) -> Self {
+        let mut schedule = self.clone();
+        schedule.task_durations.extend(overrides);
+        schedule
+    }
+
+    #[allow(dead_code)]
+    pub fn task_orders(&self) -> &HashSet<TaskOrder<'a>> {
+        &self.task_orders
+    }
+
+    #[allow(dead_code)]
+    pub fn task_durations(&self) -> &HashMap<TaskLabel<'a>, Duration> {
+        &self.task_durations
+    }
+
+    #[allow(dead_code)]
+    pub fn edge_weights(&self) -> &HashMap<(TaskLabel<'a>, TaskLabel<'a>), Duration> {
+        &self.edge_weights
+    }
+
+    #[allow(dead_code)]
+    pub fn task_duration(&self, task: TaskLabel) -> Option<Duration> {
+        self.task_durations.get(&task).copied()
+    }
+
+    /// See `analyzer::analyze_weighted_schedule`
+    pub fn analyze(&self) -> Result<ScheduleAnalysis<'a>, AnalysisError<'a>> {
+        analyzer::analyze_weighted_schedule(
+            &self.task_orders,
+            &self.task_durations,
+            &self.edge_weights,
+        )
+    }
+
+    /// See `analyzer::analyze_weighted_schedule_in_order`
+    pub fn analyze_in_order(
+        &self,
+        task_positions: &HashMap<TaskLabel<'a>, usize>,
+    ) -> Result<ScheduleAnalysis<'a>, AnalysisError<'a>> {
+        analyzer::analyze_weighted_schedule_in_order(
+            &self.task_orders,
+            &self.task_durations,
+            &self.edge_weights,
+            task_positions,
+        )
+    }
+
+    /// See `analyzer::analyze_weighted_schedule_with_max_paths`
+    pub fn analyze_with_max_paths(
+        &self,
+        task_positions: Option<&HashMap<TaskLabel<'a>, usize>>,
+        max_critical_paths: usize,
+    ) -> Result<ScheduleAnalysis<'a>, AnalysisError<'a>> {
+        analyzer::analyze_weighted_schedule_with_max_paths(
+            &self.task_orders,
+            &self.task_durations,
+            &self.edge_weights,
+            task_positions,
+            max_critical_paths,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::tests::paths;
+    use std::fs;
+
+    #[test]
+    fn analyzing_schedule_from_file() {
+        let unparsed_content = fs::read_to_string(format!(
+            "{}/resources/test/example.tasks.in",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap();
+        let schedule = Schedule::parse(&unparsed_content).unwrap();
+        assert_eq!(schedule.task_durations().len(), 8);
+        assert_eq!(schedule.task_duration(TaskLabel::new("Q")), Some(1));
+        let analysis = schedule.analyze().unwrap();
+        assert_eq!(analysis.minimum_completion_time(), 4);
+        assert_eq!(analysis.critical_path_count(), 6);
+
+        // analyzing again with a slower task leaves the schedule intact
+        let analysis = schedule
+            .with_duration_overrides(vec![(TaskLabel::new("P"), 3)])
+            .analyze()
+            .unwrap();
+        assert_eq!(analysis.minimum_completion_time(), 6);
+        assert_eq!(analysis.critical_paths(), &paths(&["Q->J->P->I"]));
+        assert_eq!(schedule.task_duration(TaskLabel::new("P")), Some(1));
+
+        let err = Schedule::parse("A(1)\nA(2)").unwrap_err();
+        assert_eq!(err.to_string(), "Conflicting durations for task: A");
+    }
+
+    #[test]
+    fn analyzing_built_schedule() {
+        let (a, b, c) = (
+            TaskLabel::new("A"),
+            TaskLabel::new("B"),
+            TaskLabel::new("C"),
+        );
+        let schedule = Schedule::new()
+            .with_task(a, 2)
+            .with_task(b, 3)
+            .with_task(c, 1)
+            .with_order(a, b)
+            .with_weight(a, c, 4);
+        assert_eq!(schedule.edge_weights()[&(a, c)], 4);
+        let analysis = schedule.analyze().unwrap();
+        assert_eq!(analysis.task_count(), 3);
+        assert_eq!(analysis.minimum_completion_time(), 7);
+        assert_eq!(analysis.critical_paths(), &paths(&["A->C"]));
+        let parsed_schedule = Schedule::parse("A(2)\nB(3) after [A]\nC(1)\nA -> C : 4").unwrap();
+        assert_eq!(parsed_schedule.task_durations(), schedule.task_durations());
+        assert_eq!(parsed_schedule.edge_weights(), schedule.edge_weights());
+        assert_eq!(
+            parsed_schedule.analyze().unwrap().to_string(),
+            analysis.to_string()
+        );
+
+        let err = Schedule::new().with_order(a, b).analyze().unwrap_err();
+        assert_eq!(err, AnalysisError::MissingDurations(vec![a, b]));
+        let err = schedule.with_order(b, a).analyze().unwrap_err();
+        assert_eq!(err, AnalysisError::Cycle);
+    }
+}
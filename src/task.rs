@@ -1,11 +1,53 @@
+use analyze_task_schedule_core::CheckedAdd;
+use std::borrow::Cow;
 use std::convert::TryFrom;
 use std::fmt;
 use std::fmt::Formatter;
-use std::ops::Deref;
+use std::ops::{Add, Deref, Sub};
 
 pub type Duration = u16;
 pub type TotalDuration = u32;
 
+/// Durations the analyzer can work with. Durations are summed up along paths as `Total`, which is
+/// usually wider than the duration itself, so that long paths do not overflow. Paths that do
+/// overflow nonetheless are detected with checked adds.
+pub trait ScheduleDuration: Copy + Ord + Default + fmt::Debug {
+    type Total: Copy
+        + Ord
+        + Default
+        + Add<Output = Self::Total>
+        + CheckedAdd
+        + Sub<Output = Self::Total>
+        + fmt::Debug
+        + fmt::Display;
+
+    fn total(self) -> Self::Total;
+}
+
+impl ScheduleDuration for Duration {
+    type Total = TotalDuration;
+
+    fn total(self) -> Self::Total {
+        self as TotalDuration
+    }
+}
+
+impl ScheduleDuration for u32 {
+    type Total = u64;
+
+    fn total(self) -> Self::Total {
+        self as u64
+    }
+}
+
+impl ScheduleDuration for u64 {
+    type Total = u64;
+
+    fn total(self) -> Self::Total {
+        self
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct TaskLabel<'a>(&'a str);
 impl<'a> Deref for TaskLabel<'a> {
@@ -23,7 +65,23 @@ impl<'a> TaskLabel<'a> {
     pub fn new(s: &'a str) -> Self {
         match TaskLabel::try_from(s) {
             Ok(label) => label,
-            Err(err) => panic!(err),
+            Err(err) => panic!("{}", err),
+        }
+    }
+}
+
+impl<'a> TaskLabel<'a> {
+    /// Arrows that are part of a label rather than relating two tasks, e.g., "A\->B"
+    pub const ESCAPED_ARROW: &'static str = "\\->";
+
+    /// Label without the backslashes escaping its arrows, e.g., "A->B" for "A\->B". Labels keep
+    /// their escapes otherwise, so that they can be parsed again as they are written out.
+    #[allow(dead_code)]
+    pub fn unescaped(&self) -> Cow<'a, str> {
+        if self.0.contains(TaskLabel::ESCAPED_ARROW) {
+            Cow::Owned(self.0.replace(TaskLabel::ESCAPED_ARROW, "->"))
+        } else {
+            Cow::Borrowed(self.0)
         }
     }
 }
@@ -87,7 +145,10 @@ pub trait TaskRelation<'a, R> {
 impl<'a> TaskRelation<'a, TaskLabel<'a>> for TaskLabel<'a> {
     fn arrow(self, right: TaskLabel<'a>) -> TaskOrder<'a> {
         if self == right {
-            panic!("Labels cannot have a dependency on themselves: {}", self)
+            panic!(
+                "Labels cannot have a dependency on themselves: {}",
+                self.as_ref()
+            )
         }
         TaskOrder {
             first: self,
@@ -140,7 +201,10 @@ impl<'a> TaskOrder<'a> {
 
 impl<'a> std::fmt::Display for TaskOrder<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "first: {}, second: {:?}", self.first, self.second)
+        match self.second {
+            Some(second) => write!(f, "{}->{}", self.first.as_ref(), second.as_ref()),
+            None => write!(f, "{}", self.first.as_ref()),
+        }
     }
 }
 
@@ -149,6 +213,15 @@ mod tests {
     use super::*;
     use quickcheck::TestResult;
 
+    #[test]
+    fn unescaping_labels() {
+        assert_eq!(
+            TaskLabel::new("A\\->B-transform").unescaped(),
+            "A->B-transform"
+        );
+        assert_eq!(TaskLabel::new("A-B").unescaped(), "A-B");
+    }
+
     #[quickcheck]
     fn attempt_to_form_cyclic_dependency(s: String) -> TestResult {
         TestResult::must_fail(move || {
@@ -179,6 +252,18 @@ mod tests {
         })
     }
 
+    #[test]
+    #[should_panic(expected = "Labels cannot have a dependency on themselves: Build")]
+    fn attempt_to_form_cyclic_dependency_names_bare_label() {
+        "Build".arrow("Build");
+    }
+
+    #[test]
+    fn displaying_orders() {
+        assert_eq!("Build".arrow("Test").to_string(), "Build->Test");
+        assert_eq!("Build".node().to_string(), "Build");
+    }
+
     #[test]
     #[should_panic]
     fn attempt_to_use_empty_label_both_sides_of_arrow() {